@@ -1,7 +1,9 @@
+pub mod scheduler;
 pub mod subscription;
 
 use actix::{Actor, Addr};
 
+use crate::jobs::Jobs;
 use crate::mailer::Mailer;
 use crate::models::Models;
 use crate::settings::Settings;
@@ -9,18 +11,24 @@ use crate::settings::Settings;
 #[derive(Clone)]
 pub struct Actors {
   pub subscription: Addr<subscription::Actor>,
+  // The scheduler does not handle messages, the address keeps it running.
+  #[allow(dead_code)]
+  pub scheduler: Addr<scheduler::Actor>,
 }
 
 impl Actors {
-  pub fn new(models: Models, settings: Settings, mailer: Mailer) -> Self {
+  pub fn new(models: Models, settings: Settings, mailer: Mailer, jobs: Jobs) -> Self {
     let subscription = subscription::Actor {
       models,
       settings,
       mailer,
     };
 
+    let scheduler = scheduler::Actor { jobs };
+
     Self {
       subscription: subscription.start(),
+      scheduler: scheduler.start(),
     }
   }
 }
@@ -0,0 +1,74 @@
+use actix::Actor as ActixActor;
+use actix::AsyncContext;
+use actix::Context as ActixContext;
+use std::time::Duration;
+
+use crate::jobs::poll_integrations;
+use crate::jobs::Jobs;
+
+// Interval used to queue the job that unsnoozes resources and resurfaces due
+// reviews.
+const UNSNOOZE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+// Interval used to queue the job that checks a batch of resource links.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Interval used to queue the job that computes the similarity signatures of
+// new and updated resources.
+const INDEX_INTERVAL: Duration = Duration::from_secs(10 * 60);
+// Interval used to queue the job that refreshes a batch of stale resources.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// Interval used to queue the job that polls the sources of the GitHub stars
+// and YouTube playlist integrations.
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone)]
+pub struct Actor {
+  pub jobs: Jobs,
+}
+
+impl ActixActor for Actor {
+  type Context = ActixContext<Self>;
+
+  fn started(&mut self, ctx: &mut ActixContext<Self>) {
+    info!("Scheduler actor started");
+
+    ctx.run_interval(UNSNOOZE_INTERVAL, |actor, _ctx| {
+      let jobs = actor.jobs.clone();
+      actix::spawn(async move {
+        jobs.queue("unsnooze_resources", ()).await;
+      });
+    });
+
+    ctx.run_interval(HEALTH_CHECK_INTERVAL, |actor, _ctx| {
+      let jobs = actor.jobs.clone();
+      actix::spawn(async move {
+        jobs.queue("check_resources_health", ()).await;
+      });
+    });
+
+    ctx.run_interval(INDEX_INTERVAL, |actor, _ctx| {
+      let jobs = actor.jobs.clone();
+      actix::spawn(async move {
+        jobs.queue("index_resources", ()).await;
+      });
+    });
+
+    ctx.run_interval(REFRESH_INTERVAL, |actor, _ctx| {
+      let jobs = actor.jobs.clone();
+      actix::spawn(async move {
+        jobs.queue("refresh_resources", ()).await;
+      });
+    });
+
+    ctx.run_interval(POLL_INTERVAL, |actor, _ctx| {
+      let jobs = actor.jobs.clone();
+      actix::spawn(async move {
+        let payload = poll_integrations::JobPayload { integration: None };
+        jobs.queue("poll_integrations", payload).await;
+      });
+    });
+  }
+
+  fn stopped(&mut self, _ctx: &mut ActixContext<Self>) {
+    info!("Scheduler actor stopped");
+  }
+}
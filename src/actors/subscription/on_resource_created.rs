@@ -7,6 +7,9 @@ use wither::bson::oid::ObjectId;
 use crate::actors::subscription::Actor as SubscriptionActor;
 use crate::errors::Error;
 use crate::lib::date;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
 use crate::models::resource::Resource;
 use crate::models::Model as ModelTrait;
 use crate::models::Models;
@@ -72,9 +75,9 @@ async fn on_resource_created(models: Models, resource_id: ObjectId) -> Result<()
         .get_next_resource_position(&integration.list)
         .await?;
 
-      models
-        .resource
-        .create(Resource {
+      let resource = models
+        .list
+        .create_resource(Resource {
           id: None,
           list: integration.list.clone(),
           user: integration.user.clone(),
@@ -93,9 +96,33 @@ async fn on_resource_created(models: Models, resource_id: ObjectId) -> Result<()
           length: None,
           publisher: None,
           populated_at: None,
+          refresh_attempted_at: None,
+          signature_indexed_at: None,
+          annotations_text: None,
+          estimated_reading_time: None,
+          progress: None,
+          snoozed_until: None,
+          review: None,
+          archived_at: None,
+          favicon: None,
+          kind: None,
+          media: None,
+          health: None,
+          last_checked_at: None,
+          edited_fields: resource.edited_fields.clone(),
+          suggested_tags: resource.suggested_tags.clone(),
         })
         .await?;
 
+      models
+        .activity
+        .record(Activity::new(
+          &resource,
+          Action::Create,
+          Source::Subscription,
+        ))
+        .await;
+
       Ok::<(), Error>(())
     }
   });
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::errors::Error;
+
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+  async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), Error>;
+  async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error>;
+  async fn delete(&self, key: &str) -> Result<(), Error>;
+}
+
+// Stores blobs as files in the local filesystem, keys are used as paths
+// relative to the root directory.
+pub struct LocalBlobStore {
+  root: PathBuf,
+}
+
+impl LocalBlobStore {
+  pub fn new(root: &str) -> Self {
+    Self {
+      root: PathBuf::from(root),
+    }
+  }
+
+  fn get_path(&self, key: &str) -> PathBuf {
+    self.root.join(key)
+  }
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+  async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), Error> {
+    let path = self.get_path(key);
+    if let Some(parent) = path.parent() {
+      tokio::fs::create_dir_all(parent)
+        .await
+        .map_err(Error::BlobStore)?;
+    }
+    tokio::fs::write(path, data)
+      .await
+      .map_err(Error::BlobStore)?;
+
+    Ok(())
+  }
+
+  async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, Error> {
+    match tokio::fs::read(self.get_path(key)).await {
+      Ok(data) => Ok(Some(data)),
+      Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+      Err(err) => Err(Error::BlobStore(err)),
+    }
+  }
+
+  async fn delete(&self, key: &str) -> Result<(), Error> {
+    match tokio::fs::remove_file(self.get_path(key)).await {
+      Ok(_) => Ok(()),
+      Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+      Err(err) => Err(Error::BlobStore(err)),
+    }
+  }
+}
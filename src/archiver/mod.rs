@@ -0,0 +1,286 @@
+pub mod blob_store;
+
+use ammonia::UrlRelative;
+use regex::Regex;
+use std::sync::Arc;
+use url::Url;
+use wither::bson::oid::ObjectId;
+
+use crate::errors::Error;
+use crate::lib::http;
+use crate::settings::Settings;
+use blob_store::BlobStore;
+use blob_store::LocalBlobStore;
+
+// Pages above this size are not archived.
+const MAX_PAGE_SIZE: usize = 10 * 1024 * 1024;
+// Images above this size are kept hotlinked instead of being inlined.
+const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024;
+// Total size of the inlined images of a snapshot, the following images are
+// kept hotlinked.
+const MAX_INLINED_SIZE: usize = 20 * 1024 * 1024;
+const MAX_IMAGES: usize = 100;
+
+// Creates self-contained HTML snapshots of the resource pages. Pages are
+// sanitized using an allowlist of tags and attributes, relative links are
+// resolved and images are inlined as data URIs. Only public addresses are
+// requested.
+#[derive(Clone)]
+pub struct Archiver {
+  inner: Arc<ArchiverInner>,
+}
+
+struct ArchiverInner {
+  client: reqwest::Client,
+  store: Box<dyn BlobStore>,
+  image_regex: Regex,
+  image_source_regex: Regex,
+  image_srcset_regex: Regex,
+}
+
+impl Archiver {
+  pub fn new(settings: &Settings) -> Result<Self, Error> {
+    let store: Box<dyn BlobStore> = match settings.archive.backend.as_str() {
+      "local" => Box::new(LocalBlobStore::new(settings.archive.path.as_str())),
+      backend => {
+        return Err(Error::Archive(format!(
+          "Unsupported blob store backend {}",
+          backend
+        )))
+      }
+    };
+
+    // Redirects are followed by the archiver, this way every redirect target
+    // is checked before it is requested.
+    let client = reqwest::Client::builder()
+      .user_agent("Mozilla/5.0 (compatible; Listas/1.0; +https://listas.io)")
+      .redirect(reqwest::redirect::Policy::none())
+      .build()?;
+
+    let inner = ArchiverInner {
+      client,
+      store,
+      image_regex: Regex::new(r"(?is)<img\b[^>]*>").unwrap(),
+      image_source_regex: Regex::new(
+        r#"(?is)\s(data-src|src)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#,
+      )
+      .unwrap(),
+      image_srcset_regex: Regex::new(
+        r#"(?is)\s(data-srcset|srcset)\s*=\s*(?:"[^"]*"|'[^']*'|[^\s>]+)"#,
+      )
+      .unwrap(),
+    };
+
+    Ok(Self {
+      inner: Arc::new(inner),
+    })
+  }
+
+  pub fn get_resource_key(resource_id: &ObjectId) -> String {
+    format!("resources/{}.html", resource_id)
+  }
+
+  // Stores the snapshot of the specified URL, returns false when the URL does
+  // not point to an HTML page.
+  pub async fn archive(&self, resource_id: &ObjectId, url: &Url) -> Result<bool, Error> {
+    let snapshot = match self.create_snapshot(url).await? {
+      Some(snapshot) => snapshot,
+      None => return Ok(false),
+    };
+
+    let key = Self::get_resource_key(resource_id);
+    self.inner.store.put(&key, snapshot.into_bytes()).await?;
+
+    Ok(true)
+  }
+
+  pub async fn get(&self, resource_id: &ObjectId) -> Result<Option<Vec<u8>>, Error> {
+    let key = Self::get_resource_key(resource_id);
+    self.inner.store.get(&key).await
+  }
+
+  pub async fn remove(&self, resource_id: &ObjectId) -> Result<(), Error> {
+    let key = Self::get_resource_key(resource_id);
+    self.inner.store.delete(&key).await
+  }
+
+  pub fn get_backup_key(backup_id: &ObjectId) -> String {
+    format!("backups/{}.json.gz", backup_id)
+  }
+
+  // The blob store is shared with the account backups.
+  pub async fn put_backup(&self, backup_id: &ObjectId, data: Vec<u8>) -> Result<(), Error> {
+    let key = Self::get_backup_key(backup_id);
+    self.inner.store.put(&key, data).await
+  }
+
+  pub async fn get_backup(&self, backup_id: &ObjectId) -> Result<Option<Vec<u8>>, Error> {
+    let key = Self::get_backup_key(backup_id);
+    self.inner.store.get(&key).await
+  }
+
+  pub async fn remove_backup(&self, backup_id: &ObjectId) -> Result<(), Error> {
+    let key = Self::get_backup_key(backup_id);
+    self.inner.store.delete(&key).await
+  }
+
+  // Requests the URL following its redirects. Returns None when the URL or
+  // one of its redirects points to a non public address.
+  async fn fetch(&self, url: &Url) -> Result<Option<(Url, reqwest::Response)>, Error> {
+    http::send_public(&self.inner.client, reqwest::Method::GET, url).await
+  }
+
+  async fn create_snapshot(&self, url: &Url) -> Result<Option<String>, Error> {
+    let (url, response) = match self.fetch(url).await? {
+      Some(result) => result,
+      None => return Ok(None),
+    };
+
+    if !response.status().is_success() {
+      return Ok(None);
+    }
+
+    let content_type = response
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .map(|content_type| content_type.to_owned());
+
+    let is_html = content_type
+      .as_ref()
+      .map(|content_type| content_type.contains("html"))
+      .unwrap_or(true);
+
+    if !is_html {
+      return Ok(None);
+    }
+
+    let body = match http::read_body(response, MAX_PAGE_SIZE).await? {
+      Some(body) => body,
+      None => {
+        debug!("Page {} is too large to be archived", url);
+        return Ok(None);
+      }
+    };
+
+    let html = http::decode_text(&body, content_type.as_deref());
+    let html = sanitize(&html, &url);
+    let html = self.inline_images(&html, &url).await;
+
+    let snapshot = format!(
+      r#"<!DOCTYPE html><html><head><meta charset="utf-8"></head><body>{}</body></html>"#,
+      html
+    );
+
+    Ok(Some(snapshot))
+  }
+
+  async fn inline_images(&self, html: &str, url: &Url) -> String {
+    let mut snapshot = String::with_capacity(html.len());
+    let mut last_end = 0;
+    let mut remaining_size = MAX_INLINED_SIZE;
+
+    for image in self.inner.image_regex.find_iter(html).take(MAX_IMAGES) {
+      snapshot.push_str(&html[last_end..image.start()]);
+      last_end = image.end();
+
+      let tag = image.as_str();
+      let max_size = remaining_size.min(MAX_IMAGE_SIZE);
+      let inlined = match self.get_image_url(tag, url) {
+        Some(image_url) if max_size > 0 => self.fetch_image(&image_url, max_size).await,
+        _ => None,
+      };
+
+      if let Some(ref data_uri) = inlined {
+        remaining_size = remaining_size.saturating_sub(data_uri.len());
+      }
+
+      match inlined {
+        Some(data_uri) => snapshot.push_str(&self.replace_image_source(tag, &data_uri)),
+        None => snapshot.push_str(tag),
+      }
+    }
+
+    snapshot.push_str(&html[last_end..]);
+    snapshot
+  }
+
+  // Lazy loaded images usually store the real image URL in the data-src
+  // attribute, it is preferred over the src attribute when present.
+  fn get_image_url(&self, tag: &str, url: &Url) -> Option<Url> {
+    let mut source: Option<String> = None;
+
+    for captures in self.inner.image_source_regex.captures_iter(tag) {
+      let value = captures
+        .get(2)
+        .or_else(|| captures.get(3))
+        .or_else(|| captures.get(4))
+        .map(|value| value.as_str().replace("&amp;", "&"));
+
+      let value = match value {
+        Some(value) if !value.is_empty() && !value.starts_with("data:") => value,
+        _ => continue,
+      };
+
+      let is_lazy_source = captures[1].eq_ignore_ascii_case("data-src");
+      if is_lazy_source || source.is_none() {
+        source = Some(value);
+      }
+    }
+
+    url.join(source?.as_str()).ok()
+  }
+
+  fn replace_image_source(&self, tag: &str, data_uri: &str) -> String {
+    let tag = self.inner.image_srcset_regex.replace_all(tag, "");
+    let tag = self.inner.image_source_regex.replace_all(&tag, "");
+    let tag = tag.trim_start_matches("<img").trim_start_matches("<IMG");
+
+    format!(r#"<img src="{}"{}"#, data_uri, tag)
+  }
+
+  async fn fetch_image(&self, url: &Url, max_size: usize) -> Option<String> {
+    let (_, response) = self.fetch(url).await.ok()??;
+    if !response.status().is_success() {
+      return None;
+    }
+
+    // Only the MIME type is kept, the value is written in the image source
+    // attribute.
+    let content_type = response
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .and_then(|content_type| content_type.split(';').next())
+      .map(|content_type| content_type.trim().to_lowercase())?;
+
+    let is_valid_type = content_type.starts_with("image/")
+      && content_type
+        .chars()
+        .all(|char| char.is_ascii_alphanumeric() || "/.+-".contains(char));
+
+    if !is_valid_type {
+      return None;
+    }
+
+    // Base64 encoding grows the image by a third.
+    let image = http::read_body(response, max_size / 4 * 3).await.ok()??;
+
+    Some(format!(
+      "data:{};base64,{}",
+      content_type,
+      base64::encode(&image)
+    ))
+  }
+}
+
+// Removes scripts, styles, frames, embedded objects, event handlers and
+// URLs with schemes like javascript or data. Relative URLs are resolved using
+// the page URL. Lazy loaded image sources are kept to be inlined.
+fn sanitize(html: &str, url: &Url) -> String {
+  ammonia::Builder::default()
+    .add_tag_attributes("img", &["data-src"])
+    .url_relative(UrlRelative::RewriteWithBase(url.clone()))
+    .clean(html)
+    .to_string()
+}
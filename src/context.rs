@@ -1,4 +1,5 @@
 use crate::actors::Actors;
+use crate::archiver::Archiver;
 use crate::database::Database;
 use crate::jobs::Jobs;
 use crate::mailer::Mailer;
@@ -17,4 +18,5 @@ pub struct Context {
   pub models: Models,
   pub traer: Traer,
   pub jobs: Jobs,
+  pub archiver: Archiver,
 }
@@ -10,6 +10,8 @@ use wither::bson;
 use wither::mongodb::error::CommandError as MongoCommandError;
 use wither::mongodb::error::Error as MongoError;
 use wither::mongodb::error::ErrorKind as MongoErrorKind;
+use wither::mongodb::error::WriteError as MongoWriteError;
+use wither::mongodb::error::WriteFailure as MongoWriteFailure;
 use wither::WitherError;
 
 use crate::mailer::MailerError;
@@ -64,9 +66,43 @@ pub enum Error {
 
   #[error("{0}")]
   ValidateModel(#[from] ValidationErrors),
+
+  #[error("Blob store error: {0}")]
+  BlobStore(std::io::Error),
+
+  #[error("Archive error: {0}")]
+  Archive(String),
+
+  #[error("Invalid backup: {0}")]
+  Backup(String),
+
+  #[error("Integration source error: {0}")]
+  IntegrationSource(String),
 }
 
+// Code returned by MongoDB when a write violates a unique index.
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
 impl Error {
+  pub fn is_duplicate_key(&self) -> bool {
+    let mongo_error = match self {
+      Error::Wither(WitherError::Mongo(mongo_error)) => mongo_error,
+      Error::Mongo(mongo_error) => mongo_error,
+      _ => return false,
+    };
+
+    matches!(
+      mongo_error.kind.as_ref(),
+      MongoErrorKind::CommandError(MongoCommandError {
+        code: DUPLICATE_KEY_CODE,
+        ..
+      }) | MongoErrorKind::WriteError(MongoWriteFailure::WriteError(MongoWriteError {
+        code: DUPLICATE_KEY_CODE,
+        ..
+      }))
+    )
+  }
+
   fn get_codes(&self) -> (StatusCode, u16) {
     match *self {
       // 4XX
@@ -83,6 +119,7 @@ impl Error {
         }
       }
       Error::ValidateModel(_) => (StatusCode::BAD_REQUEST, 4046),
+      Error::Backup(_) => (StatusCode::BAD_REQUEST, 4047),
 
       // 401
       Error::Jwt(_) => (StatusCode::UNAUTHORIZED, 4015),
@@ -99,6 +136,9 @@ impl Error {
       Error::SendEmail(_) => (StatusCode::INTERNAL_SERVER_ERROR, 5007),
       Error::BuildEmail(_) => (StatusCode::INTERNAL_SERVER_ERROR, 5008),
       Error::SerializeMongoResponse(_) => (StatusCode::INTERNAL_SERVER_ERROR, 5009),
+      Error::BlobStore(_) => (StatusCode::INTERNAL_SERVER_ERROR, 5010),
+      Error::Archive(_) => (StatusCode::INTERNAL_SERVER_ERROR, 5011),
+      Error::IntegrationSource(_) => (StatusCode::INTERNAL_SERVER_ERROR, 5012),
     }
   }
 }
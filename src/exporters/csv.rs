@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+use crate::exporters::ExportResource;
+use crate::exporters::Exporter;
+use crate::lib::date;
+use crate::models::list::List;
+
+pub struct CsvExporter;
+
+#[derive(Serialize)]
+struct Row<'a> {
+  url: &'a str,
+  title: Option<&'a str>,
+  description: Option<&'a str>,
+  // Tags separated by commas.
+  tags: String,
+  created_at: String,
+  completed_at: Option<String>,
+  notes: String,
+}
+
+impl Exporter for CsvExporter {
+  fn content_type(&self) -> &'static str {
+    "text/csv; charset=utf-8"
+  }
+
+  fn extension(&self) -> &'static str {
+    "csv"
+  }
+
+  fn export(&self, _list: &List, resources: Vec<ExportResource>) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for resource in resources.iter() {
+      let row = Row {
+        url: &resource.resource.url,
+        title: resource.resource.title.as_deref(),
+        description: resource.resource.description.as_deref(),
+        tags: resource.resource.tags.join(","),
+        created_at: date::to_rfc3339(resource.resource.created_at),
+        completed_at: resource.resource.completed_at.map(date::to_rfc3339),
+        notes: resource.get_notes_text(),
+      };
+
+      writer
+        .serialize(row)
+        .expect("Failed to write CSV export row");
+    }
+
+    let bytes = writer.into_inner().expect("Failed to flush CSV export");
+    String::from_utf8(bytes).expect("CSV export to be valid UTF-8")
+  }
+}
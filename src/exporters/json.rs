@@ -0,0 +1,38 @@
+use serde::Serialize;
+
+use crate::exporters::ExportResource;
+use crate::exporters::Exporter;
+use crate::exporters::Note;
+use crate::models::list::List;
+use crate::models::resource::PrivateResource;
+
+pub struct JsonExporter;
+
+#[derive(Serialize)]
+struct JsonResource {
+  #[serde(flatten)]
+  resource: PrivateResource,
+  notes: Vec<Note>,
+}
+
+impl Exporter for JsonExporter {
+  fn content_type(&self) -> &'static str {
+    "application/json"
+  }
+
+  fn extension(&self) -> &'static str {
+    "json"
+  }
+
+  fn export(&self, _list: &List, resources: Vec<ExportResource>) -> String {
+    let resources = resources
+      .into_iter()
+      .map(|resource| JsonResource {
+        resource: resource.resource.into(),
+        notes: resource.notes,
+      })
+      .collect::<Vec<JsonResource>>();
+
+    serde_json::to_string_pretty(&resources).expect("Failed to serialize JSON export")
+  }
+}
@@ -0,0 +1,55 @@
+use crate::exporters::ExportResource;
+use crate::exporters::Exporter;
+use crate::models::list::List;
+
+pub struct MarkdownExporter;
+
+impl Exporter for MarkdownExporter {
+  fn content_type(&self) -> &'static str {
+    "text/markdown; charset=utf-8"
+  }
+
+  fn extension(&self) -> &'static str {
+    "md"
+  }
+
+  fn export(&self, list: &List, resources: Vec<ExportResource>) -> String {
+    let mut lines = vec![format!("# {}", list.title), String::new()];
+
+    if let Some(ref description) = list.description {
+      lines.push(description.clone());
+      lines.push(String::new());
+    }
+
+    for resource in resources {
+      let checkbox = if resource.is_completed() { "x" } else { " " };
+      let tags = resource
+        .resource
+        .tags
+        .iter()
+        .map(|tag| format!(" `#{}`", tag))
+        .collect::<String>();
+
+      lines.push(format!(
+        "- [{}] [{}]({}){}",
+        checkbox,
+        escape(resource.get_title()),
+        resource.resource.url,
+        tags
+      ));
+
+      // Notes are nested in the task, indented to be part of the list item.
+      for note in resource.notes.iter() {
+        for line in note.to_text().lines() {
+          lines.push(format!("  {}", line).trim_end().to_owned());
+        }
+      }
+    }
+
+    lines.join("\n") + "\n"
+  }
+}
+
+fn escape(text: &str) -> String {
+  text.replace('[', "\\[").replace(']', "\\]")
+}
@@ -0,0 +1,159 @@
+pub mod csv;
+pub mod json;
+pub mod markdown;
+pub mod netscape;
+pub mod outline;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::mongodb::options::FindOptions;
+
+use crate::errors::Error;
+use crate::models::annotation::Annotation;
+use crate::models::list::List;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+
+// Serializes a list and its resources into an export file.
+pub trait Exporter {
+  fn content_type(&self) -> &'static str;
+  fn extension(&self) -> &'static str;
+  fn export(&self, list: &List, resources: Vec<ExportResource>) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+  // Checklist with a task for each resource.
+  Markdown,
+  Csv,
+  // Array of resources matching the private resource schema.
+  Json,
+  // Netscape bookmark file supported by browsers and bookmark services.
+  Netscape,
+  // OPML outline with a child outline for each resource.
+  Opml,
+}
+
+impl ExportFormat {
+  pub fn exporter(&self) -> Box<dyn Exporter> {
+    match self {
+      ExportFormat::Markdown => Box::new(markdown::MarkdownExporter),
+      ExportFormat::Csv => Box::new(csv::CsvExporter),
+      ExportFormat::Json => Box::new(json::JsonExporter),
+      ExportFormat::Netscape => Box::new(netscape::NetscapeExporter),
+      ExportFormat::Opml => Box::new(outline::OutlineExporter),
+    }
+  }
+}
+
+// Attachment header of the export file, named after the list slug.
+pub fn get_content_disposition(list: &List, exporter: &dyn Exporter) -> String {
+  format!(
+    "attachment; filename=\"{}.{}\"",
+    list.slug,
+    exporter.extension()
+  )
+}
+
+pub struct ExportResource {
+  pub resource: Resource,
+  // Annotations text, highlights are included as quotes.
+  pub notes: Vec<Note>,
+}
+
+impl ExportResource {
+  pub fn get_title(&self) -> &str {
+    self.resource.title.as_deref().unwrap_or(&self.resource.url)
+  }
+
+  pub fn is_completed(&self) -> bool {
+    self.resource.completed_at.is_some()
+  }
+
+  // Plain text of the notes, used by the formats without structure.
+  pub fn get_notes_text(&self) -> String {
+    self
+      .notes
+      .iter()
+      .map(|note| note.to_text())
+      .collect::<Vec<String>>()
+      .join("\n\n")
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Note {
+  pub quote: Option<String>,
+  pub note: Option<String>,
+}
+
+impl Note {
+  pub fn to_text(&self) -> String {
+    let quote = self.quote.as_ref().map(|quote| format!("> {}", quote));
+    vec![quote, self.note.clone()]
+      .into_iter()
+      .flatten()
+      .collect::<Vec<String>>()
+      .join("\n")
+  }
+}
+
+impl From<Annotation> for Note {
+  fn from(annotation: Annotation) -> Self {
+    Self {
+      quote: annotation.highlight.map(|highlight| highlight.quote),
+      note: annotation.note,
+    }
+  }
+}
+
+// Returns the list resources sorted by position. Annotations are private,
+// they are only included when the list owner exports the list.
+pub async fn find_export_resources(
+  models: &Models,
+  list_id: &ObjectId,
+  include_notes: bool,
+) -> Result<Vec<ExportResource>, Error> {
+  let options = FindOptions::builder()
+    .sort(doc! { "position": 1 })
+    .projection(doc! { "html": false, "text": false })
+    .build();
+  let resources = models
+    .resource
+    .find(doc! { "list": list_id }, Some(options))
+    .await?;
+
+  let mut notes: HashMap<ObjectId, Vec<Note>> = HashMap::new();
+  if include_notes {
+    let options = FindOptions::builder()
+      .sort(doc! { "created_at": 1 })
+      .build();
+    let annotations = models
+      .annotation
+      .find(doc! { "list": list_id }, Some(options))
+      .await?;
+
+    for annotation in annotations {
+      notes
+        .entry(annotation.resource.clone())
+        .or_default()
+        .push(annotation.into());
+    }
+  }
+
+  let resources = resources
+    .into_iter()
+    .map(|resource| {
+      let notes = notes
+        .remove(resource.id.as_ref().unwrap())
+        .unwrap_or_default();
+      ExportResource { resource, notes }
+    })
+    .collect();
+
+  Ok(resources)
+}
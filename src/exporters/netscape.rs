@@ -0,0 +1,50 @@
+use maud::{html, PreEscaped};
+
+use crate::exporters::ExportResource;
+use crate::exporters::Exporter;
+use crate::models::list::List;
+
+pub struct NetscapeExporter;
+
+impl Exporter for NetscapeExporter {
+  fn content_type(&self) -> &'static str {
+    "text/html; charset=utf-8"
+  }
+
+  fn extension(&self) -> &'static str {
+    "html"
+  }
+
+  // The list is exported as a folder. Resources that are not completed are
+  // flagged with the TOREAD attribute used by Pinboard and Delicious.
+  fn export(&self, list: &List, resources: Vec<ExportResource>) -> String {
+    let markup = html! {
+        (PreEscaped("<!DOCTYPE NETSCAPE-Bookmark-file-1>\n"))
+        meta http-equiv="Content-Type" content="text/html; charset=UTF-8";
+        title { "Bookmarks" }
+        h1 { "Bookmarks" }
+        dl {
+            dt {
+                h3 add_date=(list.created_at.timestamp()) { (list.title) }
+                dl {
+                    @for resource in &resources {
+                        dt {
+                            a
+                                href=(resource.resource.url)
+                                add_date=(resource.resource.created_at.timestamp())
+                                tags=(resource.resource.tags.join(","))
+                                toread=(if resource.is_completed() { "0" } else { "1" })
+                            { (resource.get_title()) }
+                        }
+                        @if !resource.notes.is_empty() {
+                            dd { (resource.get_notes_text()) }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    markup.into_string()
+  }
+}
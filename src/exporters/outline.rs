@@ -0,0 +1,49 @@
+use maud::{html, PreEscaped};
+
+use crate::exporters::ExportResource;
+use crate::exporters::Exporter;
+use crate::models::list::List;
+
+pub struct OutlineExporter;
+
+impl Exporter for OutlineExporter {
+  fn content_type(&self) -> &'static str {
+    "text/x-opml; charset=utf-8"
+  }
+
+  fn extension(&self) -> &'static str {
+    "opml"
+  }
+
+  // Resources are outlines of the list outline, notes are nested outlines.
+  // Completion uses the _complete attribute supported by outliners.
+  fn export(&self, list: &List, resources: Vec<ExportResource>) -> String {
+    let markup = html! {
+        (PreEscaped(r#"<?xml version="1.0" encoding="UTF-8"?>"#))
+        opml version="2.0" {
+            head {
+                title { (list.title) }
+            }
+            body {
+                outline text=(list.title) {
+                    @for resource in &resources {
+                        outline
+                            type="link"
+                            text=(resource.get_title())
+                            url=(resource.resource.url)
+                            category=(resource.resource.tags.join(","))
+                            _complete=(resource.is_completed())
+                        {
+                            @for note in &resource.notes {
+                                outline text=(note.to_text()) {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    markup.into_string()
+  }
+}
@@ -0,0 +1,128 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+use url::Url;
+
+use crate::errors::Error;
+use crate::lib::http;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+  Head,
+  Get,
+}
+
+#[derive(Debug, Clone)]
+pub struct HealthResponse {
+  pub status: u16,
+  // Location header of redirect responses.
+  pub location: Option<String>,
+}
+
+// HTTP client used by the health checker, abstracted to be able to mock the
+// responses.
+#[async_trait]
+pub trait HealthClient: Send + Sync {
+  // Sends a single request without following redirects. Network errors and
+  // URLs pointing to non public addresses are returned as a description of
+  // the error.
+  async fn fetch(&self, method: Method, url: &Url) -> Result<HealthResponse, String>;
+
+  // Returns the closest Wayback Machine snapshot of the URL.
+  async fn get_wayback_url(&self, url: &Url) -> Result<Option<String>, Error>;
+}
+
+#[derive(Debug, Deserialize)]
+struct WaybackResponse {
+  archived_snapshots: WaybackSnapshots,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaybackSnapshots {
+  closest: Option<WaybackSnapshot>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaybackSnapshot {
+  available: bool,
+  url: String,
+}
+
+pub struct ReqwestHealthClient {
+  client: reqwest::Client,
+  wayback_client: reqwest::Client,
+}
+
+impl ReqwestHealthClient {
+  pub fn new() -> Self {
+    let client = reqwest::Client::builder()
+      .user_agent("Mozilla/5.0 (compatible; Listas/1.0; +https://listas.io)")
+      .redirect(reqwest::redirect::Policy::none())
+      .timeout(Duration::from_secs(15))
+      .build()
+      .expect("Failed to build health checker HTTP client");
+
+    let wayback_client = reqwest::Client::builder()
+      .timeout(Duration::from_secs(15))
+      .build()
+      .expect("Failed to build Wayback Machine HTTP client");
+
+    Self {
+      client,
+      wayback_client,
+    }
+  }
+}
+
+impl Default for ReqwestHealthClient {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[async_trait]
+impl HealthClient for ReqwestHealthClient {
+  async fn fetch(&self, method: Method, url: &Url) -> Result<HealthResponse, String> {
+    // The checker requests every redirect target using this method, this way
+    // each of them is checked before it is requested.
+    if !http::is_public_url(url).await {
+      return Err(format!("{} does not point to a public address", url));
+    }
+
+    let request = match method {
+      Method::Head => self.client.head(url.as_str()),
+      Method::Get => self.client.get(url.as_str()),
+    };
+
+    let res = request.send().await.map_err(|err| err.to_string())?;
+    let location = res
+      .headers()
+      .get(reqwest::header::LOCATION)
+      .and_then(|location| location.to_str().ok())
+      .map(|location| location.to_string());
+
+    Ok(HealthResponse {
+      status: res.status().as_u16(),
+      location,
+    })
+  }
+
+  async fn get_wayback_url(&self, url: &Url) -> Result<Option<String>, Error> {
+    let res = self
+      .wayback_client
+      .get("https://archive.org/wayback/available")
+      .query(&[("url", url.as_str())])
+      .send()
+      .await?
+      .json::<WaybackResponse>()
+      .await?;
+
+    let snapshot = res
+      .archived_snapshots
+      .closest
+      .filter(|snapshot| snapshot.available)
+      .map(|snapshot| snapshot.url);
+
+    Ok(snapshot)
+  }
+}
@@ -0,0 +1,308 @@
+pub mod client;
+
+use std::sync::Arc;
+use url::Url;
+
+use crate::models::resource::LinkHealth;
+use crate::models::resource::Resource;
+use crate::settings::Settings;
+use client::HealthClient;
+use client::Method;
+
+const MAX_REDIRECTS: usize = 10;
+// Links are replaced with a Wayback Machine snapshot once they are not found
+// this amount of consecutive checks. Network and server errors are usually
+// temporary and never replace a link.
+const REPLACE_AFTER_NOT_FOUND_COUNT: i32 = 3;
+
+// Checks whether the resource URLs are still reachable.
+#[derive(Clone)]
+pub struct HealthChecker {
+  client: Arc<dyn HealthClient>,
+  // Resources are checked again once this amount of days passed since the
+  // last check.
+  pub recheck_after_days: i64,
+}
+
+#[derive(Debug)]
+pub struct ResourceHealth {
+  pub health: LinkHealth,
+  // Wayback Machine snapshot replacing the resource URL.
+  pub replacement_url: Option<String>,
+}
+
+impl HealthChecker {
+  pub fn new(settings: &Settings, client: Arc<dyn HealthClient>) -> Self {
+    Self {
+      client,
+      recheck_after_days: settings.health.recheck_after_days,
+    }
+  }
+
+  // Checks the resource URL, links that stay not found are replaced when
+  // the resource list opted in.
+  pub async fn check_resource(&self, resource: &Resource, replace_broken: bool) -> ResourceHealth {
+    let url = resource.get_url();
+    let previous = resource.health.as_ref();
+    let mut health = self.check(&url).await;
+
+    health.original_url = previous.and_then(|previous| previous.original_url.clone());
+    health.not_found_count = match health.is_not_found() {
+      true => {
+        previous
+          .map(|previous| previous.not_found_count)
+          .unwrap_or(0)
+          + 1
+      }
+      false => 0,
+    };
+
+    let mut replacement_url = None;
+    if replace_broken && health.not_found_count >= REPLACE_AFTER_NOT_FOUND_COUNT {
+      match self.client.get_wayback_url(&url).await {
+        Ok(Some(wayback_url)) => {
+          health.original_url = Some(resource.url.clone());
+          replacement_url = Some(wayback_url);
+        }
+        Ok(None) => (),
+        Err(err) => error!(
+          "Failed to get Wayback Machine snapshot of {}. Error {}",
+          url, err
+        ),
+      }
+    }
+
+    ResourceHealth {
+      health,
+      replacement_url,
+    }
+  }
+
+  pub async fn check(&self, url: &Url) -> LinkHealth {
+    let mut current = url.clone();
+    let mut redirects = vec![];
+
+    loop {
+      let res = match self.fetch(&current).await {
+        Ok(res) => res,
+        Err(err) => return LinkHealth::broken(None, redirects, Some(err)),
+      };
+
+      let is_redirect = (300..400).contains(&res.status);
+      let location = res
+        .location
+        .as_deref()
+        .and_then(|location| current.join(location).ok());
+
+      match location {
+        Some(location) if is_redirect => {
+          if redirects.len() >= MAX_REDIRECTS {
+            let error = "Too many redirects".to_string();
+            return LinkHealth::broken(Some(res.status), redirects, Some(error));
+          }
+          redirects.push(location.to_string());
+          current = location;
+        }
+        _ => {
+          let status = Some(res.status as i32);
+          return LinkHealth {
+            status,
+            redirects,
+            is_broken: is_broken_status(res.status),
+            error: None,
+            original_url: None,
+            not_found_count: 0,
+          };
+        }
+      }
+    }
+  }
+
+  // Some servers do not implement HEAD requests properly, GET is used as a
+  // fallback.
+  async fn fetch(&self, url: &Url) -> Result<client::HealthResponse, String> {
+    let res = self.client.fetch(Method::Head, url).await;
+    match res {
+      Ok(res) if ![400, 403, 405, 501].contains(&res.status) => Ok(res),
+      _ => self.client.fetch(Method::Get, url).await,
+    }
+  }
+}
+
+fn is_broken_status(status: u16) -> bool {
+  matches!(status, 404 | 410 | 451) || status >= 500
+}
+
+#[cfg(test)]
+mod tests {
+  use async_trait::async_trait;
+  use futures::executor::block_on;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+  use wither::bson::oid::ObjectId;
+
+  use super::*;
+  use crate::errors::Error;
+  use crate::lib::date;
+  use client::HealthResponse;
+
+  const WAYBACK_URL: &str = "https://web.archive.org/web/2021/https://example.com/";
+
+  struct StubHealthClient {
+    status: Result<u16, String>,
+    wayback_url: Result<Option<String>, String>,
+    wayback_calls: AtomicUsize,
+  }
+
+  impl StubHealthClient {
+    fn new(status: Result<u16, String>) -> Self {
+      Self {
+        status,
+        wayback_url: Ok(Some(WAYBACK_URL.to_owned())),
+        wayback_calls: AtomicUsize::new(0),
+      }
+    }
+  }
+
+  #[async_trait]
+  impl HealthClient for StubHealthClient {
+    async fn fetch(&self, _method: Method, _url: &Url) -> Result<HealthResponse, String> {
+      self.status.clone().map(|status| HealthResponse {
+        status,
+        location: None,
+      })
+    }
+
+    async fn get_wayback_url(&self, _url: &Url) -> Result<Option<String>, Error> {
+      self.wayback_calls.fetch_add(1, Ordering::SeqCst);
+      self.wayback_url.clone().map_err(Error::Archive)
+    }
+  }
+
+  fn create_checker(client: Arc<StubHealthClient>) -> HealthChecker {
+    HealthChecker {
+      client,
+      recheck_after_days: 7,
+    }
+  }
+
+  fn create_resource(not_found_count: i32) -> Resource {
+    let health = LinkHealth {
+      not_found_count,
+      ..LinkHealth::broken(Some(404), vec![], None)
+    };
+
+    Resource {
+      id: Some(ObjectId::new()),
+      user: ObjectId::new(),
+      list: ObjectId::new(),
+      url: "https://example.com/".to_owned(),
+      title: None,
+      position: "i".to_owned(),
+      description: None,
+      thumbnail: None,
+      tags: vec![],
+      html: None,
+      text: None,
+      author: None,
+      length: None,
+      publisher: None,
+      created_at: date::now(),
+      updated_at: date::now(),
+      completed_at: None,
+      populated_at: None,
+      refresh_attempted_at: None,
+      signature_indexed_at: None,
+      annotations_text: None,
+      estimated_reading_time: None,
+      progress: None,
+      snoozed_until: None,
+      review: None,
+      archived_at: None,
+      favicon: None,
+      kind: None,
+      media: None,
+      health: Some(health).filter(|_| not_found_count > 0),
+      last_checked_at: None,
+      edited_fields: None,
+      suggested_tags: None,
+    }
+  }
+
+  #[test]
+  fn not_found_links_are_replaced_after_repeated_checks() {
+    let client = Arc::new(StubHealthClient::new(Ok(404)));
+    let checker = create_checker(client.clone());
+
+    let result = block_on(checker.check_resource(&create_resource(0), true));
+    assert_eq!(result.health.not_found_count, 1);
+    assert!(result.replacement_url.is_none());
+
+    let resource = create_resource(REPLACE_AFTER_NOT_FOUND_COUNT - 1);
+    let result = block_on(checker.check_resource(&resource, true));
+    assert_eq!(result.health.not_found_count, REPLACE_AFTER_NOT_FOUND_COUNT);
+    assert_eq!(result.replacement_url.as_deref(), Some(WAYBACK_URL));
+    assert_eq!(
+      result.health.original_url.as_deref(),
+      Some("https://example.com/")
+    );
+    assert_eq!(client.wayback_calls.load(Ordering::SeqCst), 1);
+  }
+
+  #[test]
+  fn server_errors_do_not_replace_links() {
+    let client = Arc::new(StubHealthClient::new(Ok(503)));
+    let checker = create_checker(client.clone());
+
+    let resource = create_resource(REPLACE_AFTER_NOT_FOUND_COUNT);
+    let result = block_on(checker.check_resource(&resource, true));
+    assert!(result.health.is_broken);
+    assert_eq!(result.health.not_found_count, 0);
+    assert!(result.replacement_url.is_none());
+    assert_eq!(client.wayback_calls.load(Ordering::SeqCst), 0);
+  }
+
+  #[test]
+  fn fetch_errors_do_not_replace_links() {
+    let client = Arc::new(StubHealthClient::new(Err("timeout".to_owned())));
+    let checker = create_checker(client.clone());
+
+    let resource = create_resource(REPLACE_AFTER_NOT_FOUND_COUNT);
+    let result = block_on(checker.check_resource(&resource, true));
+    assert!(result.health.is_broken);
+    assert_eq!(result.health.not_found_count, 0);
+    assert!(result.replacement_url.is_none());
+    assert_eq!(client.wayback_calls.load(Ordering::SeqCst), 0);
+  }
+
+  #[test]
+  fn links_are_not_replaced_unless_the_list_opted_in() {
+    let client = Arc::new(StubHealthClient::new(Ok(410)));
+    let checker = create_checker(client.clone());
+
+    let resource = create_resource(REPLACE_AFTER_NOT_FOUND_COUNT);
+    let result = block_on(checker.check_resource(&resource, false));
+    assert_eq!(
+      result.health.not_found_count,
+      REPLACE_AFTER_NOT_FOUND_COUNT + 1
+    );
+    assert!(result.replacement_url.is_none());
+    assert_eq!(client.wayback_calls.load(Ordering::SeqCst), 0);
+  }
+
+  #[test]
+  fn wayback_errors_keep_the_health_result() {
+    let client = Arc::new(StubHealthClient {
+      wayback_url: Err("unavailable".to_owned()),
+      ..StubHealthClient::new(Ok(404))
+    });
+    let checker = create_checker(client.clone());
+
+    let resource = create_resource(REPLACE_AFTER_NOT_FOUND_COUNT);
+    let result = block_on(checker.check_resource(&resource, true));
+    assert!(result.health.is_broken);
+    assert_eq!(result.health.status, Some(404));
+    assert!(result.replacement_url.is_none());
+    assert!(result.health.original_url.is_none());
+    assert_eq!(client.wayback_calls.load(Ordering::SeqCst), 1);
+  }
+}
@@ -0,0 +1,82 @@
+use regex::Regex;
+use select::document::Document;
+use select::predicate::Name;
+
+use crate::importers::is_http_url;
+use crate::importers::ImportItem;
+
+// Emails with more links are usually newsletters, only the first links are
+// saved.
+const MAX_URLS: usize = 100;
+
+// Characters that end a sentence and are rarely the last character of a URL.
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"', '*'];
+
+// Extracts the links of the plain text and HTML bodies of an email, the
+// links are deduplicated keeping the order in which they appear.
+pub fn parse(text: Option<&str>, html: Option<&str>) -> Vec<ImportItem> {
+  let mut urls = vec![];
+
+  if let Some(text) = text {
+    urls.extend(parse_text(text));
+  }
+
+  if let Some(html) = html {
+    urls.extend(parse_html(html));
+  }
+
+  let mut items: Vec<ImportItem> = vec![];
+  for url in urls {
+    if items.len() == MAX_URLS {
+      break;
+    }
+
+    if items.iter().any(|item| item.url == url) {
+      continue;
+    }
+
+    items.push(ImportItem::new(url));
+  }
+
+  items
+}
+
+fn parse_text(text: &str) -> Vec<String> {
+  let url_regex = Regex::new(r#"https?://[^\s<>"]+"#).unwrap();
+
+  url_regex
+    .find_iter(text)
+    .map(|url| trim_url(url.as_str()))
+    .filter(|url| is_http_url(url))
+    .collect()
+}
+
+fn parse_html(html: &str) -> Vec<String> {
+  Document::from(html)
+    .find(Name("a"))
+    .filter_map(|node| node.attr("href"))
+    .map(|url| url.trim().to_owned())
+    .filter(|url| is_http_url(url))
+    .collect()
+}
+
+// Removes the punctuation that follows a URL in a sentence, closing brackets
+// are kept when the URL contains the opening one, E.g. wikipedia links.
+fn trim_url(url: &str) -> String {
+  let mut url = url;
+
+  loop {
+    let trimmed = url.trim_end_matches(TRAILING_PUNCTUATION);
+    let trimmed = match trimmed.chars().last() {
+      Some(')') if !trimmed.contains('(') => &trimmed[..trimmed.len() - 1],
+      Some(']') if !trimmed.contains('[') => &trimmed[..trimmed.len() - 1],
+      Some('>') => &trimmed[..trimmed.len() - 1],
+      _ => trimmed,
+    };
+
+    if trimmed == url {
+      return url.to_owned();
+    }
+    url = trimmed;
+  }
+}
@@ -0,0 +1,118 @@
+use serde::Deserialize;
+
+use crate::importers::clean_note;
+use crate::importers::clean_text;
+use crate::importers::is_http_url;
+use crate::importers::parse_csv;
+use crate::importers::parse_timestamp;
+use crate::importers::split_tags;
+use crate::importers::ImportItem;
+use crate::importers::Importer;
+use crate::importers::NOT_WEB_URL;
+use crate::importers::UNPARSABLE_ENTRY;
+
+pub struct InstapaperImporter;
+
+impl Importer for InstapaperImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    parse_csv::<InstapaperRow>(payload)
+      .into_iter()
+      .map(|row| {
+        let row = match row {
+          Ok(row) if is_http_url(row.url.trim()) => row,
+          Ok(row) => return ImportItem::invalid(&row.url, NOT_WEB_URL),
+          Err(entry) => return ImportItem::invalid(&entry, UNPARSABLE_ENTRY),
+        };
+
+        let folder = row.folder.trim();
+        // Unread, Archive and Starred are built-in folders, any other value
+        // is a folder created by the user.
+        let (completed, folder) = match folder {
+          "" | "Unread" | "Starred" => (false, vec![]),
+          "Archive" => (true, vec![]),
+          folder => (false, vec![folder.to_owned()]),
+        };
+
+        ImportItem {
+          url: row.url.trim().to_owned(),
+          title: clean_text(&row.title),
+          tags: parse_tags(&row.tags),
+          created_at: parse_timestamp(&row.timestamp),
+          folder,
+          completed,
+          note: clean_note(&row.selection),
+          error: None,
+        }
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct InstapaperRow {
+  #[serde(rename = "URL")]
+  url: String,
+  #[serde(rename = "Title", default)]
+  title: String,
+  // Text selected by the user when the bookmark was saved.
+  #[serde(rename = "Selection", default)]
+  selection: String,
+  #[serde(rename = "Folder", default)]
+  folder: String,
+  #[serde(rename = "Timestamp", default)]
+  timestamp: String,
+  // Only present in recent exports, a JSON array E.g. ["rust","programming"].
+  #[serde(rename = "Tags", default)]
+  tags: String,
+}
+
+fn parse_tags(tags: &str) -> Vec<String> {
+  let tags = tags.trim();
+  if tags.starts_with('[') {
+    return serde_json::from_str::<Vec<String>>(tags).unwrap_or_default();
+  }
+  split_tags(tags, ',')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_the_csv_export() {
+    let items = InstapaperImporter.parse(include_str!("fixtures/instapaper.csv"));
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[0].title.as_deref(), Some("Announcing Rust 1.56.0"));
+    assert_eq!(items[0].tags, vec!["rust", "release"]);
+    assert_eq!(items[0].created_at, Some(1_635_000_000));
+    assert_eq!(
+      items[0].note.as_deref(),
+      Some("Rust 2021 is here.\nIt brings disjoint captures.")
+    );
+    assert!(items[0].folder.is_empty());
+    assert!(!items[0].completed);
+  }
+
+  #[test]
+  fn maps_the_builtin_folders() {
+    let items = InstapaperImporter.parse(include_str!("fixtures/instapaper.csv"));
+
+    assert!(items[1].completed);
+    assert!(items[1].folder.is_empty());
+    assert!(items[1].tags.is_empty());
+
+    assert!(!items[2].completed);
+    assert_eq!(items[2].folder, vec!["Architecture"]);
+    assert_eq!(items[2].tags, vec!["design", "services"]);
+  }
+
+  #[test]
+  fn non_web_urls_are_invalid() {
+    let items = InstapaperImporter.parse(include_str!("fixtures/instapaper.csv"));
+
+    assert_eq!(items[3].url, "ftp://example.com/file.txt");
+    assert_eq!(items[3].error.as_deref(), Some(NOT_WEB_URL));
+    assert!(items[..3].iter().all(|item| item.error.is_none()));
+  }
+}
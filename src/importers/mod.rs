@@ -0,0 +1,170 @@
+pub mod email;
+pub mod instapaper;
+pub mod netscape;
+pub mod pinboard;
+pub mod pocket;
+pub mod raindrop;
+pub mod urls;
+
+use serde::{Deserialize, Serialize};
+
+const NOTE_MAX_LENGTH: usize = 10000;
+
+pub const UNPARSABLE_ENTRY: &str = "Entry could not be parsed";
+pub const NOT_WEB_URL: &str = "URL is not an http or https URL";
+
+// Parses an export file into bookmarks. Entries that can not be parsed are
+// returned as invalid items so a single broken row does not fail the whole
+// import and the user can see what was not imported.
+pub trait Importer {
+  fn parse(&self, payload: &str) -> Vec<ImportItem>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+  // One URL per line.
+  #[default]
+  Urls,
+  // Netscape bookmark file exported by browsers.
+  Netscape,
+  // Pocket HTML export (ril_export.html).
+  Pocket,
+  // Pocket CSV export (part_000000.csv).
+  PocketCsv,
+  // Instapaper CSV export.
+  Instapaper,
+  // Raindrop.io CSV export.
+  Raindrop,
+  // Pinboard JSON export.
+  Pinboard,
+}
+
+impl ImportFormat {
+  pub fn importer(&self) -> Box<dyn Importer> {
+    match self {
+      ImportFormat::Urls => Box::new(urls::UrlsImporter),
+      ImportFormat::Netscape => Box::new(netscape::NetscapeImporter),
+      ImportFormat::Pocket => Box::new(pocket::PocketHtmlImporter),
+      ImportFormat::PocketCsv => Box::new(pocket::PocketCsvImporter),
+      ImportFormat::Instapaper => Box::new(instapaper::InstapaperImporter),
+      ImportFormat::Raindrop => Box::new(raindrop::RaindropImporter),
+      ImportFormat::Pinboard => Box::new(pinboard::PinboardImporter),
+    }
+  }
+}
+
+// Bookmark parsed from an import file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportItem {
+  pub url: String,
+  pub title: Option<String>,
+  pub tags: Vec<String>,
+  // Unix timestamp in seconds.
+  pub created_at: Option<i64>,
+  // Folder path of the bookmark, E.g. ["Programming", "Rust"].
+  pub folder: Vec<String>,
+  // Read or archived in the source service. Services do not export when the
+  // bookmark was read, the resource is completed at its creation date.
+  pub completed: bool,
+  // User note, imported as an annotation of the resource.
+  pub note: Option<String>,
+  // Reason the entry could not be imported, invalid items are not queued.
+  #[serde(default)]
+  pub error: Option<String>,
+}
+
+impl ImportItem {
+  pub fn new(url: String) -> Self {
+    Self {
+      url,
+      ..Default::default()
+    }
+  }
+
+  // The URL is the raw entry when it could not be parsed.
+  pub fn invalid(url: &str, reason: &str) -> Self {
+    Self {
+      url: url.trim().to_owned(),
+      error: Some(reason.to_owned()),
+      ..Default::default()
+    }
+  }
+}
+
+pub fn is_http_url(url: &str) -> bool {
+  url.starts_with("http://") || url.starts_with("https://")
+}
+
+// Collapses whitespace, returns None for blank text.
+pub fn clean_text(text: &str) -> Option<String> {
+  let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+  if text.is_empty() {
+    return None;
+  }
+  Some(text)
+}
+
+// Keeps line breaks, notes are usually multiline markdown. Notes are cut to
+// the annotation max length.
+pub fn clean_note(note: &str) -> Option<String> {
+  let note = note.trim();
+  if note.is_empty() {
+    return None;
+  }
+  Some(note.chars().take(NOTE_MAX_LENGTH).collect())
+}
+
+pub fn split_tags(tags: &str, separator: char) -> Vec<String> {
+  tags
+    .split(separator)
+    .map(|tag| tag.trim().to_owned())
+    .filter(|tag| !tag.is_empty())
+    .collect()
+}
+
+pub fn parse_rfc3339_timestamp(date: &str) -> Option<i64> {
+  let date = chrono::DateTime::parse_from_rfc3339(date.trim()).ok()?;
+  Some(date.timestamp())
+}
+
+// Some exporters use milliseconds or microseconds instead of seconds.
+pub fn normalize_timestamp(timestamp: i64) -> i64 {
+  match timestamp {
+    timestamp if timestamp > 10_000_000_000_000 => timestamp / 1_000_000,
+    timestamp if timestamp > 10_000_000_000 => timestamp / 1_000,
+    timestamp => timestamp,
+  }
+}
+
+pub fn parse_timestamp(timestamp: &str) -> Option<i64> {
+  let timestamp = timestamp.trim().parse::<i64>().ok()?;
+  Some(normalize_timestamp(timestamp))
+}
+
+// Deserializes the rows of a CSV export with headers, rows that do not match
+// the record are returned as errors with the raw row.
+pub fn parse_csv<T>(payload: &str) -> Vec<Result<T, String>>
+where
+  T: serde::de::DeserializeOwned,
+{
+  let mut reader = csv::ReaderBuilder::new()
+    .flexible(true)
+    .trim(csv::Trim::Headers)
+    .from_reader(payload.trim().as_bytes());
+
+  let headers = match reader.headers() {
+    Ok(headers) => headers.clone(),
+    Err(_) => return vec![],
+  };
+
+  reader
+    .records()
+    .map(|record| {
+      let record = record.map_err(|err| err.to_string())?;
+      record
+        .deserialize::<T>(Some(&headers))
+        .map_err(|_| record.iter().collect::<Vec<&str>>().join(","))
+    })
+    .collect()
+}
@@ -0,0 +1,135 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::Name;
+
+use crate::importers::clean_text;
+use crate::importers::is_http_url;
+use crate::importers::parse_timestamp;
+use crate::importers::split_tags;
+use crate::importers::ImportItem;
+use crate::importers::Importer;
+use crate::importers::NOT_WEB_URL;
+
+pub struct NetscapeImporter;
+
+impl Importer for NetscapeImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    parse(payload)
+  }
+}
+
+// Parses the Netscape bookmark file format exported by browsers and most
+// bookmarking services. Folders are H3 headers followed by a DL list with
+// their bookmarks.
+pub fn parse(html: &str) -> Vec<ImportItem> {
+  let document = Document::from(html);
+
+  document
+    .find(Name("a"))
+    .filter_map(|node| {
+      // Anchors without href are not bookmarks.
+      let url = node.attr("href")?.trim();
+      if !is_http_url(url) {
+        return Some(ImportItem::invalid(url, NOT_WEB_URL));
+      }
+
+      let title = clean_text(&node.text());
+      let tags = node
+        .attr("tags")
+        .map(|tags| split_tags(tags, ','))
+        .unwrap_or_default();
+      let created_at = node.attr("add_date").and_then(parse_timestamp);
+
+      Some(ImportItem {
+        url: url.to_owned(),
+        title,
+        tags,
+        created_at,
+        folder: get_folder(&node),
+        ..Default::default()
+      })
+    })
+    .collect()
+}
+
+// Returns the folder header of a DL list. Browsers nest the list inside the
+// DT element of the header, other exporters place it as a sibling.
+fn get_folder_header<'a>(list: &Node<'a>) -> Option<Node<'a>> {
+  let parent = list.parent()?;
+  if parent.name() == Some("dt") {
+    if let Some(header) = parent.children().find(|node| node.name() == Some("h3")) {
+      return Some(header);
+    }
+  }
+
+  let mut sibling = list.prev();
+  while let Some(node) = sibling {
+    match node.name() {
+      Some("h3") => return Some(node),
+      Some("dt") => return node.children().find(|node| node.name() == Some("h3")),
+      Some("dl") | Some("a") => return None,
+      _ => sibling = node.prev(),
+    }
+  }
+
+  None
+}
+
+fn get_folder(node: &Node) -> Vec<String> {
+  let mut folder = vec![];
+  let mut parent = node.parent();
+
+  while let Some(node) = parent {
+    if node.name() == Some("dl") {
+      let header = get_folder_header(&node);
+      // The bookmarks toolbar is the root folder of browser exports.
+      let is_toolbar = header
+        .as_ref()
+        .map(|header| header.attr("personal_toolbar_folder").is_some())
+        .unwrap_or(false);
+
+      match header.and_then(|header| clean_text(&header.text())) {
+        Some(name) if !is_toolbar => folder.push(name),
+        _ => (),
+      }
+    }
+    parent = node.parent();
+  }
+
+  folder.reverse();
+  folder
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_the_bookmark_file() {
+    let items = NetscapeImporter.parse(include_str!("fixtures/netscape.html"));
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[0].url, "https://www.mongodb.com/docs/manual/indexes/");
+    assert_eq!(items[0].title.as_deref(), Some("MongoDB Indexes"));
+    assert_eq!(items[0].created_at, Some(1_600_000_000));
+    assert_eq!(items[1].tags, vec!["rust", "release"]);
+    assert_eq!(items[1].created_at, Some(1_635_000_000));
+    assert_eq!(items[3].url, "place:sort=8&maxResults=10");
+    assert_eq!(items[3].error.as_deref(), Some(NOT_WEB_URL));
+  }
+
+  #[test]
+  fn nested_folders_are_kept_without_the_toolbar() {
+    let items = NetscapeImporter.parse(include_str!("fixtures/netscape.html"));
+
+    assert!(items[0].folder.is_empty());
+    assert_eq!(items[1].folder, vec!["Programming", "Rust"]);
+    assert!(items[2].folder.is_empty());
+  }
+
+  #[test]
+  fn millisecond_dates_are_normalized() {
+    let items = NetscapeImporter.parse(include_str!("fixtures/netscape.html"));
+    assert_eq!(items[2].created_at, Some(1_500_000_000));
+  }
+}
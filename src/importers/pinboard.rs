@@ -0,0 +1,109 @@
+use serde::Deserialize;
+
+use crate::importers::clean_note;
+use crate::importers::clean_text;
+use crate::importers::is_http_url;
+use crate::importers::parse_rfc3339_timestamp;
+use crate::importers::split_tags;
+use crate::importers::ImportItem;
+use crate::importers::Importer;
+use crate::importers::NOT_WEB_URL;
+use crate::importers::UNPARSABLE_ENTRY;
+
+pub struct PinboardImporter;
+
+impl Importer for PinboardImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    // Bookmarks are parsed one by one so an invalid entry does not discard
+    // the whole file, the entry is kept as an invalid item.
+    let bookmarks = match serde_json::from_str::<Vec<serde_json::Value>>(payload.trim()) {
+      Ok(bookmarks) => bookmarks,
+      Err(err) => {
+        debug!("Failed to parse Pinboard export. Error: {}", err);
+        return vec![];
+      }
+    };
+
+    bookmarks
+      .into_iter()
+      .map(|bookmark| {
+        let entry = bookmark.to_string();
+        match serde_json::from_value::<PinboardBookmark>(bookmark) {
+          Ok(bookmark) if is_http_url(bookmark.href.trim()) => ImportItem {
+            url: bookmark.href.trim().to_owned(),
+            title: clean_text(&bookmark.description),
+            tags: split_tags(&bookmark.tags, ' '),
+            created_at: parse_rfc3339_timestamp(&bookmark.time),
+            // Pinboard has no read state, "toread" only flags the bookmarks
+            // saved to read later. Bookmarks without the flag are not
+            // necessarily read.
+            completed: false,
+            note: clean_note(&bookmark.extended),
+            ..Default::default()
+          },
+          Ok(bookmark) => ImportItem::invalid(&bookmark.href, NOT_WEB_URL),
+          Err(_) => ImportItem::invalid(&entry, UNPARSABLE_ENTRY),
+        }
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct PinboardBookmark {
+  href: String,
+  // Pinboard calls the title "description" and the notes "extended".
+  #[serde(default)]
+  description: String,
+  #[serde(default)]
+  extended: String,
+  #[serde(default)]
+  time: String,
+  // Tags separated by spaces.
+  #[serde(default)]
+  tags: String,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_the_json_export() {
+    let items = PinboardImporter.parse(include_str!("fixtures/pinboard.json"));
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(
+      items[0].url,
+      "https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"
+    );
+    assert_eq!(
+      items[0].title.as_deref(),
+      Some("Announcing Rust 1.56.0 and Rust 2021")
+    );
+    assert_eq!(items[0].tags, vec!["rust", "release"]);
+    assert_eq!(items[0].created_at, Some(1_634_983_200));
+    assert_eq!(items[0].note.as_deref(), Some("Rust 2021 is here."));
+    assert_eq!(items[1].note, None);
+    assert!(items[1].tags.is_empty());
+  }
+
+  #[test]
+  fn bookmarks_without_url_are_invalid() {
+    let items = PinboardImporter.parse(include_str!("fixtures/pinboard.json"));
+
+    assert_eq!(items[2].error.as_deref(), Some(UNPARSABLE_ENTRY));
+    assert!(items[2].url.contains("Bookmark without URL"));
+  }
+
+  #[test]
+  fn bookmarks_are_not_completed() {
+    let items = PinboardImporter.parse(include_str!("fixtures/pinboard.json"));
+    assert!(items.iter().all(|item| !item.completed));
+  }
+
+  #[test]
+  fn invalid_exports_have_no_items() {
+    assert!(PinboardImporter.parse("not json").is_empty());
+  }
+}
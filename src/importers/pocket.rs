@@ -0,0 +1,157 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::Name;
+use serde::Deserialize;
+
+use crate::importers::clean_text;
+use crate::importers::is_http_url;
+use crate::importers::parse_csv;
+use crate::importers::parse_timestamp;
+use crate::importers::split_tags;
+use crate::importers::ImportItem;
+use crate::importers::Importer;
+use crate::importers::NOT_WEB_URL;
+use crate::importers::UNPARSABLE_ENTRY;
+
+pub struct PocketHtmlImporter;
+
+impl Importer for PocketHtmlImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    parse_html(payload)
+  }
+}
+
+pub struct PocketCsvImporter;
+
+impl Importer for PocketCsvImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    parse_csv::<PocketRow>(payload)
+      .into_iter()
+      .map(|row| match row {
+        Ok(row) if is_http_url(row.url.trim()) => ImportItem {
+          url: row.url.trim().to_owned(),
+          title: clean_text(&row.title),
+          tags: split_tags(&row.tags, '|'),
+          created_at: parse_timestamp(&row.time_added),
+          completed: row.status.trim() == "archive",
+          ..Default::default()
+        },
+        Ok(row) => ImportItem::invalid(&row.url, NOT_WEB_URL),
+        Err(entry) => ImportItem::invalid(&entry, UNPARSABLE_ENTRY),
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct PocketRow {
+  #[serde(default)]
+  title: String,
+  url: String,
+  #[serde(default)]
+  time_added: String,
+  // Tags separated by a pipe, E.g. "rust|programming".
+  #[serde(default)]
+  tags: String,
+  // Either "unread" or "archive".
+  #[serde(default)]
+  status: String,
+}
+
+// The HTML export has an "Unread" and a "Read Archive" H1 header, each one
+// followed by a UL list with the bookmarks.
+fn parse_html(html: &str) -> Vec<ImportItem> {
+  let document = Document::from(html);
+
+  document
+    .find(Name("a"))
+    .filter_map(|node| {
+      // Anchors without href are not bookmarks.
+      let url = node.attr("href")?.trim();
+      if !is_http_url(url) {
+        return Some(ImportItem::invalid(url, NOT_WEB_URL));
+      }
+
+      Some(ImportItem {
+        url: url.to_owned(),
+        title: clean_text(&node.text()),
+        tags: node
+          .attr("tags")
+          .map(|tags| split_tags(tags, ','))
+          .unwrap_or_default(),
+        created_at: node.attr("time_added").and_then(parse_timestamp),
+        completed: is_archived(&node),
+        ..Default::default()
+      })
+    })
+    .collect()
+}
+
+fn is_archived(node: &Node) -> bool {
+  let mut list = node.parent();
+  while let Some(parent) = list {
+    if parent.name() == Some("ul") {
+      break;
+    }
+    list = parent.parent();
+  }
+
+  let list = match list {
+    Some(list) => list,
+    None => return false,
+  };
+
+  let mut sibling = list.prev();
+  while let Some(node) = sibling {
+    if node.name() == Some("h1") {
+      return node.text().to_lowercase().contains("archive");
+    }
+    sibling = node.prev();
+  }
+
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_the_html_export() {
+    let items = PocketHtmlImporter.parse(include_str!("fixtures/pocket.html"));
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(
+      items[0].url,
+      "https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"
+    );
+    assert_eq!(
+      items[0].title.as_deref(),
+      Some("Announcing Rust 1.56.0 and Rust 2021")
+    );
+    assert_eq!(items[0].tags, vec!["rust", "release"]);
+    assert_eq!(items[0].created_at, Some(1_635_000_000));
+    assert!(!items[0].completed);
+
+    assert_eq!(items[1].url, "javascript:alert(1)");
+    assert_eq!(items[1].error.as_deref(), Some(NOT_WEB_URL));
+
+    assert_eq!(items[2].title.as_deref(), Some("MongoDB Indexes"));
+    assert!(items[2].tags.is_empty());
+    assert!(items[2].completed);
+  }
+
+  #[test]
+  fn parses_the_csv_export() {
+    let items = PocketCsvImporter.parse(include_str!("fixtures/pocket.csv"));
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].tags, vec!["rust", "release"]);
+    assert_eq!(items[0].created_at, Some(1_635_000_000));
+    assert!(!items[0].completed);
+    assert_eq!(items[1].url, "https://www.mongodb.com/docs/manual/indexes/");
+    assert!(items[1].completed);
+    assert_eq!(items[2].url, "file:///etc/passwd");
+    assert_eq!(items[2].error.as_deref(), Some(NOT_WEB_URL));
+  }
+}
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+
+use crate::importers::clean_note;
+use crate::importers::clean_text;
+use crate::importers::is_http_url;
+use crate::importers::parse_csv;
+use crate::importers::parse_rfc3339_timestamp;
+use crate::importers::split_tags;
+use crate::importers::ImportItem;
+use crate::importers::Importer;
+use crate::importers::NOT_WEB_URL;
+use crate::importers::UNPARSABLE_ENTRY;
+
+pub struct RaindropImporter;
+
+impl Importer for RaindropImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    parse_csv::<RaindropRow>(payload)
+      .into_iter()
+      .map(|row| {
+        let row = match row {
+          Ok(row) if is_http_url(row.url.trim()) => row,
+          Ok(row) => return ImportItem::invalid(&row.url, NOT_WEB_URL),
+          Err(entry) => return ImportItem::invalid(&entry, UNPARSABLE_ENTRY),
+        };
+
+        // Highlights are exported as text, they are kept next to the note.
+        let note = vec![clean_note(&row.note), clean_note(&row.highlights)]
+          .into_iter()
+          .flatten()
+          .collect::<Vec<String>>()
+          .join("\n\n");
+
+        ImportItem {
+          url: row.url.trim().to_owned(),
+          title: clean_text(&row.title),
+          tags: split_tags(&row.tags, ','),
+          created_at: parse_rfc3339_timestamp(&row.created),
+          folder: parse_folder(&row.folder),
+          // Raindrop has no read state.
+          completed: false,
+          note: clean_note(&note),
+          error: None,
+        }
+      })
+      .collect()
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct RaindropRow {
+  #[serde(default)]
+  title: String,
+  #[serde(default)]
+  note: String,
+  url: String,
+  // Collection path separated by slashes, E.g. "Programming/Rust".
+  #[serde(default)]
+  folder: String,
+  #[serde(default)]
+  tags: String,
+  #[serde(default)]
+  created: String,
+  #[serde(default)]
+  highlights: String,
+}
+
+fn parse_folder(folder: &str) -> Vec<String> {
+  // Bookmarks without collection are exported to "Unsorted".
+  if folder.trim() == "Unsorted" {
+    return vec![];
+  }
+  split_tags(folder, '/')
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_the_csv_export() {
+    let items = RaindropImporter.parse(include_str!("fixtures/raindrop.csv"));
+
+    assert_eq!(items.len(), 3);
+    assert_eq!(
+      items[0].url,
+      "https://blog.rust-lang.org/2021/10/21/Rust-1.56.0.html"
+    );
+    assert_eq!(items[0].title.as_deref(), Some("Announcing Rust 1.56.0"));
+    assert_eq!(items[0].tags, vec!["rust", "release"]);
+    assert_eq!(items[0].folder, vec!["Programming", "Rust"]);
+    assert_eq!(items[0].created_at, Some(1_634_983_200));
+    assert_eq!(
+      items[0].note.as_deref(),
+      Some("Read the edition guide\n\nRust 2021 is here")
+    );
+    assert!(!items[0].completed);
+  }
+
+  #[test]
+  fn unsorted_bookmarks_have_no_folder() {
+    let items = RaindropImporter.parse(include_str!("fixtures/raindrop.csv"));
+
+    assert!(items[1].folder.is_empty());
+    assert_eq!(items[1].note, None);
+  }
+
+  #[test]
+  fn invalid_urls_are_kept_as_invalid_items() {
+    let items = RaindropImporter.parse(include_str!("fixtures/raindrop.csv"));
+
+    assert_eq!(items[2].url, "not a url");
+    assert_eq!(items[2].error.as_deref(), Some(NOT_WEB_URL));
+  }
+}
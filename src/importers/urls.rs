@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::importers::is_http_url;
+use crate::importers::ImportItem;
+use crate::importers::Importer;
+use crate::importers::NOT_WEB_URL;
+use crate::importers::UNPARSABLE_ENTRY;
+
+pub struct UrlsImporter;
+
+impl Importer for UrlsImporter {
+  fn parse(&self, payload: &str) -> Vec<ImportItem> {
+    parse(payload)
+  }
+}
+
+#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
+struct ImportUrl {
+  #[validate(url)]
+  url: String,
+}
+
+// One URL per line, anything after the first space is ignored.
+pub fn parse(payload: &str) -> Vec<ImportItem> {
+  payload
+    .trim()
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty())
+    .map(|line| {
+      let url = line.split(' ').next().unwrap_or_default();
+      let import_url = ImportUrl {
+        url: url.to_string(),
+      };
+
+      match import_url.validate() {
+        Ok(_) if is_http_url(&import_url.url) => ImportItem::new(import_url.url),
+        Ok(_) => ImportItem::invalid(url, NOT_WEB_URL),
+        Err(_) => ImportItem::invalid(line, UNPARSABLE_ENTRY),
+      }
+    })
+    .collect::<Vec<ImportItem>>()
+}
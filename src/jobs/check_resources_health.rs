@@ -0,0 +1,152 @@
+use futures::stream::{self, StreamExt};
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use std::collections::HashSet;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+
+use crate::errors::Error;
+use crate::health::HealthChecker;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+
+const QUEUE_NAME: &str = "check_resources_health";
+// Amount of resources checked by each job run.
+const BATCH_SIZE: i64 = 500;
+const CONCURRENCY: usize = 10;
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models, health_checker: HealthChecker) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let models = models.clone();
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let health_checker = health_checker.clone();
+      let delivery = delivery.expect("Error caught in in consumer");
+      async move {
+        if let Some((_channel, delivery)) = delivery {
+          info!("Received message: {:?}", &delivery.data);
+
+          check_resources_health(models, health_checker)
+            .await
+            .expect("Check resources health succesfully");
+
+          delivery
+            .ack(BasicAckOptions::default())
+            .await
+            .expect("Failed to ack");
+        }
+      }
+    })
+    .unwrap();
+}
+
+async fn check_resources_health(
+  models: Models,
+  health_checker: HealthChecker,
+) -> Result<(), Error> {
+  let resources = models
+    .resource
+    .find_health_check_pending(health_checker.recheck_after_days, BATCH_SIZE)
+    .await?;
+
+  info!("Checking health of {} resources", resources.len());
+
+  // Lists that opted in to replace their broken links.
+  let list_ids = resources
+    .iter()
+    .map(|resource| resource.list.clone())
+    .collect::<HashSet<ObjectId>>()
+    .into_iter()
+    .collect::<Vec<ObjectId>>();
+  let replacing_lists = models
+    .list
+    .find(
+      doc! { "_id": { "$in": list_ids }, "replace_broken_links": true },
+      None,
+    )
+    .await?
+    .into_iter()
+    .filter_map(|list| list.id)
+    .collect::<HashSet<ObjectId>>();
+
+  let results = stream::iter(resources)
+    .map(|resource| {
+      let replace_broken = replacing_lists.contains(&resource.list);
+      check_resource_health(resource, replace_broken, &models, &health_checker)
+    })
+    .buffer_unordered(CONCURRENCY)
+    .collect::<Vec<Result<(), Error>>>()
+    .await;
+
+  for result in results {
+    if let Err(err) = result {
+      error!("Failed to check resource health. Error {}", err);
+    }
+  }
+
+  Ok(())
+}
+
+async fn check_resource_health(
+  resource: Resource,
+  replace_broken: bool,
+  models: &Models,
+  health_checker: &HealthChecker,
+) -> Result<(), Error> {
+  let resource_id = resource.id.clone().unwrap();
+  let result = health_checker
+    .check_resource(&resource, replace_broken)
+    .await;
+
+  if let Some(ref replacement_url) = result.replacement_url {
+    debug!(
+      "Replacing broken resource {} URL with {}",
+      &resource_id, replacement_url
+    );
+  }
+
+  let is_replaced = result.replacement_url.is_some();
+  models
+    .resource
+    .update_health(&resource_id, result.health, result.replacement_url)
+    .await?;
+
+  if is_replaced {
+    let activity = Activity::new(&resource, Action::Update, Source::System);
+    let previous = doc! { "url": &resource.url };
+    models
+      .activity
+      .record(activity.with_previous(previous))
+      .await;
+  }
+
+  Ok(())
+}
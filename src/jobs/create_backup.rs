@@ -0,0 +1,111 @@
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use serde::{Deserialize, Serialize};
+
+use crate::archiver::Archiver;
+use crate::errors::Error;
+use crate::lib::backup;
+use crate::lib::util::to_object_id;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+
+const QUEUE_NAME: &str = "create_backup";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JobPayload {
+  pub backup: String,
+}
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models, archiver: Archiver) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let archiver = archiver.clone();
+      let delivery = delivery.expect("Error caught in consumer");
+
+      async move {
+        let delivery = match delivery {
+          Some((_channel, delivery)) => delivery,
+          None => return, // The consumer got canceled.
+        };
+
+        let payload: JobPayload = bincode::deserialize(delivery.data.as_ref()).unwrap();
+        info!(
+          "Processing create_backup job for backup {}",
+          &payload.backup
+        );
+
+        // Failures are stored in the backup, the message is not requeued.
+        if let Err(err) = create_backup(payload, models, archiver).await {
+          error!("Failed to process the create_backup job. Error: {}", err);
+        }
+
+        delivery
+          .ack(BasicAckOptions::default())
+          .await
+          .expect("Failed to ack");
+      }
+    })
+    .unwrap();
+}
+
+async fn create_backup(
+  payload: JobPayload,
+  models: Models,
+  archiver: Archiver,
+) -> Result<(), Error> {
+  let backup_id = to_object_id(payload.backup)?;
+  let backup = match models.backup.find_by_id(&backup_id).await? {
+    Some(backup) => backup,
+    None => {
+      error!("Backup {} not found", &backup_id);
+      return Ok(());
+    }
+  };
+
+  models.backup.set_running(&backup_id).await?;
+
+  let result = async {
+    let archive = backup::create_archive(&models, &backup.user).await?;
+    let data = backup::encode(&archive)?;
+    let size = data.len() as i64;
+    archiver.put_backup(&backup_id, data).await?;
+    Ok::<i64, Error>(size)
+  }
+  .await;
+
+  match result {
+    Ok(size) => models.backup.set_completed(&backup_id, size).await,
+    Err(err) => {
+      models
+        .backup
+        .set_failed(&backup_id, err.to_string())
+        .await?;
+      Err(err)
+    }
+  }
+}
@@ -6,12 +6,25 @@ use lapin::options::BasicNackOptions;
 use lapin::options::QueueDeclareOptions;
 use lapin::types::FieldTable;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use validator::Validate;
 use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::DateTime;
 
 use crate::errors::Error;
+use crate::importers::ImportItem;
 use crate::lib::date;
 use crate::lib::util::parse_url;
+use crate::lib::util::sanitize_tags;
 use crate::lib::util::to_object_id;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::annotation::Annotation;
+use crate::models::import_job::ImportJob;
+use crate::models::import_job_item::ImportJobItem;
+use crate::models::import_job_item::ItemStatus;
 use crate::models::list::List;
 use crate::models::resource::Resource;
 use crate::models::Model;
@@ -79,33 +92,65 @@ pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
 }
 
 async fn create_resources(payload: JobPayload, models: Models) -> Result<(), Error> {
-  let list_id = payload.list;
-  let list_id = to_object_id(list_id)?;
-  let urls = payload.urls;
+  let job_id = to_object_id(payload.import_job)?;
+  let list_id = to_object_id(payload.list)?;
+  let source = payload.source;
+  let items = payload.items;
 
   let list = match models.list.find_by_id(&list_id).await? {
     Some(list) => list,
     None => {
       error!("List {:?} not found", &list_id);
+      for job_item in items {
+        let reason = Some("List not found".to_owned());
+        models
+          .import_job
+          .set_item_result(&job_id, job_item.index, ItemStatus::Failed, reason, None)
+          .await?;
+      }
       return Ok(());
     }
   };
 
-  let position = models.list.get_next_resource_position(&list_id).await?;
+  let positions = models
+    .list
+    .get_next_resource_positions(&list_id, items.len())
+    .await?;
 
-  let resource_futures = urls.into_iter().enumerate().map(|(index, url)| {
-    let position = position + (index + 1) as i32;
-    let models = models.clone();
-    let list = list.clone();
+  let resource_futures = items
+    .into_iter()
+    .zip(positions)
+    .map(|(job_item, position)| {
+      let models = models.clone();
+      let list = list.clone();
+      let job_id = job_id.clone();
 
-    async move {
-      let result = create_resource(models.clone(), &list, url.clone(), position).await;
-      // TODO: Improve this error handling, should we retry this URL?
-      if let Err(err) = result {
-        error!("Failed to create resource with URL {}. Error: {}", url, err);
-      };
-    }
-  });
+      async move {
+        let url = job_item.item.url.clone();
+        let result = create_resource(models.clone(), &list, job_item.item, position, source).await;
+        let (status, reason, resource) = match result {
+          Ok(Outcome::Created(resource_id)) => (ItemStatus::Created, None, Some(resource_id)),
+          Ok(Outcome::Duplicate) => (ItemStatus::Duplicate, None, None),
+          Ok(Outcome::Invalid(reason)) => (ItemStatus::Invalid, Some(reason), None),
+          Err(err) => {
+            error!("Failed to create resource with URL {}. Error: {}", url, err);
+            (ItemStatus::Failed, Some(err.to_string()), None)
+          }
+        };
+
+        let result = models
+          .import_job
+          .set_item_result(&job_id, job_item.index, status, reason, resource)
+          .await;
+
+        if let Err(err) = result {
+          error!(
+            "Failed to update import job {} item {}. Error: {}",
+            &job_id, job_item.index, err
+          );
+        }
+      }
+    });
 
   futures::stream::iter(resource_futures)
     .buffer_unordered(50)
@@ -115,18 +160,28 @@ async fn create_resources(payload: JobPayload, models: Models) -> Result<(), Err
   Ok(())
 }
 
+enum Outcome {
+  Created(ObjectId),
+  Duplicate,
+  Invalid(String),
+}
+
 async fn create_resource(
   models: Models,
   list: &List,
-  url: String,
-  position: i32,
-) -> Result<(), Error> {
+  item: ImportItem,
+  position: String,
+  source: Source,
+) -> Result<Outcome, Error> {
   debug!(
     "Creating resource from url {:?} to list {:?}",
-    &url, &list.id
+    &item.url, &list.id
   );
 
-  let url = parse_url(&url)?;
+  let url = match parse_url(&item.url) {
+    Ok(url) => url,
+    Err(err) => return Ok(Outcome::Invalid(err.to_string())),
+  };
   let list_id = list.id.clone().unwrap();
   let resource_exists = models
     .resource
@@ -134,40 +189,157 @@ async fn create_resource(
     .await?;
 
   if resource_exists {
-    return Ok(());
+    return Ok(Outcome::Duplicate);
   }
 
+  // Imported titles were set by the user in the source service, they are
+  // kept when the resource is populated.
+  let edited_fields = match item.title {
+    Some(_) => vec!["title".to_owned()],
+    None => vec![],
+  };
+  let created_at = item
+    .created_at
+    .and_then(date::from_timestamp)
+    .unwrap_or_else(date::now);
+  let completed_at = match item.completed {
+    true => Some(created_at),
+    false => None,
+  };
+
   let resource = Resource {
     id: None,
     url: url.to_string(),
     position,
     user: list.user.clone(),
     list: list_id,
-    created_at: date::now(),
+    created_at,
     updated_at: date::now(),
-    title: None,
+    title: item.title,
     description: None,
     thumbnail: None,
-    tags: vec![],
+    tags: sanitize_tags(item.tags),
     html: None,
     text: None,
     author: None,
     length: None,
     publisher: None,
-    completed_at: None,
+    completed_at,
     populated_at: None,
+    refresh_attempted_at: None,
+    signature_indexed_at: None,
+    annotations_text: None,
+    estimated_reading_time: None,
+    progress: None,
+    snoozed_until: None,
+    review: None,
+    archived_at: None,
+    favicon: None,
+    kind: None,
+    media: None,
+    health: None,
+    last_checked_at: None,
+    edited_fields: Some(edited_fields),
+    suggested_tags: None,
   };
 
-  let resource = models.resource.build(resource).await?;
+  let resource = models.list.create_resource(resource).await?;
+  let resource_id = resource.id.clone().unwrap();
+
+  // The resource was created, following errors are logged and do not fail
+  // the import of the URL.
+  if let Err(err) = finish_resource(&models, &resource, item.note, created_at, source).await {
+    error!(
+      "Failed to finish imported resource {}. Error: {}",
+      &resource_id, err
+    );
+  }
+
+  Ok(Outcome::Created(resource_id))
+}
+
+async fn finish_resource(
+  models: &Models,
+  resource: &Resource,
+  note: Option<String>,
+  created_at: DateTime,
+  source: Source,
+) -> Result<(), Error> {
   let resource_id = resource.id.clone().unwrap();
 
+  models
+    .activity
+    .record(Activity::new(resource, Action::Create, source))
+    .await;
+
+  if let Some(note) = note {
+    let annotation = Annotation {
+      id: None,
+      user: resource.user.clone(),
+      list: resource.list.clone(),
+      resource: resource_id.clone(),
+      highlight: None,
+      note: Some(note),
+      created_at,
+      updated_at: created_at,
+    };
+
+    annotation.validate().map_err(Error::ValidateModel)?;
+    models.annotation.create(annotation).await?;
+    let text = models.annotation.get_search_text(&resource_id).await?;
+    models
+      .resource
+      .update_annotations_text(&resource_id, text)
+      .await?;
+  }
+
   models.resource.populate(resource_id).await?;
 
   Ok(())
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JobItem {
+  // Index of the item in the import job.
+  pub index: usize,
+  pub item: ImportItem,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct JobPayload {
+  pub import_job: String,
   pub list: String,
-  pub urls: Vec<String>,
+  pub source: Source,
+  pub items: Vec<JobItem>,
+}
+
+// Creates a payload for each list of the given import job items, invalid
+// items are not queued.
+pub fn create_payloads(job: &ImportJob, items: &[ImportJobItem]) -> Vec<JobPayload> {
+  let job_id = job.id.clone().unwrap().to_hex();
+  let mut payloads: HashMap<ObjectId, Vec<JobItem>> = HashMap::new();
+
+  for job_item in items {
+    if job_item.status == ItemStatus::Invalid {
+      continue;
+    }
+
+    payloads
+      .entry(job_item.list.clone())
+      .or_default()
+      .push(JobItem {
+        index: job_item.index as usize,
+        item: job_item.item.clone(),
+      });
+  }
+
+  payloads
+    .into_iter()
+    .map(|(list_id, items)| JobPayload {
+      import_job: job_id.clone(),
+      list: list_id.to_hex(),
+      source: job.source,
+      items,
+    })
+    .collect()
 }
@@ -0,0 +1,99 @@
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use std::collections::HashSet;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+
+use crate::errors::Error;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+
+const QUEUE_NAME: &str = "index_resources";
+// Maximum number of resources indexed each time the job runs.
+const BATCH_SIZE: i64 = 500;
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let models = models.clone();
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let delivery = delivery.expect("Error caught in in consumer");
+      async move {
+        if let Some((_channel, delivery)) = delivery {
+          info!("Received message: {:?}", &delivery.data);
+
+          index_resources(models)
+            .await
+            .expect("Index resources succesfully");
+
+          delivery
+            .ack(BasicAckOptions::default())
+            .await
+            .expect("Failed to ack");
+        }
+      }
+    })
+    .unwrap();
+}
+
+// Computes the similarity signatures of the resources populated or updated
+// since their last indexing.
+async fn index_resources(models: Models) -> Result<(), Error> {
+  let resources = models.resource.find_signature_pending(BATCH_SIZE).await?;
+  info!("Indexing {} resources", resources.len());
+
+  let list_ids = resources
+    .iter()
+    .map(|resource| resource.list.clone())
+    .collect::<HashSet<ObjectId>>()
+    .into_iter()
+    .collect::<Vec<ObjectId>>();
+  let public_lists = models
+    .list
+    .find(doc! { "_id": { "$in": list_ids }, "is_public": true }, None)
+    .await?
+    .into_iter()
+    .filter_map(|list| list.id)
+    .collect::<HashSet<ObjectId>>();
+
+  for resource in resources {
+    let resource_id = resource.id.clone().unwrap();
+    let is_public = public_lists.contains(&resource.list);
+    // Failing to index a resource should not prevent indexing the rest of
+    // the resources.
+    let result = match models.resource_signature.index(&resource, is_public).await {
+      Ok(()) => models.resource.set_signature_indexed_at(&resource_id).await,
+      Err(err) => Err(err),
+    };
+
+    if let Err(err) = result {
+      error!("Failed to index resource {}. Error {}", &resource_id, err);
+    }
+  }
+
+  Ok(())
+}
@@ -1,8 +1,20 @@
+pub mod check_resources_health;
+pub mod create_backup;
 pub mod create_resources;
+pub mod index_resources;
+pub mod poll_integrations;
 pub mod populate_resources;
+pub mod refresh_resources;
+pub mod restore_backup;
+pub mod unsnooze_resources;
 
+use crate::archiver::Archiver;
+use crate::health::HealthChecker;
 use crate::models::Models;
 use crate::rabbit_mq::RabbitMQ;
+use crate::settings::Settings;
+use crate::sources::Sources;
+use crate::thirdparty::rss::Rss;
 use lapin::options::BasicPublishOptions;
 use lapin::BasicProperties;
 use serde::Serialize;
@@ -13,9 +25,25 @@ pub struct Jobs {
 }
 
 impl Jobs {
-  pub async fn setup(rabbit_mq: RabbitMQ, models: Models) -> Self {
-    populate_resources::setup(rabbit_mq.clone(), models.clone()).await;
+  pub async fn setup(
+    rabbit_mq: RabbitMQ,
+    models: Models,
+    archiver: Archiver,
+    health_checker: HealthChecker,
+    sources: Sources,
+    rss: Rss,
+    settings: &Settings,
+  ) -> Self {
+    populate_resources::setup(rabbit_mq.clone(), models.clone(), archiver.clone()).await;
+    create_backup::setup(rabbit_mq.clone(), models.clone(), archiver.clone()).await;
+    restore_backup::setup(rabbit_mq.clone(), models.clone(), archiver, rss).await;
     create_resources::setup(rabbit_mq.clone(), models.clone()).await;
+    unsnooze_resources::setup(rabbit_mq.clone(), models.clone()).await;
+    index_resources::setup(rabbit_mq.clone(), models.clone()).await;
+    poll_integrations::setup(rabbit_mq.clone(), models.clone(), sources).await;
+    check_resources_health::setup(rabbit_mq.clone(), models.clone(), health_checker).await;
+    let stale_after_days = settings.refresh.stale_after_days;
+    refresh_resources::setup(rabbit_mq.clone(), models.clone(), stale_after_days).await;
 
     Self { rabbit_mq }
   }
@@ -0,0 +1,435 @@
+use futures::stream::{self, StreamExt};
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::mongodb::options::FindOptions;
+
+use crate::errors::Error;
+use crate::lib::date;
+use crate::lib::util::parse_url;
+use crate::lib::util::sanitize_tags;
+use crate::lib::util::to_object_id;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::integration::Integration;
+use crate::models::integration::POLLED_KINDS;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+use crate::sources::SourceItem;
+use crate::sources::Sources;
+
+const QUEUE_NAME: &str = "poll_integrations";
+// Amount of integrations polled by each job run.
+const BATCH_SIZE: i64 = 100;
+const CONCURRENCY: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JobPayload {
+  // Polls a single integration, E.g. right after it is created. The least
+  // recently polled integrations are polled when empty.
+  pub integration: Option<String>,
+}
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models, sources: Sources) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let sources = sources.clone();
+      let delivery = delivery.expect("Error caught in consumer");
+
+      async move {
+        let delivery = match delivery {
+          Some((_channel, delivery)) => delivery,
+          None => return, // The consumer got canceled.
+        };
+
+        let payload: JobPayload = bincode::deserialize(delivery.data.as_ref()).unwrap();
+        info!("Processing poll_integrations job");
+
+        // Integrations are polled again on the next run, the message is not
+        // requeued.
+        if let Err(err) = poll_integrations(payload, models, sources).await {
+          error!(
+            "Failed to process the poll_integrations job. Error: {}",
+            err
+          );
+        }
+
+        delivery
+          .ack(BasicAckOptions::default())
+          .await
+          .expect("Failed to ack");
+      }
+    })
+    .unwrap();
+}
+
+async fn poll_integrations(
+  payload: JobPayload,
+  models: Models,
+  sources: Sources,
+) -> Result<(), Error> {
+  let query = match payload.integration {
+    Some(integration_id) => doc! { "_id": to_object_id(integration_id)? },
+    None => doc! { "kind": { "$in": POLLED_KINDS } },
+  };
+  let options = FindOptions::builder()
+    .sort(doc! { "polled_at": 1 })
+    .limit(BATCH_SIZE)
+    .build();
+
+  let integrations = models.integration.find(query, Some(options)).await?;
+  info!("Polling {} integrations", integrations.len());
+
+  let results = stream::iter(integrations)
+    .map(|integration| {
+      let models = models.clone();
+      let sources = sources.clone();
+      async move {
+        let result = poll_integration(&models, &sources, &integration).await;
+        (integration.id.unwrap(), result)
+      }
+    })
+    .buffer_unordered(CONCURRENCY)
+    .collect::<Vec<(ObjectId, Result<usize, Error>)>>()
+    .await;
+
+  for (integration_id, result) in results {
+    match result {
+      Ok(count) => debug!(
+        "Created {} resources from integration {}",
+        count, integration_id
+      ),
+      Err(err) => error!(
+        "Failed to poll integration {}. Error {}",
+        integration_id, err
+      ),
+    }
+  }
+
+  Ok(())
+}
+
+// Creates a resource for each source item the integration did not fetch
+// before. Returns the amount of created resources.
+async fn poll_integration(
+  models: &Models,
+  sources: &Sources,
+  integration: &Integration,
+) -> Result<usize, Error> {
+  let provider = match sources.get(&integration.kind) {
+    Some(provider) => provider,
+    None => return Ok(0),
+  };
+
+  // Set before fetching so a failing source does not block the next
+  // integrations of the batch.
+  models
+    .integration
+    .update_one(
+      doc! { "_id": integration.id.as_ref().unwrap() },
+      doc! { "$set": { "polled_at": Bson::DateTime(date::now().into()) } },
+      None,
+    )
+    .await?;
+
+  let integration_id = integration.id.as_ref().unwrap();
+  let items = get_unique_items(provider.fetch(integration).await?);
+  let urls = items
+    .iter()
+    .map(|(url, _)| url.clone())
+    .collect::<Vec<String>>();
+  let seen_urls = models
+    .integration_item
+    .find_seen_urls(integration_id, &urls)
+    .await?;
+
+  let mut new_items: Vec<(String, SourceItem)> = vec![];
+  for (url, item) in get_unseen_items(items, &seen_urls) {
+    // Items fetched before the integration recorded them were not stored,
+    // the resources of the list are checked too.
+    let resource_exists = models
+      .resource
+      .exists(doc! { "user": &integration.user, "list": &integration.list, "url": &url })
+      .await?;
+
+    if !resource_exists {
+      new_items.push((url, item));
+    }
+  }
+
+  let unseen_urls = urls
+    .into_iter()
+    .filter(|url| !seen_urls.contains(url))
+    .collect::<Vec<String>>();
+
+  if new_items.is_empty() {
+    models
+      .integration_item
+      .set_seen_urls(integration_id, &unseen_urls)
+      .await?;
+    return Ok(0);
+  }
+
+  let positions = models
+    .list
+    .get_next_resource_positions(&integration.list, new_items.len())
+    .await?;
+
+  let mut resource_ids = vec![];
+  for ((url, item), position) in new_items.into_iter().zip(positions) {
+    let resource = create_resource_payload_from_item(url, item, integration, position);
+    let resource = models.list.create_resource(resource).await?;
+
+    models
+      .activity
+      .record(Activity::new(
+        &resource,
+        Action::Create,
+        Source::Integration,
+      ))
+      .await;
+
+    resource_ids.push(resource.id.unwrap());
+  }
+
+  // Stored once the resources exist, items of a failed poll are fetched
+  // again.
+  models
+    .integration_item
+    .set_seen_urls(integration_id, &unseen_urls)
+    .await?;
+
+  models
+    .list
+    .update_last_activity_at(&integration.list)
+    .await?;
+
+  let count = resource_ids.len();
+  for resource_id in resource_ids {
+    if let Err(err) = models.resource.populate(resource_id.clone()).await {
+      error!(
+        "Failed to populate resource {} created from integration. Error {}",
+        resource_id, err
+      );
+    }
+  }
+
+  Ok(count)
+}
+
+// Normalizes the item URLs, items with invalid URLs and repeated URLs are
+// skipped.
+fn get_unique_items(items: Vec<SourceItem>) -> Vec<(String, SourceItem)> {
+  let mut unique_items: Vec<(String, SourceItem)> = vec![];
+  for item in items {
+    let url = match parse_url(&item.url) {
+      Ok(url) => url.to_string(),
+      Err(_) => continue,
+    };
+
+    if !unique_items
+      .iter()
+      .any(|(unique_url, _)| unique_url == &url)
+    {
+      unique_items.push((url, item));
+    }
+  }
+
+  unique_items
+}
+
+fn get_unseen_items(
+  items: Vec<(String, SourceItem)>,
+  seen_urls: &HashSet<String>,
+) -> Vec<(String, SourceItem)> {
+  items
+    .into_iter()
+    .filter(|(url, _)| !seen_urls.contains(url))
+    .collect()
+}
+
+fn create_resource_payload_from_item(
+  url: String,
+  item: SourceItem,
+  integration: &Integration,
+  position: String,
+) -> Resource {
+  let now = date::now();
+
+  Resource {
+    id: None,
+    user: integration.user.clone(),
+    list: integration.list.clone(),
+    position,
+    tags: sanitize_tags(item.tags),
+    url,
+    title: item.title,
+    description: item.description,
+    created_at: now,
+    updated_at: now,
+    thumbnail: item.thumbnail,
+    completed_at: None,
+    html: None,
+    text: None,
+    author: None,
+    length: None,
+    publisher: None,
+    populated_at: None,
+    refresh_attempted_at: None,
+    signature_indexed_at: None,
+    annotations_text: None,
+    estimated_reading_time: None,
+    progress: None,
+    snoozed_until: None,
+    review: None,
+    archived_at: None,
+    favicon: None,
+    kind: None,
+    media: None,
+    health: None,
+    last_checked_at: None,
+    edited_fields: Some(vec![]),
+    suggested_tags: None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use async_trait::async_trait;
+  use futures::executor::block_on;
+  use std::sync::Arc;
+
+  use super::*;
+  use crate::models::integration::github_stars::GitHubStars;
+  use crate::models::integration::Kind;
+  use crate::sources::SourceProvider;
+
+  struct StubSourceProvider {
+    items: Vec<SourceItem>,
+  }
+
+  #[async_trait]
+  impl SourceProvider for StubSourceProvider {
+    async fn fetch(&self, _integration: &Integration) -> Result<Vec<SourceItem>, Error> {
+      Ok(self.items.clone())
+    }
+  }
+
+  fn create_item(url: &str) -> SourceItem {
+    SourceItem {
+      url: url.to_owned(),
+      title: Some(url.to_owned()),
+      tags: vec!["GitHub".to_owned(), "rust".to_owned()],
+      ..Default::default()
+    }
+  }
+
+  fn create_integration() -> Integration {
+    let now = date::now();
+    Integration {
+      id: Some(ObjectId::new()),
+      user: ObjectId::new(),
+      list: ObjectId::new(),
+      kind: Kind::GitHubStars,
+      created_at: now,
+      updated_at: now,
+      rss: None,
+      listas_subscription: None,
+      github_stars: Some(GitHubStars {
+        username: "octocat".to_owned(),
+      }),
+      youtube_playlist: None,
+      polled_at: None,
+    }
+  }
+
+  fn fetch(items: Vec<SourceItem>) -> Vec<(String, SourceItem)> {
+    let provider: Arc<dyn SourceProvider> = Arc::new(StubSourceProvider { items });
+    let items = block_on(provider.fetch(&create_integration())).unwrap();
+    get_unique_items(items)
+  }
+
+  #[test]
+  fn repeated_and_invalid_urls_are_skipped() {
+    let items = fetch(vec![
+      create_item("https://github.com/rust-lang/rust"),
+      create_item("not a url"),
+      create_item("https://github.com/rust-lang/rust"),
+      create_item("https://github.com/tokio-rs/tokio"),
+    ]);
+
+    let urls = items
+      .iter()
+      .map(|(url, _)| url.as_str())
+      .collect::<Vec<&str>>();
+    assert_eq!(
+      urls,
+      vec![
+        "https://github.com/rust-lang/rust",
+        "https://github.com/tokio-rs/tokio"
+      ]
+    );
+  }
+
+  #[test]
+  fn seen_items_are_not_created_again() {
+    let items = fetch(vec![
+      create_item("https://github.com/rust-lang/rust"),
+      create_item("https://github.com/tokio-rs/tokio"),
+    ]);
+
+    let mut seen_urls = HashSet::new();
+    seen_urls.insert("https://github.com/rust-lang/rust".to_owned());
+
+    let items = get_unseen_items(items, &seen_urls);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].0, "https://github.com/tokio-rs/tokio");
+  }
+
+  #[test]
+  fn new_items_are_created_in_the_integration_list() {
+    let integration = create_integration();
+    let mut items = fetch(vec![create_item("https://github.com/tokio-rs/tokio")]);
+    let (url, item) = items.remove(0);
+
+    let resource = create_resource_payload_from_item(url, item, &integration, "i".to_owned());
+
+    assert_eq!(resource.user, integration.user);
+    assert_eq!(resource.list, integration.list);
+    assert_eq!(resource.url, "https://github.com/tokio-rs/tokio");
+    assert_eq!(resource.position, "i");
+    assert_eq!(resource.tags, vec!["github", "rust"]);
+    assert_eq!(resource.edited_fields, Some(vec![]));
+  }
+}
@@ -3,15 +3,18 @@ use lapin::options::BasicAckOptions;
 use lapin::options::BasicConsumeOptions;
 use lapin::options::QueueDeclareOptions;
 use lapin::types::FieldTable;
+use wither::bson::oid::ObjectId;
 
+use crate::archiver::Archiver;
 use crate::errors::Error;
 use crate::lib::util::to_object_id;
+use crate::models::Model as ModelTrait;
 use crate::models::Models;
 use crate::rabbit_mq::RabbitMQ;
 
 const QUEUE_NAME: &str = "populate_resources";
 
-pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models, archiver: Archiver) {
   let channel = rabbit_mq.channel;
   let _queue = channel
     .queue_declare(
@@ -36,6 +39,7 @@ pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
   consumer
     .set_delegate(move |delivery: DeliveryResult| {
       let models = models.clone();
+      let archiver = archiver.clone();
       let delivery = delivery.expect("Error caught in in consumer");
       async move {
         if let Some((_channel, delivery)) = delivery {
@@ -43,7 +47,7 @@ pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
 
           info!("Received message: {:?}", &payload);
 
-          populate_resources(payload, models)
+          populate_resources(payload, models, archiver)
             .await
             .expect("Populate resources succesfully");
 
@@ -57,22 +61,57 @@ pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
     .unwrap();
 }
 
-async fn populate_resources(payload: Vec<u8>, models: Models) -> Result<(), Error> {
+async fn populate_resources(
+  payload: Vec<u8>,
+  models: Models,
+  archiver: Archiver,
+) -> Result<(), Error> {
   let ids: Vec<String> = bincode::deserialize(payload.as_ref()).unwrap();
   // TODO: Handle resources in parallel
   info!("Populating resources {:?}", &ids);
 
   for id in ids {
-    populate_resource(id, models.clone()).await.unwrap();
+    populate_resource(id, models.clone(), archiver.clone())
+      .await
+      .unwrap();
   }
 
   Ok(())
 }
 
-async fn populate_resource(resource_id: String, models: Models) -> Result<(), Error> {
+async fn populate_resource(
+  resource_id: String,
+  models: Models,
+  archiver: Archiver,
+) -> Result<(), Error> {
   info!("Populating resource: {}", &resource_id);
   let id = to_object_id(resource_id).expect("Job to receive a valid resource ID");
-  models.resource.populate(id).await.unwrap();
+  models.resource.populate(id.clone()).await.unwrap();
+
+  // Failing to archive a resource should not prevent populating the rest of
+  // the resources.
+  if let Err(err) = archive_resource(&id, &models, &archiver).await {
+    error!("Failed to archive resource {}. Error {}", &id, err);
+  }
+
+  Ok(())
+}
+
+async fn archive_resource(
+  resource_id: &ObjectId,
+  models: &Models,
+  archiver: &Archiver,
+) -> Result<(), Error> {
+  let resource = match models.resource.find_by_id(resource_id).await? {
+    Some(resource) => resource,
+    None => return Ok(()),
+  };
+
+  info!("Archiving resource: {}", resource_id);
+  let is_archived = archiver.archive(resource_id, &resource.get_url()).await?;
+  if is_archived {
+    models.resource.set_archived_at(resource_id).await?;
+  }
 
   Ok(())
 }
@@ -0,0 +1,93 @@
+use futures::stream::{self, StreamExt};
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use wither::bson::oid::ObjectId;
+
+use crate::errors::Error;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+
+const QUEUE_NAME: &str = "refresh_resources";
+// Amount of resources refreshed by each job run.
+const BATCH_SIZE: i64 = 200;
+const CONCURRENCY: usize = 5;
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models, stale_after_days: i64) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let models = models.clone();
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let delivery = delivery.expect("Error caught in in consumer");
+      async move {
+        if let Some((_channel, delivery)) = delivery {
+          info!("Received message: {:?}", &delivery.data);
+
+          refresh_resources(models, stale_after_days)
+            .await
+            .expect("Refresh resources succesfully");
+
+          delivery
+            .ack(BasicAckOptions::default())
+            .await
+            .expect("Failed to ack");
+        }
+      }
+    })
+    .unwrap();
+}
+
+// Populates again the resources with stale metadata.
+async fn refresh_resources(models: Models, stale_after_days: i64) -> Result<(), Error> {
+  let resources = models
+    .resource
+    .find_stale(stale_after_days, BATCH_SIZE)
+    .await?;
+
+  info!("Refreshing {} stale resources", resources.len());
+
+  let resource_ids = resources
+    .iter()
+    .filter_map(|resource| resource.id.clone())
+    .collect::<Vec<ObjectId>>();
+  models
+    .resource
+    .set_refresh_attempted_at(resource_ids)
+    .await?;
+
+  let results = stream::iter(resources)
+    .map(|resource| models.resource.populate(resource.id.unwrap()))
+    .buffer_unordered(CONCURRENCY)
+    .collect::<Vec<Result<(), Error>>>()
+    .await;
+
+  for result in results {
+    if let Err(err) = result {
+      error!("Failed to refresh resource. Error {}", err);
+    }
+  }
+
+  Ok(())
+}
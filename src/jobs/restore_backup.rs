@@ -0,0 +1,123 @@
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+use serde::{Deserialize, Serialize};
+
+use crate::archiver::Archiver;
+use crate::errors::Error;
+use crate::lib::backup;
+use crate::lib::util::to_object_id;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+use crate::thirdparty::rss::Rss;
+
+const QUEUE_NAME: &str = "restore_backup";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JobPayload {
+  pub backup: String,
+}
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models, archiver: Archiver, rss: Rss) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let archiver = archiver.clone();
+      let rss = rss.clone();
+      let delivery = delivery.expect("Error caught in consumer");
+
+      async move {
+        let delivery = match delivery {
+          Some((_channel, delivery)) => delivery,
+          None => return, // The consumer got canceled.
+        };
+
+        let payload: JobPayload = bincode::deserialize(delivery.data.as_ref()).unwrap();
+        info!(
+          "Processing restore_backup job for backup {}",
+          &payload.backup
+        );
+
+        // Failures are stored in the backup, the message is not requeued.
+        if let Err(err) = restore_backup(payload, models, archiver, rss).await {
+          error!("Failed to process the restore_backup job. Error: {}", err);
+        }
+
+        delivery
+          .ack(BasicAckOptions::default())
+          .await
+          .expect("Failed to ack");
+      }
+    })
+    .unwrap();
+}
+
+async fn restore_backup(
+  payload: JobPayload,
+  models: Models,
+  archiver: Archiver,
+  rss: Rss,
+) -> Result<(), Error> {
+  let backup_id = to_object_id(payload.backup)?;
+  let backup = match models.backup.find_by_id(&backup_id).await? {
+    Some(backup) => backup,
+    None => {
+      error!("Backup {} not found", &backup_id);
+      return Ok(());
+    }
+  };
+
+  models.backup.set_running(&backup_id).await?;
+
+  let result = async {
+    let data = archiver
+      .get_backup(&backup_id)
+      .await?
+      .ok_or_else(|| Error::Backup("Uploaded archive not found".to_owned()))?;
+    let archive = backup::decode(&data)?;
+    backup::restore(&models, &rss, &backup.user, archive).await
+  }
+  .await;
+
+  // The uploaded archive is only kept until it is restored.
+  if let Err(err) = archiver.remove_backup(&backup_id).await {
+    error!(
+      "Failed to remove uploaded archive of backup {}. Error: {}",
+      &backup_id, err
+    );
+  }
+
+  match result {
+    Ok(summary) => models.backup.set_restored(&backup_id, &summary).await,
+    Err(err) => {
+      models
+        .backup
+        .set_failed(&backup_id, err.to_string())
+        .await?;
+      Err(err)
+    }
+  }
+}
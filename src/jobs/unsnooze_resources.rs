@@ -0,0 +1,63 @@
+use lapin::message::DeliveryResult;
+use lapin::options::BasicAckOptions;
+use lapin::options::BasicConsumeOptions;
+use lapin::options::QueueDeclareOptions;
+use lapin::types::FieldTable;
+
+use crate::errors::Error;
+use crate::models::Models;
+use crate::rabbit_mq::RabbitMQ;
+
+const QUEUE_NAME: &str = "unsnooze_resources";
+
+pub async fn setup(rabbit_mq: RabbitMQ, models: Models) {
+  let channel = rabbit_mq.channel;
+  let _queue = channel
+    .queue_declare(
+      QUEUE_NAME,
+      QueueDeclareOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let consumer = channel
+    .basic_consume(
+      QUEUE_NAME,
+      "",
+      BasicConsumeOptions::default(),
+      FieldTable::default(),
+    )
+    .await
+    .unwrap();
+
+  let models = models.clone();
+  consumer
+    .set_delegate(move |delivery: DeliveryResult| {
+      let models = models.clone();
+      let delivery = delivery.expect("Error caught in in consumer");
+      async move {
+        if let Some((_channel, delivery)) = delivery {
+          info!("Received message: {:?}", &delivery.data);
+
+          unsnooze_resources(models)
+            .await
+            .expect("Unsnooze resources succesfully");
+
+          delivery
+            .ack(BasicAckOptions::default())
+            .await
+            .expect("Failed to ack");
+        }
+      }
+    })
+    .unwrap();
+}
+
+async fn unsnooze_resources(models: Models) -> Result<(), Error> {
+  info!("Unsnoozing resources and resurfacing due reviews");
+  models.resource.unsnooze().await?;
+  models.resource.resurface_reviews().await?;
+
+  Ok(())
+}
@@ -0,0 +1,545 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::DateTime;
+use wither::mongodb::options::FindOptions;
+
+use crate::errors::Error;
+use crate::lib::date;
+use crate::lib::util::parse_url;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::integration::rss::Rss;
+use crate::models::integration::subscription::ListasSubscription;
+use crate::models::integration::Integration;
+use crate::models::integration::Kind;
+use crate::models::like::Like;
+use crate::models::list::List;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::models::Models;
+use crate::thirdparty::rss::Rss as RssClient;
+
+// Increased when the archive format changes in a way older restores can not
+// read. Archives with a greater version are rejected.
+pub const BACKUP_VERSION: i32 = 1;
+// Maximum size of the decompressed archive, compressed JSON archives can
+// expand to many times their upload size.
+const MAX_ARCHIVE_SIZE: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupArchive {
+  pub version: i32,
+  // Owner of the backed up account, the restore uses the requesting user.
+  pub user: ObjectId,
+  pub created_at: DateTime,
+  pub lists: Vec<List>,
+  pub resources: Vec<Resource>,
+  pub integrations: Vec<Integration>,
+  pub likes: Vec<Like>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreSummary {
+  pub lists_count: usize,
+  pub resources_count: usize,
+  pub integrations_count: usize,
+  pub likes_count: usize,
+  // Documents that could not be restored, E.g. RSS feeds that could not be
+  // subscribed again.
+  pub skipped_count: usize,
+}
+
+pub async fn create_archive(models: &Models, user_id: &ObjectId) -> Result<BackupArchive, Error> {
+  let query = doc! { "user": user_id };
+  let sort = FindOptions::builder()
+    .sort(doc! { "created_at": 1 })
+    .build();
+
+  let lists = models.list.find(query.clone(), Some(sort.clone())).await?;
+  let resources = models
+    .resource
+    .find(query.clone(), Some(sort.clone()))
+    .await?;
+  let integrations = models
+    .integration
+    .find(query.clone(), Some(sort.clone()))
+    .await?;
+  let likes = models.like.find(query, Some(sort)).await?;
+
+  Ok(BackupArchive {
+    version: BACKUP_VERSION,
+    user: user_id.clone(),
+    created_at: date::now(),
+    lists,
+    resources,
+    integrations,
+    likes,
+  })
+}
+
+// Archives are gzip compressed JSON documents.
+pub fn encode(archive: &BackupArchive) -> Result<Vec<u8>, Error> {
+  let json = serde_json::to_vec(archive).map_err(|err| Error::Backup(err.to_string()))?;
+  let mut encoder = GzEncoder::new(vec![], Compression::default());
+  encoder.write_all(&json).map_err(Error::BlobStore)?;
+  let data = encoder.finish().map_err(Error::BlobStore)?;
+
+  Ok(data)
+}
+
+// Accepts compressed and plain JSON archives.
+pub fn decode(data: &[u8]) -> Result<BackupArchive, Error> {
+  let is_gzip = data.starts_with(&[0x1f, 0x8b]);
+  let json = if is_gzip {
+    decompress(data, MAX_ARCHIVE_SIZE)?
+  } else {
+    data.to_vec()
+  };
+
+  let archive: BackupArchive =
+    serde_json::from_slice(&json).map_err(|err| Error::Backup(err.to_string()))?;
+
+  if archive.version > BACKUP_VERSION {
+    return Err(Error::Backup(format!(
+      "Unsupported backup version {}",
+      archive.version
+    )));
+  }
+
+  Ok(archive)
+}
+
+fn decompress(data: &[u8], limit: u64) -> Result<Vec<u8>, Error> {
+  let mut json = vec![];
+  // Reads one byte past the limit to tell an archive of the limit size from
+  // a larger one.
+  GzDecoder::new(data)
+    .take(limit + 1)
+    .read_to_end(&mut json)
+    .map_err(|err| Error::Backup(err.to_string()))?;
+
+  if json.len() as u64 > limit {
+    return Err(Error::Backup(format!(
+      "Decompressed archive exceeds {} bytes",
+      limit
+    )));
+  }
+
+  Ok(json)
+}
+
+// Documents created by a restore, removed when the restore fails.
+#[derive(Debug, Default)]
+struct Restored {
+  lists: Vec<ObjectId>,
+  likes: Vec<ObjectId>,
+  rss_subscriptions: Vec<String>,
+}
+
+// Re-creates the archive documents for the specified user with new IDs. The
+// references between the restored documents are remapped, references to
+// documents of other users are kept. MongoDB runs standalone without
+// transactions, the restored documents are removed when the restore fails so
+// it can be retried.
+pub async fn restore(
+  models: &Models,
+  rss: &RssClient,
+  user_id: &ObjectId,
+  archive: BackupArchive,
+) -> Result<RestoreSummary, Error> {
+  let mut restored = Restored::default();
+  let result = restore_archive(models, rss, user_id, archive, &mut restored).await;
+
+  if let Err(ref err) = result {
+    error!(
+      "Failed to restore backup for user {}, rolling back. Error: {}",
+      user_id, err
+    );
+    if let Err(err) = rollback(models, rss, &restored).await {
+      error!(
+        "Failed to roll back backup restore for user {}. Error: {}",
+        user_id, err
+      );
+    }
+  }
+
+  result
+}
+
+// Resources, their activity and the integrations only reference restored
+// lists, they are removed by list.
+async fn rollback(models: &Models, rss: &RssClient, restored: &Restored) -> Result<(), Error> {
+  for subscription_id in restored.rss_subscriptions.iter() {
+    if let Err(err) = rss.unsuscribe(subscription_id).await {
+      error!(
+        "Failed to unsubscribe from RSS subscription {}. Error: {}",
+        subscription_id, err
+      );
+    }
+  }
+
+  let by_list = doc! { "list": { "$in": &restored.lists } };
+  models.resource.delete_many(by_list.clone()).await?;
+  models.activity.delete_many(by_list.clone()).await?;
+  models.integration.delete_many(by_list).await?;
+  models
+    .like
+    .delete_many(doc! { "_id": { "$in": &restored.likes } })
+    .await?;
+  models
+    .list
+    .delete_many(doc! { "_id": { "$in": &restored.lists } })
+    .await?;
+
+  Ok(())
+}
+
+// Archives reference lists that were not part of them, E.g. liked or
+// subscribed lists of other users. Those references are kept when the list
+// exists and is public or owned by the user. Returns the list to reference.
+async fn get_restored_list(
+  models: &Models,
+  user_id: &ObjectId,
+  lists: &HashMap<ObjectId, ObjectId>,
+  list_id: &ObjectId,
+) -> Result<Option<ObjectId>, Error> {
+  if let Some(list_id) = lists.get(list_id) {
+    return Ok(Some(list_id.clone()));
+  }
+
+  let is_accessible = models
+    .list
+    .exists(doc! {
+      "_id": list_id,
+      "$or": [{ "is_public": true }, { "user": user_id }]
+    })
+    .await?;
+
+  Ok(Some(list_id.clone()).filter(|_| is_accessible))
+}
+
+// Only one payload is restored, the first one found.
+fn get_integration_kind(integration: &Integration) -> Option<Kind> {
+  if integration.rss.is_some() {
+    Some(Kind::Rss)
+  } else if integration.listas_subscription.is_some() {
+    Some(Kind::ListasSubscription)
+  } else if integration.github_stars.is_some() {
+    Some(Kind::GitHubStars)
+  } else if integration.youtube_playlist.is_some() {
+    Some(Kind::YouTubePlaylist)
+  } else {
+    None
+  }
+}
+
+async fn restore_archive(
+  models: &Models,
+  rss: &RssClient,
+  user_id: &ObjectId,
+  archive: BackupArchive,
+  restored: &mut Restored,
+) -> Result<RestoreSummary, Error> {
+  let mut summary = RestoreSummary::default();
+  let mut lists: HashMap<ObjectId, ObjectId> = HashMap::new();
+  let now = date::now();
+  // Users have a single inbox, the archive inbox is restored as a regular
+  // list when the user already has one.
+  let has_inbox = models
+    .list
+    .exists(doc! { "user": user_id, "is_inbox": true })
+    .await?;
+
+  for list in archive.lists.iter() {
+    let list_id = ObjectId::new();
+    lists.insert(list.id.clone().unwrap(), list_id);
+  }
+
+  for list in archive.lists {
+    let slug = models.list.get_available_slug(user_id, &list.slug).await?;
+    let is_inbox = match has_inbox {
+      true => None,
+      false => list.is_inbox,
+    };
+    let fork = list.fork.map(|mut fork| {
+      if let Some(list_id) = lists.get(&fork.list) {
+        fork.list = list_id.clone();
+        fork.user = user_id.clone();
+      }
+      fork
+    });
+
+    let list = List {
+      id: lists.get(list.id.as_ref().unwrap()).cloned(),
+      user: user_id.clone(),
+      slug,
+      fork,
+      // Inbound tokens are unique, the restored list gets a new address.
+      inbound_token: Some(List::create_inbound_token()),
+      is_inbox,
+      updated_at: now,
+      ..list
+    };
+
+    let list_id = list.id.clone().unwrap();
+    models.list.create(list).await?;
+    restored.lists.push(list_id);
+    summary.lists_count += 1;
+  }
+
+  let resource_futures = archive.resources.into_iter().map(|resource| {
+    let list_id = lists.get(&resource.list).cloned();
+
+    async move {
+      let list_id = match list_id {
+        Some(list_id) => list_id,
+        None => return Ok(false),
+      };
+
+      let resource = Resource {
+        id: None,
+        user: user_id.clone(),
+        list: list_id,
+        // Signatures are not part of the archive.
+        signature_indexed_at: None,
+        ..resource
+      };
+
+      let resource = models.resource.create(resource).await?;
+      models
+        .activity
+        .record(Activity::new(&resource, Action::Create, Source::Backup))
+        .await;
+      Ok(true)
+    }
+  });
+
+  let results = futures::stream::iter(resource_futures)
+    .buffer_unordered(50)
+    .collect::<Vec<Result<bool, Error>>>()
+    .await;
+
+  for result in results {
+    match result? {
+      true => summary.resources_count += 1,
+      false => summary.skipped_count += 1,
+    }
+  }
+
+  for integration in archive.integrations {
+    let list_id = match lists.get(&integration.list) {
+      Some(list_id) => list_id.clone(),
+      None => {
+        summary.skipped_count += 1;
+        continue;
+      }
+    };
+
+    // The archived kind is not trusted, it is derived from the restored
+    // payload.
+    let kind = match get_integration_kind(&integration) {
+      Some(kind) => kind,
+      None => {
+        summary.skipped_count += 1;
+        continue;
+      }
+    };
+
+    let listas_subscription = match integration.listas_subscription {
+      Some(subscription) if matches!(kind, Kind::ListasSubscription) => {
+        match get_restored_list(models, user_id, &lists, &subscription.list).await? {
+          Some(list_id) => Some(ListasSubscription { list: list_id }),
+          None => {
+            summary.skipped_count += 1;
+            continue;
+          }
+        }
+      }
+      _ => None,
+    };
+
+    if let Some(ref subscription) = listas_subscription {
+      let exists = models
+        .integration
+        .exists(doc! { "user": user_id, "listas_subscription.list": &subscription.list })
+        .await?;
+
+      if exists {
+        summary.skipped_count += 1;
+        continue;
+      }
+    }
+
+    // RSS subscriptions belong to the environment that created them, the
+    // feed is subscribed again.
+    let rss_integration = match integration.rss {
+      Some(previous) if matches!(kind, Kind::Rss) => match resubscribe(rss, &previous).await {
+        Some(rss_integration) => {
+          let subscription_id = rss_integration.subscription_id.clone();
+          restored.rss_subscriptions.push(subscription_id);
+          Some(rss_integration)
+        }
+        None => {
+          summary.skipped_count += 1;
+          continue;
+        }
+      },
+      _ => None,
+    };
+
+    let github_stars = integration
+      .github_stars
+      .filter(|_| matches!(kind, Kind::GitHubStars));
+    let youtube_playlist = integration
+      .youtube_playlist
+      .filter(|_| matches!(kind, Kind::YouTubePlaylist));
+
+    let integration = Integration {
+      id: None,
+      user: user_id.clone(),
+      list: list_id,
+      kind,
+      rss: rss_integration,
+      listas_subscription,
+      github_stars,
+      youtube_playlist,
+      updated_at: now,
+      ..integration
+    };
+
+    models.integration.create(integration).await?;
+    summary.integrations_count += 1;
+  }
+
+  for like in archive.likes {
+    let list_id = match get_restored_list(models, user_id, &lists, &like.list).await? {
+      Some(list_id) => list_id,
+      None => {
+        summary.skipped_count += 1;
+        continue;
+      }
+    };
+
+    let exists = models
+      .like
+      .exists(doc! { "user": user_id, "list": &list_id })
+      .await?;
+
+    if exists {
+      summary.skipped_count += 1;
+      continue;
+    }
+
+    let like = Like {
+      id: None,
+      user: user_id.clone(),
+      list: list_id,
+      ..like
+    };
+
+    let like = models.like.create(like).await?;
+    restored.likes.push(like.id.unwrap());
+    summary.likes_count += 1;
+  }
+
+  Ok(summary)
+}
+
+async fn resubscribe(rss: &RssClient, previous: &Rss) -> Option<Rss> {
+  let url = parse_url(&previous.url).ok()?;
+  match rss.subscribe(&url).await {
+    Ok(subscription) => Some(Rss {
+      url: subscription.url,
+      subscription_id: subscription.id,
+      metadata: subscription.metadata,
+    }),
+    Err(err) => {
+      error!("Failed to subscribe to RSS feed {}. Error: {}", &url, err);
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+  }
+
+  #[test]
+  fn decompresses_archives_up_to_the_limit() {
+    let data = compress(&[b'a'; 100]);
+    assert_eq!(decompress(&data, 100).unwrap().len(), 100);
+  }
+
+  #[test]
+  fn archives_past_the_limit_are_rejected() {
+    let data = compress(&[b'a'; 101]);
+    assert!(matches!(decompress(&data, 100), Err(Error::Backup(_))));
+  }
+
+  #[test]
+  fn corrupt_archives_are_backup_errors() {
+    let mut data = compress(b"{}");
+    data.truncate(data.len() / 2);
+    assert!(matches!(decode(&data), Err(Error::Backup(_))));
+  }
+
+  #[test]
+  fn newer_archive_versions_are_rejected() {
+    let archive = BackupArchive {
+      version: BACKUP_VERSION + 1,
+      user: ObjectId::new(),
+      created_at: date::now(),
+      lists: vec![],
+      resources: vec![],
+      integrations: vec![],
+      likes: vec![],
+    };
+
+    let data = encode(&archive).unwrap();
+    assert!(matches!(decode(&data), Err(Error::Backup(_))));
+  }
+
+  #[test]
+  fn integration_kind_is_derived_from_the_payload() {
+    let integration = Integration {
+      id: None,
+      user: ObjectId::new(),
+      list: ObjectId::new(),
+      kind: Kind::Rss,
+      created_at: date::now(),
+      updated_at: date::now(),
+      rss: None,
+      listas_subscription: Some(ListasSubscription {
+        list: ObjectId::new(),
+      }),
+      github_stars: None,
+      youtube_playlist: None,
+      polled_at: None,
+    };
+
+    assert!(matches!(
+      get_integration_kind(&integration),
+      Some(Kind::ListasSubscription)
+    ));
+
+    let integration = Integration {
+      listas_subscription: None,
+      ..integration
+    };
+    assert!(get_integration_kind(&integration).is_none());
+  }
+}
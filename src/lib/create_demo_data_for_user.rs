@@ -2,6 +2,7 @@ use wither::bson::oid::ObjectId;
 
 use crate::errors::Error;
 use crate::lib::date;
+use crate::lib::rank;
 use crate::models::list::List;
 use crate::models::resource::Resource;
 use crate::models::Model as ModelTrait;
@@ -37,6 +38,10 @@ pub async fn create(models: &Models, user: ObjectId) -> Result<(), Error> {
       updated_at: now,
       last_activity_at: now,
       archived_at: None,
+      auto_tag: None,
+      replace_broken_links: None,
+      inbound_token: Some(List::create_inbound_token()),
+      is_inbox: None,
     })
     .await?;
 
@@ -44,7 +49,7 @@ pub async fn create(models: &Models, user: ObjectId) -> Result<(), Error> {
     .resource
     .create(Resource {
       id: None,
-      position: 0,
+      position: rank::initial(),
       tags: tags.clone(),
       user: user.clone(),
       list: list.id.clone().unwrap(),
@@ -61,6 +66,21 @@ pub async fn create(models: &Models, user: ObjectId) -> Result<(), Error> {
       length: None,
       publisher: None,
       populated_at: None,
+      refresh_attempted_at: None,
+      signature_indexed_at: None,
+      annotations_text: None,
+      estimated_reading_time: None,
+      progress: None,
+      snoozed_until: None,
+      review: None,
+      archived_at: None,
+      favicon: None,
+      kind: None,
+      media: None,
+      health: None,
+      last_checked_at: None,
+      edited_fields: Some(vec![]),
+      suggested_tags: None,
     })
     .await?;
 
@@ -1,4 +1,5 @@
 use chrono::ParseError;
+use chrono::TimeZone;
 use wither::bson::DateTime;
 
 pub fn to_rfc3339(date: DateTime) -> String {
@@ -27,3 +28,9 @@ pub fn from_ymd(ymd: &str) -> Result<DateTime, ParseError> {
 pub fn now() -> DateTime {
   chrono::Utc::now().into()
 }
+
+// Unix timestamp in seconds.
+pub fn from_timestamp(timestamp: i64) -> Option<DateTime> {
+  let date = chrono::Utc.timestamp_opt(timestamp, 0).single()?;
+  Some(date.into())
+}
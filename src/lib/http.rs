@@ -0,0 +1,161 @@
+use actix_web::web;
+use encoding_rs::Encoding;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs};
+use url::Url;
+
+use crate::errors::Error;
+
+const MAX_REDIRECTS: usize = 10;
+
+// Returns true when every address the URL host resolves to is publicly
+// routable. Used before requesting URLs provided by users, this way loopback,
+// private network and cloud metadata addresses can not be reached.
+pub async fn is_public_url(url: &Url) -> bool {
+  if url.scheme() != "http" && url.scheme() != "https" {
+    return false;
+  }
+
+  let host = match url.host_str() {
+    Some(host) => host
+      .trim_start_matches('[')
+      .trim_end_matches(']')
+      .to_owned(),
+    None => return false,
+  };
+  let port = url.port_or_known_default().unwrap_or(80);
+
+  let addresses = web::block(move || {
+    (host.as_str(), port)
+      .to_socket_addrs()
+      .map(|addresses| addresses.collect::<Vec<_>>())
+  })
+  .await;
+
+  match addresses {
+    Ok(addresses) if !addresses.is_empty() => addresses
+      .iter()
+      .all(|address| is_public_address(&address.ip())),
+    _ => false,
+  }
+}
+
+// Requests the URL following its redirects, the client must be built with
+// the redirect policy set to none. Returns None when the URL or one of its
+// redirects points to a non public address.
+pub async fn send_public(
+  client: &reqwest::Client,
+  method: reqwest::Method,
+  url: &Url,
+) -> Result<Option<(Url, reqwest::Response)>, Error> {
+  let mut url = url.clone();
+
+  for _ in 0..=MAX_REDIRECTS {
+    if !is_public_url(&url).await {
+      debug!("Skipping request to non public URL {}", url);
+      return Ok(None);
+    }
+
+    let response = client.request(method.clone(), url.as_str()).send().await?;
+    if !response.status().is_redirection() {
+      return Ok(Some((url, response)));
+    }
+
+    let location = response
+      .headers()
+      .get(reqwest::header::LOCATION)
+      .and_then(|location| location.to_str().ok())
+      .and_then(|location| url.join(location).ok());
+
+    url = match location {
+      Some(location) => location,
+      None => return Ok(None),
+    };
+  }
+
+  Ok(None)
+}
+
+pub fn is_public_address(address: &IpAddr) -> bool {
+  match address {
+    IpAddr::V4(address) => is_public_ipv4(address),
+    IpAddr::V6(address) => match address.to_ipv4_mapped() {
+      Some(address) => is_public_ipv4(&address),
+      None => is_public_ipv6(address),
+    },
+  }
+}
+
+fn is_public_ipv4(address: &Ipv4Addr) -> bool {
+  let octets = address.octets();
+  // 100.64.0.0/10 is the shared address space used by carrier-grade NATs.
+  let is_shared = octets[0] == 100 && (octets[1] & 0b1100_0000) == 64;
+  // 0.0.0.0/8 and the reserved 240.0.0.0/4 range.
+  let is_reserved = octets[0] == 0 || octets[0] >= 240;
+
+  !(address.is_private()
+    || address.is_loopback()
+    || address.is_link_local()
+    || address.is_broadcast()
+    || address.is_documentation()
+    || address.is_unspecified()
+    || address.is_multicast()
+    || is_shared
+    || is_reserved)
+}
+
+fn is_public_ipv6(address: &Ipv6Addr) -> bool {
+  let first_segment = address.segments()[0];
+  // fc00::/7 unique local addresses.
+  let is_unique_local = (first_segment & 0xfe00) == 0xfc00;
+  // fe80::/10 link local addresses.
+  let is_link_local = (first_segment & 0xffc0) == 0xfe80;
+
+  !(address.is_loopback()
+    || address.is_unspecified()
+    || address.is_multicast()
+    || is_unique_local
+    || is_link_local)
+}
+
+// Reads the response body, returns None when it is larger than the limit.
+// The body is read in chunks, this way larger bodies are not buffered.
+pub async fn read_body(
+  mut response: reqwest::Response,
+  limit: usize,
+) -> Result<Option<Vec<u8>>, Error> {
+  let is_too_large = response
+    .content_length()
+    .map(|length| length as usize > limit)
+    .unwrap_or(false);
+
+  if is_too_large {
+    return Ok(None);
+  }
+
+  let mut body = vec![];
+  while let Some(chunk) = response.chunk().await? {
+    if body.len() + chunk.len() > limit {
+      return Ok(None);
+    }
+    body.extend_from_slice(&chunk);
+  }
+
+  Ok(Some(body))
+}
+
+// Decodes the body using the charset of the content type, defaults to UTF-8.
+pub fn decode_text(body: &[u8], content_type: Option<&str>) -> String {
+  let encoding = content_type
+    .and_then(|content_type| {
+      content_type
+        .split(';')
+        .filter_map(|parameter| parameter.trim().split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        .map(|(_, charset)| charset.trim().trim_matches('"').to_owned())
+    })
+    .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+    .unwrap_or(encoding_rs::UTF_8);
+
+  let (text, _, _) = encoding.decode(body);
+  text.into_owned()
+}
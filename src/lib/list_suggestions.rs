@@ -0,0 +1,74 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::lib::tag_suggestions::tokenize;
+
+const MAX_SUGGESTIONS: usize = 3;
+
+// Returns the indexes of the lists that best match the content. Lists are
+// documents composed by their title, description, tags and the titles of
+// their latest resources. Words shared with the content are weighted by their
+// inverse document frequency among the lists, so words found in every list
+// do not decide the ranking.
+pub fn suggest(content: &str, lists: &[String]) -> Vec<usize> {
+  let keywords = tokenize(content).into_iter().collect::<HashSet<String>>();
+  if keywords.is_empty() {
+    return vec![];
+  }
+
+  let documents = lists
+    .iter()
+    .map(|list| {
+      let mut frequencies: HashMap<String, f64> = HashMap::new();
+      for word in tokenize(list) {
+        *frequencies.entry(word).or_insert(0.0) += 1.0;
+      }
+      frequencies
+    })
+    .collect::<Vec<HashMap<String, f64>>>();
+
+  let inverse_document_frequency = |word: &str| {
+    let count = documents
+      .iter()
+      .filter(|document| document.contains_key(word))
+      .count();
+    ((documents.len() as f64 + 1.0) / (count as f64 + 1.0)).ln() + 1.0
+  };
+
+  let mut scores = documents
+    .iter()
+    .enumerate()
+    .filter_map(|(index, document)| {
+      if document.is_empty() {
+        return None;
+      }
+
+      let score = keywords
+        .iter()
+        .filter_map(|word| {
+          let frequency = document.get(word)?;
+          Some((1.0 + frequency.ln()) * inverse_document_frequency(word))
+        })
+        .sum::<f64>();
+
+      // Lists with many resources contain more words, the score is
+      // normalized so they are not always suggested.
+      let score = score / (document.len() as f64).sqrt();
+      match score > 0.0 {
+        true => Some((index, score)),
+        false => None,
+      }
+    })
+    .collect::<Vec<(usize, f64)>>();
+
+  scores.sort_by(|(a_index, a), (b_index, b)| {
+    b.partial_cmp(a)
+      .unwrap_or(std::cmp::Ordering::Equal)
+      .then_with(|| a_index.cmp(b_index))
+  });
+
+  scores
+    .into_iter()
+    .take(MAX_SUGGESTIONS)
+    .map(|(index, _)| index)
+    .collect()
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+// Number of hash functions of a signature. Signatures are split in bands of
+// rows, two documents become candidates when at least one band is equal.
+// With 32 bands of 2 rows, documents with a similarity of 0.3 have a 95%
+// probability of sharing a band, documents with a similarity of 0.1 have a
+// 28% probability.
+const SIGNATURE_SIZE: usize = 64;
+const ROWS_PER_BAND: usize = 2;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+// The hashes are stored in the database, std hashers are not used because
+// their output is not guaranteed to be stable between Rust releases.
+fn fnv1a(value: &str) -> u64 {
+  value.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+    (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+  })
+}
+
+fn splitmix64(value: u64) -> u64 {
+  let mut value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+  value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  value ^ (value >> 31)
+}
+
+// Returns the MinHash signature of the set of features, None when the set is
+// empty.
+pub fn signature(features: &HashSet<String>) -> Option<Vec<i64>> {
+  if features.is_empty() {
+    return None;
+  }
+
+  let hashes = features
+    .iter()
+    .map(|feature| fnv1a(feature))
+    .collect::<Vec<u64>>();
+
+  let signature = (0..SIGNATURE_SIZE as u64)
+    .map(|seed| {
+      let seed = splitmix64(seed);
+      hashes
+        .iter()
+        .map(|hash| splitmix64(hash ^ seed) as u32)
+        .min()
+        .unwrap_or(u32::MAX) as i64
+    })
+    .collect();
+
+  Some(signature)
+}
+
+// Returns the locality sensitive hashing bands of the signature. The band
+// index is part of the hash so equal rows in different bands do not match.
+pub fn bands(signature: &[i64]) -> Vec<i64> {
+  signature
+    .chunks(ROWS_PER_BAND)
+    .enumerate()
+    .map(|(index, rows)| {
+      let band = rows.iter().fold(splitmix64(index as u64), |hash, row| {
+        splitmix64(hash ^ *row as u64)
+      });
+      band as i64
+    })
+    .collect()
+}
+
+// Estimates the Jaccard similarity of the sets represented by the signatures.
+pub fn similarity(a: &[i64], b: &[i64]) -> f64 {
+  if a.is_empty() || a.len() != b.len() {
+    return 0.0;
+  }
+
+  let matches = a.iter().zip(b).filter(|(a, b)| a == b).count();
+  matches as f64 / a.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_features(range: std::ops::Range<usize>) -> HashSet<String> {
+    range.map(|index| format!("word{}", index)).collect()
+  }
+
+  #[test]
+  fn fnv1a_is_stable() {
+    assert_eq!(fnv1a(""), FNV_OFFSET_BASIS);
+    assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+  }
+
+  #[test]
+  fn empty_features_have_no_signature() {
+    assert_eq!(signature(&HashSet::new()), None);
+  }
+
+  #[test]
+  fn signatures_are_deterministic() {
+    let features = create_features(0..20);
+    let a = signature(&features).unwrap();
+    let b = signature(&features).unwrap();
+    assert_eq!(a.len(), SIGNATURE_SIZE);
+    assert_eq!(a, b);
+  }
+
+  #[test]
+  fn similarity_estimates_the_jaccard_similarity() {
+    let a = signature(&create_features(0..100)).unwrap();
+    assert_eq!(similarity(&a, &a), 1.0);
+
+    // 50 shared features out of 150, a Jaccard similarity of 0.33.
+    let b = signature(&create_features(50..150)).unwrap();
+    let estimate = similarity(&a, &b);
+    assert!((estimate - 1.0 / 3.0).abs() < 0.2, "estimate {}", estimate);
+
+    let c = signature(&create_features(1000..1100)).unwrap();
+    assert!(similarity(&a, &c) < 0.1);
+  }
+
+  #[test]
+  fn similarity_of_invalid_signatures_is_zero() {
+    let a = signature(&create_features(0..10)).unwrap();
+    assert_eq!(similarity(&[], &[]), 0.0);
+    assert_eq!(similarity(&a, &a[..10]), 0.0);
+  }
+
+  #[test]
+  fn similar_signatures_share_bands() {
+    let a = signature(&create_features(0..100)).unwrap();
+    let b = signature(&create_features(10..110)).unwrap();
+    let c = signature(&create_features(1000..1100)).unwrap();
+    let (a, b, c) = (bands(&a), bands(&b), bands(&c));
+
+    assert_eq!(a.len(), SIGNATURE_SIZE / ROWS_PER_BAND);
+    assert!(a.iter().any(|band| b.contains(band)));
+    assert!(!a.iter().any(|band| c.contains(band)));
+  }
+
+  #[test]
+  fn equal_rows_in_different_bands_do_not_match() {
+    let bands = bands(&[1, 2, 1, 2]);
+    assert_eq!(bands.len(), 2);
+    assert_ne!(bands[0], bands[1]);
+  }
+}
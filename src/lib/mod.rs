@@ -1,8 +1,17 @@
+pub mod backup;
 pub mod create_demo_data_for_user;
 pub mod date;
 pub mod google;
+pub mod http;
 pub mod id;
+pub mod list_suggestions;
+pub mod minhash;
+pub mod multipart;
+pub mod opml;
 pub mod pagination;
+pub mod rank;
+pub mod reading_time;
 pub mod serde;
+pub mod tag_suggestions;
 pub mod token;
 pub mod util;
@@ -0,0 +1,297 @@
+use encoding_rs::Encoding;
+use encoding_rs::UTF_8;
+use std::collections::HashMap;
+
+// actix-multipart is not used, the version compatible with actix-web 3 is
+// not available. Bodies are small form posts, E.g. SendGrid Inbound Parse
+// webhooks, they are parsed in memory.
+
+// Form field with the charset of each field, set by SendGrid as a JSON
+// object E.g. {"text":"iso-8859-1","html":"UTF-8"}.
+const CHARSETS_FIELD: &str = "charsets";
+
+struct Part<'a> {
+  name: String,
+  value: &'a [u8],
+  charset: Option<String>,
+  transfer_encoding: Option<String>,
+}
+
+// Parses the text fields of a multipart/form-data body, file parts are
+// skipped. Values are decoded using the part transfer encoding and charset,
+// falling back to the charsets field. Returns None when the content type has
+// no boundary.
+pub fn parse(content_type: &str, body: &[u8]) -> Option<HashMap<String, String>> {
+  let boundary = get_boundary(content_type)?;
+  let delimiter = format!("--{}", boundary);
+  let parts = split(body, delimiter.as_bytes())
+    .into_iter()
+    .filter_map(parse_part)
+    .collect::<Vec<Part>>();
+
+  let charsets = parts
+    .iter()
+    .find(|part| part.name == CHARSETS_FIELD)
+    .and_then(|part| serde_json::from_slice::<HashMap<String, String>>(part.value).ok())
+    .unwrap_or_default();
+
+  let fields = parts
+    .into_iter()
+    .map(|part| {
+      let charset = part
+        .charset
+        .as_deref()
+        .or_else(|| charsets.get(&part.name).map(String::as_str));
+      let value = decode_transfer_encoding(part.value, part.transfer_encoding.as_deref());
+      let value = decode_charset(&value, charset);
+      (part.name, value)
+    })
+    .collect();
+
+  Some(fields)
+}
+
+// Splits the body by the delimiter. The first element is the preamble and
+// the last one the epilogue after the closing "--" delimiter, both are
+// skipped.
+fn split<'a>(body: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+  let mut parts = vec![];
+  let mut rest = match find(body, delimiter) {
+    Some(index) => &body[index + delimiter.len()..],
+    None => return parts,
+  };
+
+  while !rest.starts_with(b"--") {
+    let (part, next) = match find(rest, delimiter) {
+      Some(index) => (&rest[..index], &rest[index + delimiter.len()..]),
+      // The body has no closing delimiter.
+      None => (rest, &b"--"[..]),
+    };
+
+    parts.push(part);
+    rest = next;
+  }
+
+  parts
+}
+
+fn parse_part(part: &[u8]) -> Option<Part<'_>> {
+  let part = strip_line_break_prefix(part);
+  let (headers, value) = split_part(part)?;
+  let headers = String::from_utf8_lossy(headers);
+
+  let disposition = get_header(&headers, "content-disposition")?;
+  if get_parameter(disposition, "filename").is_some() {
+    return None;
+  }
+
+  let name = get_parameter(disposition, "name")?;
+  let charset = get_header(&headers, "content-type")
+    .and_then(|content_type| get_parameter(content_type, "charset"));
+  let transfer_encoding = get_header(&headers, "content-transfer-encoding")
+    .map(|transfer_encoding| transfer_encoding.trim().to_lowercase());
+
+  Some(Part {
+    name,
+    value: strip_line_break_suffix(value),
+    charset,
+    transfer_encoding,
+  })
+}
+
+fn split_part(part: &[u8]) -> Option<(&[u8], &[u8])> {
+  if let Some(index) = find(part, b"\r\n\r\n") {
+    return Some((&part[..index], &part[index + 4..]));
+  }
+
+  find(part, b"\n\n").map(|index| (&part[..index], &part[index + 2..]))
+}
+
+fn get_header<'a>(headers: &'a str, name: &str) -> Option<&'a str> {
+  headers.lines().find_map(|line| {
+    let (key, value) = line.split_once(':')?;
+    match key.trim().eq_ignore_ascii_case(name) {
+      true => Some(value),
+      false => None,
+    }
+  })
+}
+
+fn decode_transfer_encoding(value: &[u8], transfer_encoding: Option<&str>) -> Vec<u8> {
+  match transfer_encoding {
+    Some("base64") => {
+      let value = value
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect::<Vec<u8>>();
+      base64::decode(&value).unwrap_or_else(|_| value.to_vec())
+    }
+    Some("quoted-printable") => decode_quoted_printable(value),
+    // 7bit, 8bit and binary values are not encoded.
+    _ => value.to_vec(),
+  }
+}
+
+// Decodes "=XX" escapes and removes the soft line breaks "=\r\n". Invalid
+// escapes are kept as they are.
+fn decode_quoted_printable(value: &[u8]) -> Vec<u8> {
+  let mut decoded = Vec::with_capacity(value.len());
+  let mut index = 0;
+
+  while index < value.len() {
+    let byte = value[index];
+    if byte != b'=' {
+      decoded.push(byte);
+      index += 1;
+      continue;
+    }
+
+    let rest = &value[index + 1..];
+    if rest.starts_with(b"\r\n") {
+      index += 3;
+    } else if rest.starts_with(b"\n") {
+      index += 2;
+    } else if let Some(byte) = rest.get(..2).and_then(parse_hex_byte) {
+      decoded.push(byte);
+      index += 3;
+    } else {
+      decoded.push(byte);
+      index += 1;
+    }
+  }
+
+  decoded
+}
+
+fn parse_hex_byte(hex: &[u8]) -> Option<u8> {
+  let hex = std::str::from_utf8(hex).ok()?;
+  u8::from_str_radix(hex, 16).ok()
+}
+
+// Unknown charsets are decoded as UTF-8, invalid sequences are replaced.
+fn decode_charset(value: &[u8], charset: Option<&str>) -> String {
+  let encoding = charset
+    .and_then(|charset| Encoding::for_label(charset.trim().as_bytes()))
+    .unwrap_or(UTF_8);
+  let (value, _, _) = encoding.decode(value);
+  value.into_owned()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack
+    .windows(needle.len())
+    .position(|window| window == needle)
+}
+
+fn strip_line_break_prefix(value: &[u8]) -> &[u8] {
+  let value = value.strip_prefix(b"\r\n").unwrap_or(value);
+  value.strip_prefix(b"\n").unwrap_or(value)
+}
+
+fn strip_line_break_suffix(value: &[u8]) -> &[u8] {
+  let value = value.strip_suffix(b"\r\n").unwrap_or(value);
+  value.strip_suffix(b"\n").unwrap_or(value)
+}
+
+fn get_boundary(content_type: &str) -> Option<String> {
+  let is_multipart = content_type
+    .trim()
+    .to_lowercase()
+    .starts_with("multipart/form-data");
+
+  if !is_multipart {
+    return None;
+  }
+
+  get_parameter(content_type, "boundary").filter(|boundary| !boundary.is_empty())
+}
+
+// Gets a parameter of a header value, E.g. the name of
+// `form-data; name="text"`.
+fn get_parameter(header: &str, name: &str) -> Option<String> {
+  header.split(';').skip(1).find_map(|parameter| {
+    let (key, value) = parameter.split_once('=')?;
+    if !key.trim().eq_ignore_ascii_case(name) {
+      return None;
+    }
+
+    Some(value.trim().trim_matches('"').to_owned())
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CONTENT_TYPE: &str = "multipart/form-data; boundary=xYzZY";
+
+  #[test]
+  fn parses_text_fields_and_skips_files() {
+    let body = b"--xYzZY\r\n\
+      Content-Disposition: form-data; name=\"subject\"\r\n\r\n\
+      Read later\r\n\
+      --xYzZY\r\n\
+      Content-Disposition: form-data; name=\"attachment1\"; filename=\"a.bin\"\r\n\
+      Content-Type: application/octet-stream\r\n\r\n\
+      \xff\xfe\x00binary\r\n\
+      --xYzZY--\r\n";
+
+    let fields = parse(CONTENT_TYPE, body).unwrap();
+
+    assert_eq!(
+      fields.get("subject").map(String::as_str),
+      Some("Read later")
+    );
+    assert!(!fields.contains_key("attachment1"));
+  }
+
+  #[test]
+  fn decodes_values_with_the_charsets_field() {
+    let body = b"--xYzZY\r\n\
+      Content-Disposition: form-data; name=\"charsets\"\r\n\r\n\
+      {\"text\":\"iso-8859-1\"}\r\n\
+      --xYzZY\r\n\
+      Content-Disposition: form-data; name=\"text\"\r\n\r\n\
+      Caf\xe9 https://example.com\r\n\
+      --xYzZY--\r\n";
+
+    let fields = parse(CONTENT_TYPE, body).unwrap();
+
+    assert_eq!(
+      fields.get("text").map(String::as_str),
+      Some("Café https://example.com")
+    );
+  }
+
+  #[test]
+  fn decodes_transfer_encodings() {
+    let body = b"--xYzZY\r\n\
+      Content-Disposition: form-data; name=\"text\"\r\n\
+      Content-Transfer-Encoding: quoted-printable\r\n\r\n\
+      Caf=C3=A9 https://example.com/a=\r\n\
+      b\r\n\
+      --xYzZY\r\n\
+      Content-Disposition: form-data; name=\"html\"\r\n\
+      Content-Transfer-Encoding: base64\r\n\r\n\
+      PGEgaHJlZj0i\r\n\
+      aHR0cHM6Ly9leGFtcGxlLmNvbSI+PC9hPg==\r\n\
+      --xYzZY--\r\n";
+
+    let fields = parse(CONTENT_TYPE, body).unwrap();
+
+    assert_eq!(
+      fields.get("text").map(String::as_str),
+      Some("Café https://example.com/ab")
+    );
+    assert_eq!(
+      fields.get("html").map(String::as_str),
+      Some("<a href=\"https://example.com\"></a>")
+    );
+  }
+
+  #[test]
+  fn bodies_without_boundary_are_not_parsed() {
+    assert!(parse("application/json", b"{}").is_none());
+  }
+}
@@ -0,0 +1,83 @@
+use maud::{html, Markup, PreEscaped};
+use roxmltree::Node;
+
+// Feed outline of an OPML file.
+#[derive(Debug, Clone)]
+pub struct Feed {
+  pub url: String,
+  pub title: Option<String>,
+  // Folder path of the feed, E.g. ["News", "Tech"].
+  pub folder: Vec<String>,
+}
+
+// Group of feeds exported as a folder outline.
+pub struct Folder {
+  pub title: String,
+  pub feeds: Vec<Feed>,
+}
+
+// Feeds are outlines with a xmlUrl attribute, outlines without it are folders
+// that group the nested feeds.
+pub fn parse(opml: &str) -> Result<Vec<Feed>, roxmltree::Error> {
+  let document = roxmltree::Document::parse(opml.trim())?;
+  let mut feeds = vec![];
+
+  let body = document
+    .root_element()
+    .children()
+    .find(|node| node.has_tag_name("body"));
+
+  if let Some(body) = body {
+    collect_feeds(&body, &mut vec![], &mut feeds);
+  }
+
+  Ok(feeds)
+}
+
+fn collect_feeds(node: &Node, folder: &mut Vec<String>, feeds: &mut Vec<Feed>) {
+  for outline in node.children().filter(|node| node.has_tag_name("outline")) {
+    let title = outline
+      .attribute("title")
+      .or_else(|| outline.attribute("text"))
+      .map(|title| title.trim())
+      .filter(|title| !title.is_empty())
+      .map(ToOwned::to_owned);
+
+    match outline.attribute("xmlUrl") {
+      Some(url) => feeds.push(Feed {
+        url: url.trim().to_owned(),
+        title,
+        folder: folder.clone(),
+      }),
+      None => match title {
+        Some(title) => {
+          folder.push(title);
+          collect_feeds(&outline, folder, feeds);
+          folder.pop();
+        }
+        None => collect_feeds(&outline, folder, feeds),
+      },
+    }
+  }
+}
+
+pub fn create(title: &str, folders: &[Folder]) -> Markup {
+  html! {
+      (PreEscaped(r#"<?xml version="1.0" encoding="UTF-8"?>"#))
+      opml version="2.0" {
+          head {
+              title { (title) }
+          }
+          body {
+              @for folder in folders {
+                  outline text=(folder.title) title=(folder.title) {
+                      @for feed in &folder.feeds {
+                          @let text = feed.title.as_deref().unwrap_or(&feed.url);
+                          outline type="rss" text=(text) title=(text) xmlUrl=(feed.url) {}
+                      }
+                  }
+              }
+          }
+      }
+  }
+}
@@ -0,0 +1,280 @@
+// Lexicographic rank keys used to order the resources of a list (LexoRank
+// style). Ranks are base 36 strings that are compared lexicographically, this
+// way a resource can be inserted between two other resources by only updating
+// the inserted resource.
+//
+// A rank is composed by a fixed width head followed by an optional tail. New
+// resources are appended by incrementing the head by a fixed step, while
+// insertions between two ranks compute the midpoint and can make the tail
+// grow. Once a rank gets too long, the list ranks should be rebalanced.
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u128 = 36;
+const HEAD_WIDTH: usize = 6;
+// 36^3, leaves room for ~46K consecutive appends from the initial rank.
+const STEP: u128 = 46_656;
+
+// Ranks longer than this value should trigger a list rebalance.
+pub const MAX_LENGTH: usize = 16;
+
+pub fn initial() -> String {
+  let max = BASE.pow(HEAD_WIDTH as u32);
+  encode(max / 2, HEAD_WIDTH)
+}
+
+pub fn after(rank: &str) -> String {
+  let max = BASE.pow(HEAD_WIDTH as u32);
+  let head = decode_head(rank);
+
+  if head + STEP < max {
+    return encode(head + STEP, HEAD_WIDTH);
+  }
+
+  midpoint(rank.as_bytes(), None)
+}
+
+pub fn before(rank: &str) -> String {
+  let head = decode_head(rank);
+
+  if head > STEP {
+    return encode(head - STEP, HEAD_WIDTH);
+  }
+
+  midpoint(b"", Some(rank.as_bytes()))
+}
+
+pub fn between(previous: Option<&str>, next: Option<&str>) -> String {
+  match (previous, next) {
+    (None, None) => initial(),
+    (Some(previous), None) => after(previous),
+    (None, Some(next)) => before(next),
+    (Some(previous), Some(next)) => midpoint(previous.as_bytes(), Some(next.as_bytes())),
+  }
+}
+
+// Returns `count` consecutive ranks after the specified rank.
+pub fn sequence(previous: Option<&str>, count: usize) -> Vec<String> {
+  let mut ranks: Vec<String> = Vec::with_capacity(count);
+
+  for _ in 0..count {
+    let rank = match ranks.last() {
+      Some(rank) => after(rank),
+      None => between(previous, None),
+    };
+    ranks.push(rank);
+  }
+
+  ranks
+}
+
+// Returns `count` consecutive ranks separated by the append step. Ranks start
+// at the first quarter of the available space, leaving room to prepend and
+// append resources. Used to migrate and rebalance the resources of a list.
+pub fn distribute(count: usize) -> Vec<String> {
+  let count = count as u128;
+  let mut width = HEAD_WIDTH;
+  while BASE.pow(width as u32) < 2 * (count + 1) * STEP {
+    width += 1;
+  }
+
+  let start = BASE.pow(width as u32) / 4;
+  (0..count)
+    .map(|index| encode(start + index * STEP, width))
+    .collect()
+}
+
+pub fn needs_rebalance(rank: &str) -> bool {
+  rank.len() > MAX_LENGTH
+}
+
+fn to_digit(char: u8) -> u128 {
+  DIGITS
+    .iter()
+    .position(|digit| *digit == char)
+    .expect("Rank to contain valid base 36 digits") as u128
+}
+
+fn decode_head(rank: &str) -> u128 {
+  let bytes = rank.as_bytes();
+  (0..HEAD_WIDTH).fold(0, |head, index| {
+    let digit = bytes.get(index).map(|char| to_digit(*char)).unwrap_or(0);
+    head * BASE + digit
+  })
+}
+
+// Encodes the value using a fixed width, trailing zeros are removed because
+// they do not change the rank order and would prevent computing a midpoint.
+fn encode(mut value: u128, width: usize) -> String {
+  let mut chars = vec![b'0'; width];
+  for index in (0..width).rev() {
+    chars[index] = DIGITS[(value % BASE) as usize];
+    value /= BASE;
+  }
+
+  while chars.last() == Some(&b'0') {
+    chars.pop();
+  }
+
+  String::from_utf8(chars).expect("Rank to be a valid UTF-8 string")
+}
+
+// Computes a rank between `previous` and `next` where `previous` < `next`.
+// Ranks are handled as base 36 fractions, a missing `next` stands for 1.
+fn midpoint(previous: &[u8], next: Option<&[u8]>) -> String {
+  if let Some(next) = next {
+    // Remove the longest common prefix, padding the previous rank with zeros.
+    let mut index = 0;
+    while index < next.len() && previous.get(index).copied().unwrap_or(b'0') == next[index] {
+      index += 1;
+    }
+
+    if index > 0 {
+      let prefix = String::from_utf8_lossy(&next[..index]).to_string();
+      let previous = previous.get(index..).unwrap_or(&[]);
+      return prefix + &midpoint(previous, Some(&next[index..]));
+    }
+  }
+
+  let previous_digit = previous.first().map(|char| to_digit(*char)).unwrap_or(0);
+  let next_digit = next
+    .and_then(|next| next.first())
+    .map(|char| to_digit(*char))
+    .unwrap_or(BASE);
+
+  if next_digit.saturating_sub(previous_digit) > 1 {
+    let digit = (previous_digit + next_digit).div_ceil(2);
+    return (DIGITS[digit as usize] as char).to_string();
+  }
+
+  // The first digits are consecutive.
+  match next {
+    Some(next) if next.len() > 1 => (next[0] as char).to_string(),
+    _ => {
+      let digit = DIGITS[previous_digit as usize] as char;
+      let previous = previous.get(1..).unwrap_or(&[]);
+      format!("{}{}", digit, midpoint(previous, None))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_ordered(ranks: &[String]) {
+    for pair in ranks.windows(2) {
+      assert!(
+        pair[0] < pair[1],
+        "{} should be before {}",
+        pair[0],
+        pair[1]
+      );
+    }
+  }
+
+  #[test]
+  fn initial_is_the_middle_of_the_head_space() {
+    assert_eq!(initial(), "i");
+    assert_eq!(decode_head(&initial()), BASE.pow(HEAD_WIDTH as u32) / 2);
+  }
+
+  #[test]
+  fn after_and_before_step_the_head() {
+    let rank = initial();
+    assert_ordered(&[before(&rank), rank.clone(), after(&rank)]);
+    assert_eq!(decode_head(&after(&rank)) - decode_head(&rank), STEP);
+    assert_eq!(decode_head(&rank) - decode_head(&before(&rank)), STEP);
+  }
+
+  #[test]
+  fn after_the_last_head_extends_the_rank() {
+    let rank = "zzz";
+    let next = after(rank);
+    assert!(next.as_str() > rank);
+    assert!(!next.ends_with('0'));
+  }
+
+  #[test]
+  fn before_the_first_head_extends_the_rank() {
+    let rank = "0001";
+    let previous = before(rank);
+    assert!(previous.as_str() < rank);
+    assert!(!previous.is_empty());
+  }
+
+  #[test]
+  fn between_handles_missing_bounds() {
+    assert_eq!(between(None, None), initial());
+    assert_eq!(between(Some("i"), None), after("i"));
+    assert_eq!(between(None, Some("i")), before("i"));
+  }
+
+  #[test]
+  fn between_computes_a_rank_in_the_middle() {
+    let cases = [
+      ("a", "c"),
+      ("a", "b"),
+      ("a", "a1"),
+      ("az", "b"),
+      ("i", "i0001"),
+      ("0001", "0002"),
+      ("hzzzzz", "i"),
+    ];
+
+    for (previous, next) in cases.iter() {
+      let rank = between(Some(previous), Some(next));
+      assert_ordered(&[previous.to_string(), rank.clone(), next.to_string()]);
+      assert!(!rank.ends_with('0'), "{} has trailing zeros", rank);
+    }
+  }
+
+  #[test]
+  fn repeated_insertions_grow_until_a_rebalance_is_needed() {
+    let previous = initial();
+    let mut next = after(&previous);
+
+    let mut insertions = 0;
+    while !needs_rebalance(&next) {
+      let rank = between(Some(&previous), Some(&next));
+      assert_ordered(&[previous.clone(), rank.clone(), next.clone()]);
+      next = rank;
+      insertions += 1;
+    }
+
+    assert!(insertions > 10);
+    assert!(next.len() > MAX_LENGTH);
+  }
+
+  #[test]
+  fn sequence_returns_consecutive_ranks() {
+    let ranks = sequence(None, 5);
+    assert_eq!(ranks.len(), 5);
+    assert_eq!(ranks[0], initial());
+    assert_ordered(&ranks);
+
+    let last = ranks.last().unwrap();
+    let next = sequence(Some(last), 3);
+    assert!(next[0].as_str() > last.as_str());
+    assert_ordered(&next);
+  }
+
+  #[test]
+  fn distribute_leaves_room_around_the_ranks() {
+    let ranks = distribute(1_000);
+    assert_eq!(ranks.len(), 1_000);
+    assert_ordered(&ranks);
+    assert!(ranks.iter().all(|rank| !needs_rebalance(rank)));
+
+    let first = ranks.first().unwrap();
+    let last = ranks.last().unwrap();
+    assert!(before(first).as_str() < first.as_str());
+    assert!(after(last).as_str() > last.as_str());
+  }
+
+  #[test]
+  fn distribute_widens_the_ranks_for_large_lists() {
+    let ranks = distribute(100_000);
+    assert_ordered(&ranks);
+    assert!(ranks.iter().all(|rank| !needs_rebalance(rank)));
+  }
+}
@@ -0,0 +1,21 @@
+// Average adult silent reading speed used to estimate the reading time.
+const WORDS_PER_MINUTE: i64 = 238;
+// Average word length including the separator, used when only the content
+// length is available.
+const CHARS_PER_WORD: i64 = 6;
+
+// Returns the estimated reading time in seconds based on the resource text,
+// falling back to the content length returned by the metadata provider.
+pub fn estimate(text: Option<&str>, length: Option<i64>) -> Option<i64> {
+  let words = match (text, length) {
+    (Some(text), _) => text.split_whitespace().count() as i64,
+    (None, Some(length)) => length / CHARS_PER_WORD,
+    (None, None) => return None,
+  };
+
+  if words == 0 {
+    return None;
+  }
+
+  Some((words * 60 / WORDS_PER_MINUTE).max(1))
+}
@@ -1,4 +1,6 @@
+use serde::de::{self, Deserializer, Visitor};
 use serde::Serializer;
+use std::fmt;
 use wither::bson::{oid::ObjectId, DateTime};
 
 use crate::lib::date;
@@ -31,3 +33,88 @@ pub fn serialize_object_id_as_hex_string<S: Serializer>(
   let hex_string = id.to_hex();
   serializer.serialize_str(&hex_string)
 }
+
+// Resource positions used to be integers. Until the `migrate-positions`
+// script runs, legacy positions are read as their decimal representation,
+// which is a valid rank. MongoDB sorts numbers before strings, so legacy
+// resources keep their place at the top of the list.
+pub fn deserialize_position<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<String, D::Error> {
+  struct PositionVisitor;
+
+  impl<'de> Visitor<'de> for PositionVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+      formatter.write_str("a rank string or a legacy integer position")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<String, E> {
+      Ok(value.to_owned())
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<String, E> {
+      Ok(value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<String, E> {
+      Ok(value.max(0).to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<String, E> {
+      Ok(value.to_string())
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<String, E> {
+      Ok((value.max(0.0) as i64).to_string())
+    }
+  }
+
+  deserializer.deserialize_any(PositionVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+  use serde::Deserialize;
+  use wither::bson::oid::ObjectId;
+  use wither::bson::{self, doc};
+
+  use crate::models::resource::PrivateResource;
+
+  #[derive(Deserialize)]
+  struct Positioned {
+    #[serde(deserialize_with = "super::deserialize_position")]
+    position: String,
+  }
+
+  #[test]
+  fn deserializes_rank_and_legacy_positions() {
+    let rank: Positioned = bson::from_document(doc! { "position": "i0a" }).unwrap();
+    assert_eq!(rank.position, "i0a");
+
+    let legacy: Positioned = bson::from_document(doc! { "position": 12_i32 }).unwrap();
+    assert_eq!(legacy.position, "12");
+
+    let legacy: Positioned = bson::from_document(doc! { "position": 7_i64 }).unwrap();
+    assert_eq!(legacy.position, "7");
+  }
+
+  #[test]
+  fn private_resources_read_legacy_positions() {
+    let now = bson::Bson::DateTime(crate::lib::date::now().into());
+    let document = doc! {
+      "_id": ObjectId::new(),
+      "user": ObjectId::new(),
+      "list": ObjectId::new(),
+      "url": "https://example.com/",
+      "position": 3_i32,
+      "tags": [],
+      "created_at": now.clone(),
+      "updated_at": now
+    };
+
+    let resource: PrivateResource = bson::from_document(document).unwrap();
+    assert_eq!(resource.position, "3");
+  }
+}
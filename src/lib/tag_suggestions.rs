@@ -0,0 +1,155 @@
+use std::collections::{HashMap, HashSet};
+
+const MAX_SUGGESTIONS: usize = 5;
+const MIN_WORD_LENGTH: usize = 3;
+// Title words describe the resource better than the words of the body.
+const TITLE_WEIGHT: f64 = 3.0;
+// Tags already used by the user are preferred over new keywords to keep the
+// user vocabulary consistent.
+const VOCABULARY_WEIGHT: f64 = 2.0;
+// Keywords found only once in the content are usually noise.
+const MIN_KEYWORD_FREQUENCY: f64 = 2.0;
+
+const STOP_WORDS: &str = "\
+  about above after again against all also and any are because been before \
+  being below between both but can could did does doing down during each \
+  even every few for from further get gets got had has have having her here \
+  hers herself him himself his how however into its itself just like made \
+  make makes many may might more most much must new not now off once one \
+  only other our ours ourselves out over own really said same says see she \
+  should since some still such than that the their theirs them themselves \
+  then there these they thing things this those through too two under until \
+  use used using very want was way ways well were what when where which \
+  while who whom why will with without would yet you your yours yourself \
+  yourselves";
+
+pub struct Content<'a> {
+  pub title: Option<&'a str>,
+  pub text: Option<&'a str>,
+  pub publisher: Option<&'a str>,
+}
+
+// Splits the text in lowercase words, ignoring stop words, numbers and short
+// words.
+pub fn tokenize(text: &str) -> Vec<String> {
+  text
+    .split(|c: char| !c.is_alphanumeric())
+    .filter(|word| word.chars().count() >= MIN_WORD_LENGTH)
+    .map(|word| word.to_lowercase())
+    .filter(|word| !word.chars().all(|c| c.is_numeric()))
+    .filter(|word| {
+      !STOP_WORDS
+        .split_whitespace()
+        .any(|stop_word| stop_word == word)
+    })
+    .collect()
+}
+
+// Returns the tag suggestions for the content ranked by TF-IDF. The corpus
+// is composed by the other resources of the user and is used to compute the
+// inverse document frequency. Tags from the user vocabulary found in the
+// content are boosted, tags in the exclude list are never suggested.
+pub fn suggest(
+  content: &Content,
+  corpus: &[String],
+  vocabulary: &[String],
+  exclude: &[String],
+) -> Vec<String> {
+  let title = content.title.map(tokenize).unwrap_or_default();
+  let text = content.text.map(tokenize).unwrap_or_default();
+  // Publisher names (E.g. "medium", "nytimes") appear everywhere in the page
+  // and are not good keywords unless the user already tags with them.
+  let publisher = content
+    .publisher
+    .map(|publisher| {
+      let publisher = publisher.split('.').next().unwrap_or(publisher);
+      tokenize(publisher)
+    })
+    .unwrap_or_default();
+
+  let mut frequencies: HashMap<&str, f64> = HashMap::new();
+  for word in &text {
+    *frequencies.entry(word).or_insert(0.0) += 1.0;
+  }
+  for word in &title {
+    *frequencies.entry(word).or_insert(0.0) += TITLE_WEIGHT;
+  }
+
+  let total = frequencies.values().sum::<f64>();
+  if total == 0.0 {
+    return vec![];
+  }
+
+  let documents = corpus
+    .iter()
+    .map(|document| tokenize(document).into_iter().collect::<HashSet<String>>())
+    .collect::<Vec<HashSet<String>>>();
+  let inverse_document_frequency = |word: &str| {
+    let count = documents
+      .iter()
+      .filter(|document| document.contains(word))
+      .count();
+    ((documents.len() as f64 + 1.0) / (count as f64 + 1.0)).ln() + 1.0
+  };
+
+  let mut scores: HashMap<String, f64> = HashMap::new();
+
+  for (word, frequency) in &frequencies {
+    if *frequency < MIN_KEYWORD_FREQUENCY || publisher.iter().any(|name| name == word) {
+      continue;
+    }
+    let score = frequency / total * inverse_document_frequency(word);
+    scores.insert(word.to_string(), score);
+  }
+
+  // Vocabulary tags can be composed by several words (E.g. machine-learning),
+  // they match when all of their words are found in the content.
+  for tag in vocabulary {
+    let words = tokenize(tag);
+    if words.is_empty() {
+      continue;
+    }
+
+    let is_publisher = words.iter().all(|word| publisher.contains(word));
+    let frequency = words
+      .iter()
+      .map(|word| frequencies.get(word.as_str()).copied().unwrap_or(0.0))
+      .fold(f64::INFINITY, f64::min);
+
+    if frequency == 0.0 && !is_publisher {
+      continue;
+    }
+
+    let idf = words
+      .iter()
+      .map(|word| inverse_document_frequency(word))
+      .sum::<f64>()
+      / words.len() as f64;
+    let score = (frequency.max(1.0) / total * idf) * VOCABULARY_WEIGHT;
+    let entry = scores.entry(tag.clone()).or_insert(0.0);
+    *entry = entry.max(score);
+
+    // The single words of a composed tag should not be suggested too.
+    if words.len() > 1 {
+      for word in &words {
+        scores.remove(word);
+      }
+    }
+  }
+
+  let mut suggestions = scores
+    .into_iter()
+    .filter(|(tag, _)| !exclude.contains(tag))
+    .collect::<Vec<(String, f64)>>();
+  suggestions.sort_by(|(a_tag, a), (b_tag, b)| {
+    b.partial_cmp(a)
+      .unwrap_or(std::cmp::Ordering::Equal)
+      .then_with(|| a_tag.cmp(b_tag))
+  });
+
+  suggestions
+    .into_iter()
+    .take(MAX_SUGGESTIONS)
+    .map(|(tag, _)| tag)
+    .collect()
+}
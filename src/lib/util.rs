@@ -50,6 +50,14 @@ pub fn parse_url(url: &str) -> Result<Url, Error> {
   Ok(url)
 }
 
+// Only web URLs are rendered as links and requested by the metadata
+// providers, other schemes like javascript: or file: are rejected.
+pub fn is_web_url(url: &str) -> bool {
+  Url::parse(url)
+    .map(|url| url.scheme() == "http" || url.scheme() == "https")
+    .unwrap_or(false)
+}
+
 pub fn parse_query_string<'a, T>(query_string: &'a str) -> Result<T, Error>
 where
   T: Deserialize<'a>,
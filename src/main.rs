@@ -1,23 +1,31 @@
 mod actors;
+mod archiver;
 mod auth;
 mod context;
 mod database;
 mod emails;
 mod errors;
+mod exporters;
+mod health;
+mod importers;
 mod jobs;
 mod lib;
 mod logger;
 mod mailer;
+mod metadata;
 mod models;
+mod pages;
 mod rabbit_mq;
 mod routes;
 mod scripts;
 mod settings;
+mod sources;
 mod thirdparty;
 
 use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpServer};
 use std::env;
+use std::sync::Arc;
 #[macro_use]
 extern crate log;
 
@@ -56,12 +64,47 @@ async fn main() {
     Err(_) => panic!("Failed to setup mailer"),
   };
 
+  let archiver = match archiver::Archiver::new(&settings) {
+    Ok(value) => value,
+    Err(err) => panic!("Failed to setup archiver. Error: {}", err),
+  };
+
   let rss = thirdparty::rss::Rss::new(settings.rss.token.clone());
   let traer = thirdparty::traer::Traer::new(settings.traer.token.clone());
-  let models = models::Models::new(database.clone(), rss.clone(), traer.clone());
-  let actors = actors::Actors::new(models.clone(), settings.clone(), mailer.clone());
-
-  let jobs = Jobs::setup(rabbit_mq, models.clone()).await;
+  let github: Arc<dyn thirdparty::github::GitHubClient> = if settings.github.enabled {
+    Arc::new(thirdparty::github::GitHub::new(
+      settings.github.token.clone(),
+    ))
+  } else {
+    Arc::new(thirdparty::github::StubGitHub)
+  };
+  let metadata = metadata::create_provider(&settings, traer.clone());
+  let sources = sources::Sources::new(
+    Arc::new(sources::github_stars::GitHubStarsProvider::new(
+      github.clone(),
+    )),
+    Arc::new(sources::youtube_playlist::YouTubePlaylistProvider::new()),
+  );
+  let media = metadata::media::MediaExtractor::new(github);
+  let models = models::Models::new(database.clone(), rss.clone(), metadata, media);
+  let health_client = health::client::ReqwestHealthClient::new();
+  let health_checker = health::HealthChecker::new(&settings, Arc::new(health_client));
+  let jobs = Jobs::setup(
+    rabbit_mq,
+    models.clone(),
+    archiver.clone(),
+    health_checker,
+    sources,
+    rss.clone(),
+    &settings,
+  )
+  .await;
+  let actors = actors::Actors::new(
+    models.clone(),
+    settings.clone(),
+    mailer.clone(),
+    jobs.clone(),
+  );
 
   let context = web::Data::new(Context {
     database: database.clone(),
@@ -72,6 +115,7 @@ async fn main() {
     actors: actors.clone(),
     models: models.clone(),
     jobs: jobs.clone(),
+    archiver: archiver.clone(),
   });
 
   let args = env::args().collect::<Vec<String>>();
@@ -96,15 +140,28 @@ async fn main() {
       .app_data(context.clone())
       .configure(routes::user::create_router)
       .configure(routes::resource_import::create_router)
+      .configure(routes::import_job::create_router)
+      .configure(routes::backup::create_router)
       .configure(routes::resource::create_router)
+      .configure(routes::save::create_router)
+      .configure(routes::inbox::create_router)
       .configure(routes::list::create_router)
+      .configure(routes::activity::create_router)
       .configure(routes::list_by_slug::create_router)
       .configure(routes::discover::create_router)
       .configure(routes::resource_metadata::create_router)
+      .configure(routes::resource_related::create_router)
       .configure(routes::integration::create_router)
       .configure(routes::like::create_router)
+      .configure(routes::annotation::create_router)
+      .configure(routes::tag::create_router)
+      .configure(routes::share::create_router)
       .service(web::scope("/webhooks/rss").configure(routes::webhooks::rss::create_router))
       .service(web::scope("/webhooks/paddle").configure(routes::webhooks::paddle::create_router))
+      .service(
+        web::scope("/webhooks/inbound-email")
+          .configure(routes::webhooks::inbound_email::create_router),
+      )
       .service(web::scope("/").configure(routes::index::create_router))
   })
   .bind(("0.0.0.0", port))
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use url::Url;
+
+use crate::errors::Error;
+use crate::metadata::MetadataProvider;
+use crate::metadata::PageMetadata;
+
+// Uses the primary provider and falls back to the secondary one when the
+// primary provider fails or is not able to extract the page title.
+pub struct FallbackProvider {
+  primary: Box<dyn MetadataProvider>,
+  fallback: Box<dyn MetadataProvider>,
+}
+
+impl FallbackProvider {
+  pub fn new(primary: Box<dyn MetadataProvider>, fallback: Box<dyn MetadataProvider>) -> Self {
+    Self { primary, fallback }
+  }
+}
+
+fn choose(
+  primary: Result<Option<PageMetadata>, Error>,
+  fallback: Result<Option<PageMetadata>, Error>,
+) -> Result<Option<PageMetadata>, Error> {
+  match (primary, fallback) {
+    (_, Ok(Some(metadata))) => Ok(Some(metadata)),
+    (Ok(metadata), _) => Ok(metadata),
+    (Err(_), fallback) => fallback,
+  }
+}
+
+fn is_complete(metadata: &Result<Option<PageMetadata>, Error>) -> bool {
+  matches!(metadata, Ok(Some(metadata)) if metadata.title.is_some())
+}
+
+#[async_trait]
+impl MetadataProvider for FallbackProvider {
+  async fn get_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error> {
+    let metadata = self.primary.get_content(url).await;
+    if is_complete(&metadata) {
+      return metadata;
+    }
+
+    debug!(
+      "Primary metadata provider failed for {}, using fallback",
+      url
+    );
+    choose(metadata, self.fallback.get_content(url).await)
+  }
+
+  async fn get_slim_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error> {
+    let metadata = self.primary.get_slim_content(url).await;
+    if is_complete(&metadata) {
+      return metadata;
+    }
+
+    debug!(
+      "Primary metadata provider failed for {}, using fallback",
+      url
+    );
+    choose(metadata, self.fallback.get_slim_content(url).await)
+  }
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+use url::Url;
+
+use crate::errors::Error;
+use crate::lib::http;
+use crate::metadata::oembed::OEmbed;
+use crate::metadata::pdf;
+use crate::metadata::PageMetadata;
+use crate::models::resource::Kind;
+use crate::models::resource::MediaMetadata;
+use crate::thirdparty::github::GitHubClient;
+
+// PDFs above this size are not downloaded to extract their metadata.
+const MAX_PDF_SIZE: usize = 20 * 1024 * 1024;
+
+const GITHUB_RESERVED_PATHS: &[&str] = &[
+  "about",
+  "collections",
+  "explore",
+  "marketplace",
+  "orgs",
+  "settings",
+  "sponsors",
+  "topics",
+  "trending",
+];
+
+#[derive(Debug)]
+pub struct Media {
+  pub kind: Kind,
+  pub metadata: Option<MediaMetadata>,
+  // Title found by the kind specific extractor, used when the page metadata
+  // has no title (E.g. PDF documents).
+  pub title: Option<String>,
+}
+
+impl Media {
+  fn new(kind: Kind) -> Self {
+    Self {
+      kind,
+      metadata: None,
+      title: None,
+    }
+  }
+}
+
+// Detects the resource kind and extracts the kind specific metadata.
+#[derive(Clone)]
+pub struct MediaExtractor {
+  client: reqwest::Client,
+  oembed: OEmbed,
+  github: Arc<dyn GitHubClient>,
+}
+
+impl MediaExtractor {
+  pub fn new(github: Arc<dyn GitHubClient>) -> Self {
+    let user_agent = "Mozilla/5.0 (compatible; Listas/1.0; +https://listas.io)";
+    // Redirects of the resource URLs are followed by `http::send_public`, this
+    // way every redirect target is checked before it is requested. oEmbed
+    // endpoints are known providers, their redirects are followed by reqwest.
+    let client = reqwest::Client::builder()
+      .user_agent(user_agent)
+      .redirect(reqwest::redirect::Policy::none())
+      .build()
+      .expect("Failed to build media extractor HTTP client");
+    let oembed_client = reqwest::Client::builder()
+      .user_agent(user_agent)
+      .build()
+      .expect("Failed to build oEmbed HTTP client");
+
+    Self {
+      oembed: OEmbed::new(oembed_client),
+      client,
+      github,
+    }
+  }
+
+  // The page metadata is missing when the URL does not point to an HTML page.
+  pub async fn extract(&self, url: &Url, page: Option<&PageMetadata>) -> Result<Media, Error> {
+    let kind = match detect_kind_from_url(url) {
+      Some(kind) => kind,
+      None => self.detect_kind_from_response(url, page).await?,
+    };
+
+    let duration = page.and_then(|page| page.duration);
+    match kind {
+      Kind::Video | Kind::Podcast | Kind::Tweet => self.extract_oembed(url, kind, duration).await,
+      Kind::Pdf => self.extract_pdf(url).await,
+      Kind::Repo => self.extract_repository(url).await,
+      Kind::Article => Ok(Media::new(kind)),
+    }
+  }
+
+  // HTML pages are detected using their OpenGraph type, other URLs using
+  // their content type.
+  async fn detect_kind_from_response(
+    &self,
+    url: &Url,
+    page: Option<&PageMetadata>,
+  ) -> Result<Kind, Error> {
+    if let Some(page) = page {
+      let kind = match page.page_type.as_deref() {
+        Some(page_type) if page_type.starts_with("video") => Kind::Video,
+        Some(page_type) if page_type.starts_with("music") => Kind::Podcast,
+        _ => Kind::Article,
+      };
+
+      return Ok(kind);
+    }
+
+    let res = match http::send_public(&self.client, reqwest::Method::HEAD, url).await? {
+      Some((_, res)) => res,
+      None => return Ok(Kind::Article),
+    };
+    let content_type = res
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .unwrap_or_default();
+
+    let kind = if content_type.starts_with("application/pdf") {
+      Kind::Pdf
+    } else if content_type.starts_with("audio/") {
+      Kind::Podcast
+    } else if content_type.starts_with("video/") {
+      Kind::Video
+    } else {
+      Kind::Article
+    };
+
+    Ok(kind)
+  }
+
+  async fn extract_oembed(
+    &self,
+    url: &Url,
+    kind: Kind,
+    page_duration: Option<i64>,
+  ) -> Result<Media, Error> {
+    let oembed = self.oembed.get(url).await?;
+    let mut media = Media::new(kind);
+    let mut duration = page_duration;
+
+    if let Some(oembed) = oembed {
+      media.title = oembed.title;
+      duration = oembed.duration.or(duration);
+    }
+
+    if duration.is_some() {
+      media.metadata = Some(MediaMetadata {
+        duration,
+        ..Default::default()
+      });
+    }
+
+    Ok(media)
+  }
+
+  async fn extract_pdf(&self, url: &Url) -> Result<Media, Error> {
+    let mut media = Media::new(Kind::Pdf);
+
+    let res = match http::send_public(&self.client, reqwest::Method::GET, url).await? {
+      Some((_, res)) => res,
+      None => return Ok(media),
+    };
+
+    if !res.status().is_success() {
+      return Ok(media);
+    }
+
+    let data = match http::read_body(res, MAX_PDF_SIZE).await? {
+      Some(data) => data,
+      None => return Ok(media),
+    };
+
+    let pdf = pdf::parse(&data);
+    media.title = pdf.title;
+    media.metadata = Some(MediaMetadata {
+      page_count: pdf.page_count,
+      ..Default::default()
+    });
+
+    Ok(media)
+  }
+
+  async fn extract_repository(&self, url: &Url) -> Result<Media, Error> {
+    let mut media = Media::new(Kind::Repo);
+    let (owner, name) = match get_repository_path(url) {
+      Some(path) => path,
+      None => return Ok(media),
+    };
+
+    let repository = self.github.get_repository(&owner, &name).await?;
+    if let Some(repository) = repository {
+      media.title = Some(repository.full_name);
+      media.metadata = Some(MediaMetadata {
+        stars: Some(repository.stargazers_count),
+        language: repository.language,
+        ..Default::default()
+      });
+    }
+
+    Ok(media)
+  }
+}
+
+fn get_repository_path(url: &Url) -> Option<(String, String)> {
+  let mut segments = url.path_segments()?.filter(|segment| !segment.is_empty());
+  let owner = segments.next()?;
+  let name = segments.next()?;
+
+  if GITHUB_RESERVED_PATHS.contains(&owner) {
+    return None;
+  }
+
+  Some((owner.to_string(), name.trim_end_matches(".git").to_string()))
+}
+
+fn detect_kind_from_url(url: &Url) -> Option<Kind> {
+  let host = url.host_str()?.trim_start_matches("www.");
+  let path = url.path().to_lowercase();
+
+  let kind = match host {
+    "youtube.com" | "m.youtube.com" | "youtu.be" | "vimeo.com" | "dailymotion.com" => Kind::Video,
+    "twitch.tv" if path.starts_with("/videos") => Kind::Video,
+    "twitter.com" | "mobile.twitter.com" | "x.com" if path.contains("/status/") => Kind::Tweet,
+    "github.com" if get_repository_path(url).is_some() => Kind::Repo,
+    "podcasts.apple.com" | "anchor.fm" | "overcast.fm" | "pca.st" => Kind::Podcast,
+    "open.spotify.com" if path.starts_with("/episode") || path.starts_with("/show") => {
+      Kind::Podcast
+    }
+    _ if path.ends_with(".pdf") => Kind::Pdf,
+    _ if path.ends_with(".mp3") || path.ends_with(".m4a") => Kind::Podcast,
+    _ => return None,
+  };
+
+  Some(kind)
+}
@@ -0,0 +1,52 @@
+pub mod fallback;
+pub mod media;
+pub mod native;
+pub mod oembed;
+pub mod pdf;
+pub mod traer;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use url::Url;
+
+use crate::errors::Error;
+use crate::settings::ProviderKind;
+use crate::settings::Settings;
+use crate::thirdparty::traer::Traer;
+
+#[derive(Debug, Default, Clone)]
+pub struct PageMetadata {
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub html: Option<String>,
+  pub text: Option<String>,
+  pub length: Option<i64>,
+  pub author: Option<String>,
+  pub publisher: Option<String>,
+  pub image: Option<String>,
+  pub logo: Option<String>,
+  // OpenGraph type (E.g. article, video.other).
+  pub page_type: Option<String>,
+  // Video or audio duration in seconds.
+  pub duration: Option<i64>,
+}
+
+// Extracts the metadata and the main content of web pages.
+#[async_trait]
+pub trait MetadataProvider: Send + Sync {
+  async fn get_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error>;
+
+  // Returns the page metadata without the main content (html and text).
+  async fn get_slim_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error>;
+}
+
+pub fn create_provider(settings: &Settings, traer: Traer) -> Arc<dyn MetadataProvider> {
+  match settings.metadata.provider {
+    ProviderKind::Traer => Arc::new(traer::TraerProvider::new(traer)),
+    ProviderKind::Native => Arc::new(native::NativeProvider::new()),
+    ProviderKind::NativeWithTraerFallback => Arc::new(fallback::FallbackProvider::new(
+      Box::new(native::NativeProvider::new()),
+      Box::new(traer::TraerProvider::new(traer)),
+    )),
+  }
+}
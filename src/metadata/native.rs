@@ -0,0 +1,383 @@
+use async_trait::async_trait;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Name};
+use serde_json::Value;
+use std::collections::HashMap;
+use url::Url;
+
+use crate::errors::Error;
+use crate::lib::http;
+use crate::metadata::MetadataProvider;
+use crate::metadata::PageMetadata;
+
+// Paragraphs shorter than this value are ignored when looking for the main
+// content of the page.
+const MIN_PARAGRAPH_LENGTH: usize = 25;
+// Pages above this size are not parsed.
+const MAX_PAGE_SIZE: usize = 10 * 1024 * 1024;
+// Elements that never contain the main content of the page.
+const UNLIKELY_ELEMENTS: &[&str] = &[
+  "nav", "header", "footer", "aside", "form", "script", "style", "noscript",
+];
+const UNLIKELY_CANDIDATES: &[&str] = &[
+  "comment", "footer", "sidebar", "menu", "share", "related", "social", "banner", "nav", "promo",
+];
+
+// Extracts the page metadata from the page HTML without using third party
+// services.
+pub struct NativeProvider {
+  client: reqwest::Client,
+}
+
+impl NativeProvider {
+  pub fn new() -> Self {
+    // Redirects are followed by `http::send_public`, this way every redirect
+    // target is checked before it is requested.
+    let client = reqwest::Client::builder()
+      .user_agent("Mozilla/5.0 (compatible; Listas/1.0; +https://listas.io)")
+      .redirect(reqwest::redirect::Policy::none())
+      .build()
+      .expect("Failed to build native metadata provider HTTP client");
+
+    Self { client }
+  }
+
+  async fn get_html(&self, url: &Url) -> Result<Option<String>, Error> {
+    let (_, response) = match http::send_public(&self.client, reqwest::Method::GET, url).await? {
+      Some(result) => result,
+      None => return Ok(None),
+    };
+
+    if !response.status().is_success() {
+      return Ok(None);
+    }
+
+    let content_type = response
+      .headers()
+      .get(reqwest::header::CONTENT_TYPE)
+      .and_then(|content_type| content_type.to_str().ok())
+      .map(|content_type| content_type.to_owned());
+
+    let is_html = content_type
+      .as_ref()
+      .map(|content_type| content_type.contains("html"))
+      .unwrap_or(true);
+
+    if !is_html {
+      return Ok(None);
+    }
+
+    let body = match http::read_body(response, MAX_PAGE_SIZE).await? {
+      Some(body) => body,
+      None => {
+        debug!("Page {} is too large to be parsed", url);
+        return Ok(None);
+      }
+    };
+
+    let html = http::decode_text(&body, content_type.as_deref());
+    Ok(Some(html))
+  }
+}
+
+impl Default for NativeProvider {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[async_trait]
+impl MetadataProvider for NativeProvider {
+  async fn get_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error> {
+    let html = self.get_html(url).await?;
+    Ok(html.map(|html| parse(html.as_str(), url, false)))
+  }
+
+  async fn get_slim_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error> {
+    let html = self.get_html(url).await?;
+    Ok(html.map(|html| parse(html.as_str(), url, true)))
+  }
+}
+
+// Parses the page metadata from OpenGraph, Twitter cards, JSON-LD and the
+// document markup. Slim parsing skips the main content extraction.
+pub fn parse(html: &str, url: &Url, slim: bool) -> PageMetadata {
+  let document = Document::from(html);
+  let meta = get_meta_tags(&document);
+  let json_ld = get_json_ld(&document);
+
+  let get_meta = |keys: &[&str]| {
+    keys
+      .iter()
+      .find_map(|key| meta.get(*key))
+      .map(|value| value.to_string())
+  };
+  let get_json_ld = |key: &str| {
+    json_ld
+      .as_ref()
+      .and_then(|json_ld| get_json_ld_text(json_ld, key))
+  };
+
+  let title = get_meta(&["og:title", "twitter:title"])
+    .or_else(|| get_json_ld("headline"))
+    .or_else(|| get_json_ld("name"))
+    .or_else(|| get_title(&document));
+
+  let description = get_meta(&["og:description", "twitter:description", "description"])
+    .or_else(|| get_json_ld("description"));
+
+  let image = get_meta(&[
+    "og:image",
+    "og:image:url",
+    "twitter:image",
+    "twitter:image:src",
+  ])
+  .or_else(|| get_json_ld("image"))
+  .and_then(|image| resolve_url(url, &image));
+
+  let author =
+    get_meta(&["author", "article:author", "twitter:creator"]).or_else(|| get_json_ld("author"));
+
+  let publisher = get_meta(&["og:site_name", "application-name"])
+    .or_else(|| get_json_ld("publisher"))
+    .or_else(|| {
+      url
+        .host_str()
+        .map(|host| host.trim_start_matches("www.").to_string())
+    });
+
+  let logo = get_favicon(&document, url);
+  let page_type = get_meta(&["og:type"]).map(|page_type| page_type.to_lowercase());
+
+  // Video pages (E.g. YouTube) describe the duration using schema.org
+  // ISO 8601 durations, OpenGraph uses seconds.
+  let duration = get_meta(&["duration"])
+    .or_else(|| get_json_ld("duration"))
+    .and_then(|duration| parse_duration(&duration))
+    .or_else(|| {
+      get_meta(&["video:duration", "og:video:duration"])
+        .and_then(|duration| duration.parse::<i64>().ok())
+    });
+
+  let mut metadata = PageMetadata {
+    title,
+    description,
+    author,
+    publisher,
+    image,
+    logo,
+    page_type,
+    duration,
+    ..Default::default()
+  };
+
+  if !slim {
+    if let Some((html, text)) = get_main_content(&document) {
+      metadata.length = Some(text.chars().count() as i64);
+      metadata.html = Some(html);
+      metadata.text = Some(text);
+    }
+  }
+
+  metadata
+}
+
+// Parses ISO 8601 durations (E.g. PT1H2M3S, P0DT4M13S) into seconds.
+fn parse_duration(value: &str) -> Option<i64> {
+  let value = value.trim().to_uppercase();
+  let value = value.strip_prefix('P')?;
+
+  let mut seconds = 0.0;
+  let mut number = String::new();
+  let mut is_time = false;
+  let mut has_components = false;
+
+  for char in value.chars() {
+    match char {
+      'T' => is_time = true,
+      '0'..='9' | '.' | ',' => number.push(if char == ',' { '.' } else { char }),
+      unit => {
+        let amount = number.parse::<f64>().ok()?;
+        number.clear();
+        has_components = true;
+
+        seconds += amount
+          * match (unit, is_time) {
+            ('W', false) => 604_800.0,
+            ('D', false) => 86_400.0,
+            ('H', true) => 3_600.0,
+            ('M', true) => 60.0,
+            ('S', true) => 1.0,
+            _ => return None,
+          };
+      }
+    }
+  }
+
+  if !number.is_empty() || !has_components {
+    return None;
+  }
+
+  Some(seconds.round() as i64)
+}
+
+fn clean_text(text: &str) -> Option<String> {
+  let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+  if text.is_empty() {
+    return None;
+  }
+  Some(text)
+}
+
+fn resolve_url(base: &Url, value: &str) -> Option<String> {
+  base.join(value.trim()).ok().map(|url| url.to_string())
+}
+
+fn get_meta_tags(document: &Document) -> HashMap<String, String> {
+  let mut meta = HashMap::new();
+
+  for node in document.find(Name("meta")) {
+    let key = node
+      .attr("property")
+      .or_else(|| node.attr("name"))
+      .or_else(|| node.attr("itemprop"));
+    let value = node.attr("content").and_then(clean_text);
+
+    if let (Some(key), Some(value)) = (key, value) {
+      // Keep the first value, pages can define several og:image tags.
+      meta.entry(key.to_lowercase()).or_insert(value);
+    }
+  }
+
+  meta
+}
+
+fn get_title(document: &Document) -> Option<String> {
+  document
+    .find(Name("title"))
+    .next()
+    .and_then(|node| clean_text(&node.text()))
+}
+
+fn get_favicon(document: &Document, url: &Url) -> Option<String> {
+  let icons = document.find(Name("link")).filter_map(|node| {
+    let rel = node.attr("rel")?.to_lowercase();
+    let href = node.attr("href")?;
+    let priority = match rel.as_str() {
+      "apple-touch-icon" => 0,
+      "icon" => 1,
+      "shortcut icon" => 2,
+      _ => return None,
+    };
+    Some((priority, href))
+  });
+
+  match icons.min_by_key(|(priority, _)| *priority) {
+    Some((_, href)) => resolve_url(url, href),
+    None => resolve_url(url, "/favicon.ico"),
+  }
+}
+
+// Returns the first JSON-LD object that describes the page content.
+fn get_json_ld(document: &Document) -> Option<Value> {
+  document
+    .find(Attr("type", "application/ld+json"))
+    .filter_map(|node| serde_json::from_str::<Value>(&node.text()).ok())
+    .flat_map(|value| match value {
+      Value::Array(values) => values,
+      Value::Object(ref object) => match object.get("@graph") {
+        Some(Value::Array(values)) => values.clone(),
+        _ => vec![value],
+      },
+      _ => vec![],
+    })
+    .find(|value| value.get("headline").is_some() || value.get("name").is_some())
+}
+
+// JSON-LD values can be strings, objects with a name or url, or arrays of
+// them.
+fn get_json_ld_text(value: &Value, key: &str) -> Option<String> {
+  fn to_text(value: &Value) -> Option<String> {
+    match value {
+      Value::String(text) => clean_text(text),
+      Value::Object(object) => object
+        .get("name")
+        .or_else(|| object.get("url"))
+        .and_then(to_text),
+      Value::Array(values) => values.iter().find_map(to_text),
+      _ => None,
+    }
+  }
+
+  value.get(key).and_then(to_text)
+}
+
+fn is_unlikely_candidate(node: &Node) -> bool {
+  match node.name() {
+    Some("html") | Some("body") | Some("main") | Some("article") => return false,
+    Some(name) if UNLIKELY_ELEMENTS.contains(&name) => return true,
+    _ => (),
+  }
+
+  let class_and_id = format!(
+    "{} {}",
+    node.attr("class").unwrap_or_default(),
+    node.attr("id").unwrap_or_default()
+  )
+  .to_lowercase();
+
+  UNLIKELY_CANDIDATES
+    .iter()
+    .any(|candidate| class_and_id.contains(candidate))
+}
+
+fn has_unlikely_ancestor(node: &Node) -> bool {
+  let mut parent = node.parent();
+  while let Some(node) = parent {
+    if is_unlikely_candidate(&node) {
+      return true;
+    }
+    parent = node.parent();
+  }
+  false
+}
+
+// Readability style extraction, paragraphs score their parent and
+// grandparent elements based on their length. The element with the highest
+// score is considered the main content of the page.
+fn get_main_content(document: &Document) -> Option<(String, String)> {
+  let mut scores: HashMap<usize, usize> = HashMap::new();
+
+  for paragraph in document.find(Name("p")) {
+    let length = paragraph.text().trim().chars().count();
+    if length < MIN_PARAGRAPH_LENGTH || has_unlikely_ancestor(&paragraph) {
+      continue;
+    }
+
+    if let Some(parent) = paragraph.parent() {
+      *scores.entry(parent.index()).or_insert(0) += length;
+      if let Some(grandparent) = parent.parent() {
+        *scores.entry(grandparent.index()).or_insert(0) += length / 2;
+      }
+    }
+  }
+
+  let (index, _) = scores
+    .into_iter()
+    .max_by_key(|(index, score)| (*score, std::cmp::Reverse(*index)))?;
+  let content = document.nth(index)?;
+
+  let text = content
+    .find(Name("p"))
+    .filter(|paragraph| !has_unlikely_ancestor(paragraph))
+    .filter_map(|paragraph| clean_text(&paragraph.text()))
+    .collect::<Vec<String>>()
+    .join("\n\n");
+
+  if text.is_empty() {
+    return None;
+  }
+
+  Some((content.html(), text))
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::errors::Error;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OEmbedResponse {
+  pub title: Option<String>,
+  pub author_name: Option<String>,
+  pub provider_name: Option<String>,
+  pub thumbnail_url: Option<String>,
+  // Duration in seconds, not part of the oEmbed spec but returned by Vimeo.
+  // Other providers like YouTube do not return it, the duration is read from
+  // the page metadata instead.
+  pub duration: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct OEmbed {
+  client: reqwest::Client,
+}
+
+impl OEmbed {
+  pub fn new(client: reqwest::Client) -> Self {
+    Self { client }
+  }
+
+  pub async fn get(&self, url: &Url) -> Result<Option<OEmbedResponse>, Error> {
+    let endpoint = match get_endpoint(url) {
+      Some(endpoint) => endpoint,
+      None => return Ok(None),
+    };
+
+    let res = self
+      .client
+      .get(endpoint)
+      .query(&[("url", url.as_str()), ("format", "json")])
+      .send()
+      .await?;
+
+    if !res.status().is_success() {
+      return Ok(None);
+    }
+
+    Ok(Some(res.json::<OEmbedResponse>().await?))
+  }
+}
+
+fn get_endpoint(url: &Url) -> Option<&'static str> {
+  let host = url.host_str()?.trim_start_matches("www.");
+  match host {
+    "youtube.com" | "m.youtube.com" | "youtu.be" => Some("https://www.youtube.com/oembed"),
+    "vimeo.com" => Some("https://vimeo.com/api/oembed.json"),
+    "open.spotify.com" => Some("https://open.spotify.com/oembed"),
+    "twitter.com" | "mobile.twitter.com" | "x.com" => Some("https://publish.twitter.com/oembed"),
+    _ => None,
+  }
+}
@@ -0,0 +1,146 @@
+use flate2::read::ZlibDecoder;
+use lazy_static::lazy_static;
+use regex::bytes::Regex;
+use std::io::Read;
+
+// Upper bound of the inflated content, prevents decompression bombs.
+const MAX_INFLATED_SIZE: u64 = 50 * 1024 * 1024;
+
+lazy_static! {
+  static ref PAGE_REGEX: Regex = Regex::new(r"/Type\s*/Page\b").unwrap();
+  static ref TITLE_REGEX: Regex =
+    Regex::new(r"/Title\s*(?:\(((?:\\.|[^\\)])*)\)|<([0-9A-Fa-f\s]*)>)").unwrap();
+  static ref STREAM_REGEX: Regex = Regex::new(r"(?s)stream\r?\n(.*?)endstream").unwrap();
+}
+
+#[derive(Debug, Default)]
+pub struct PdfMetadata {
+  pub title: Option<String>,
+  pub page_count: Option<i64>,
+}
+
+// Extracts the title and page count of a PDF document without fully parsing
+// it. Page objects and the document information dictionary can be stored
+// inside compressed object streams, so Flate streams are inflated and
+// searched too.
+pub fn parse(data: &[u8]) -> PdfMetadata {
+  let mut sections = vec![data.to_vec()];
+  sections.extend(inflate_streams(data));
+
+  let page_count = sections
+    .iter()
+    .map(|section| PAGE_REGEX.find_iter(section).count() as i64)
+    .sum::<i64>();
+
+  let title = sections.iter().find_map(|section| {
+    TITLE_REGEX.captures_iter(section).find_map(|captures| {
+      let bytes = match (captures.get(1), captures.get(2)) {
+        (Some(literal), _) => unescape_literal(literal.as_bytes()),
+        (None, Some(hex)) => decode_hex(hex.as_bytes()),
+        _ => return None,
+      };
+      decode_text(&bytes)
+    })
+  });
+
+  PdfMetadata {
+    title,
+    page_count: if page_count > 0 {
+      Some(page_count)
+    } else {
+      None
+    },
+  }
+}
+
+fn inflate_streams(data: &[u8]) -> Vec<Vec<u8>> {
+  let mut inflated_size = 0;
+  let mut streams = vec![];
+
+  for captures in STREAM_REGEX.captures_iter(data) {
+    if inflated_size >= MAX_INFLATED_SIZE {
+      break;
+    }
+
+    let mut decoder = ZlibDecoder::new(&captures[1]).take(MAX_INFLATED_SIZE - inflated_size);
+    let mut stream = vec![];
+    // Streams that are not Flate encoded fail to decode and are skipped.
+    if decoder.read_to_end(&mut stream).is_ok() && !stream.is_empty() {
+      inflated_size += stream.len() as u64;
+      streams.push(stream);
+    }
+  }
+
+  streams
+}
+
+fn unescape_literal(literal: &[u8]) -> Vec<u8> {
+  let mut bytes = Vec::with_capacity(literal.len());
+  let mut iter = literal.iter().peekable();
+
+  while let Some(&byte) = iter.next() {
+    if byte != b'\\' {
+      bytes.push(byte);
+      continue;
+    }
+
+    match iter.next() {
+      Some(b'n') => bytes.push(b'\n'),
+      Some(b'r') => bytes.push(b'\r'),
+      Some(b't') => bytes.push(b'\t'),
+      Some(b'b') => bytes.push(0x08),
+      Some(b'f') => bytes.push(0x0c),
+      // Octal character code, up to three digits.
+      Some(&digit) if (b'0'..=b'7').contains(&digit) => {
+        let mut value = (digit - b'0') as u32;
+        for _ in 0..2 {
+          match iter.peek() {
+            Some(&&next) if (b'0'..=b'7').contains(&next) => {
+              value = value * 8 + (next - b'0') as u32;
+              iter.next();
+            }
+            _ => break,
+          }
+        }
+        bytes.push(value as u8);
+      }
+      Some(&other) => bytes.push(other),
+      None => (),
+    }
+  }
+
+  bytes
+}
+
+fn decode_hex(hex: &[u8]) -> Vec<u8> {
+  let digits = hex
+    .iter()
+    .filter_map(|byte| (*byte as char).to_digit(16))
+    .collect::<Vec<u32>>();
+
+  digits
+    .chunks(2)
+    .map(|pair| (pair[0] * 16 + pair.get(1).copied().unwrap_or(0)) as u8)
+    .collect()
+}
+
+// PDF text strings are either UTF-16BE with a byte order mark or
+// PDFDocEncoding, which is handled as Latin-1.
+fn decode_text(bytes: &[u8]) -> Option<String> {
+  let text = if bytes.starts_with(&[0xfe, 0xff]) {
+    let units = bytes[2..]
+      .chunks(2)
+      .filter(|pair| pair.len() == 2)
+      .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+      .collect::<Vec<u16>>();
+    String::from_utf16_lossy(&units)
+  } else {
+    bytes.iter().map(|byte| *byte as char).collect()
+  };
+
+  let text = text.trim().to_string();
+  if text.is_empty() {
+    return None;
+  }
+  Some(text)
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use url::Url;
+
+use crate::errors::Error;
+use crate::metadata::MetadataProvider;
+use crate::metadata::PageMetadata;
+use crate::thirdparty::traer::Traer;
+
+pub struct TraerProvider {
+  traer: Traer,
+}
+
+impl TraerProvider {
+  pub fn new(traer: Traer) -> Self {
+    Self { traer }
+  }
+}
+
+#[async_trait]
+impl MetadataProvider for TraerProvider {
+  async fn get_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error> {
+    let content = self.traer.get_content_from_url(url).await?;
+    let content = match content {
+      Some(content) => content,
+      None => return Ok(None),
+    };
+
+    let metadata = PageMetadata {
+      title: content.title,
+      description: content.description,
+      html: content.html,
+      text: content.text,
+      length: content.length,
+      author: content.author,
+      publisher: content.publisher,
+      image: content.image,
+      logo: content.logo,
+      page_type: None,
+      duration: None,
+    };
+
+    Ok(Some(metadata))
+  }
+
+  async fn get_slim_content(&self, url: &Url) -> Result<Option<PageMetadata>, Error> {
+    let response = self.traer.get_slim_content_from_url(url).await?;
+    if !response.success {
+      return Ok(None);
+    }
+
+    let content = response.data;
+    let metadata = PageMetadata {
+      title: content.title,
+      description: content.description,
+      author: content.author,
+      publisher: content.publisher,
+      image: content.image,
+      logo: content.logo,
+      ..Default::default()
+    };
+
+    Ok(Some(metadata))
+  }
+}
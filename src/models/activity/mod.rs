@@ -0,0 +1,152 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use wither::bson::DateTime;
+use wither::bson::Document;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+use crate::lib::date;
+use crate::lib::serde::serialize_bson_datetime_as_iso_string;
+use crate::lib::serde::serialize_bson_datetime_option_as_iso_string;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+use crate::models::annotation::Annotation;
+use crate::models::resource::Resource;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+  Create,
+  Update,
+  Remove,
+  Complete,
+  UndoComplete,
+  Reorder,
+  Move,
+  Snooze,
+  Unsnooze,
+  CreateAnnotation,
+  UpdateAnnotation,
+  RemoveAnnotation,
+}
+
+impl Action {
+  // Changes that can be undone. Resource creations are not undone, removing
+  // the resource is the equivalent operation. Other changes are only
+  // recorded.
+  pub const UNDOABLE: [Action; 3] = [Action::Remove, Action::Move, Action::Update];
+
+  pub fn is_undoable(&self) -> bool {
+    Self::UNDOABLE.contains(self)
+  }
+}
+
+// Origin of the change.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+  User,
+  Rss,
+  Subscription,
+  Import,
+  Email,
+  Integration,
+  Backup,
+  // Background jobs, E.g. link health checks, metadata refreshes and
+  // snoozes.
+  System,
+}
+
+// Change made to a resource of a list. Moves are stored in the target list,
+// the source list is stored in the previous values.
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[model(index(keys = r#"doc!{ "list": 1, "created_at": -1 }"#))]
+#[model(index(keys = r#"doc!{ "previous.list": 1, "created_at": -1 }"#))]
+#[model(index(keys = r#"doc!{ "resource": 1, "created_at": -1 }"#))]
+pub struct Activity {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub user: ObjectId,
+  pub list: ObjectId,
+  pub resource: ObjectId,
+  pub action: Action,
+  pub source: Source,
+  // Values of the changed fields before the change, used to undo it. Removed
+  // resources store the whole resource document.
+  pub previous: Option<Document>,
+  pub created_at: DateTime,
+  pub undone_at: Option<DateTime>,
+}
+
+impl Activity {
+  pub fn new(resource: &Resource, action: Action, source: Source) -> Self {
+    Self {
+      id: None,
+      user: resource.user.clone(),
+      list: resource.list.clone(),
+      resource: resource.id.clone().expect("Resource to have an ID"),
+      action,
+      source,
+      previous: None,
+      created_at: date::now(),
+      undone_at: None,
+    }
+  }
+
+  pub fn from_annotation(annotation: &Annotation, action: Action) -> Self {
+    Self {
+      id: None,
+      user: annotation.user.clone(),
+      list: annotation.list.clone(),
+      resource: annotation.resource.clone(),
+      action,
+      source: Source::User,
+      previous: None,
+      created_at: date::now(),
+      undone_at: None,
+    }
+  }
+
+  pub fn with_previous(mut self, previous: Document) -> Self {
+    self.previous = Some(previous);
+    self
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateActivity {
+  #[serde(alias = "_id", serialize_with = "serialize_object_id_as_hex_string")]
+  pub id: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub list: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub resource: ObjectId,
+  pub action: Action,
+  pub source: Source,
+  // Names of the changed fields.
+  pub fields: Vec<String>,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub created_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub undone_at: Option<DateTime>,
+}
+
+impl From<Activity> for PrivateActivity {
+  fn from(activity: Activity) -> Self {
+    let fields = match (activity.action, activity.previous) {
+      (Action::Remove, _) | (Action::RemoveAnnotation, _) | (_, None) => vec![],
+      (_, Some(previous)) => previous.keys().cloned().collect(),
+    };
+
+    Self {
+      id: activity.id.unwrap(),
+      list: activity.list,
+      resource: activity.resource,
+      action: activity.action,
+      source: activity.source,
+      fields,
+      created_at: activity.created_at,
+      undone_at: activity.undone_at,
+    }
+  }
+}
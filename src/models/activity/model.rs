@@ -0,0 +1,132 @@
+use wither::bson;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::bson::Document;
+use wither::mongodb::options::FindOneOptions;
+use wither::mongodb::options::FindOptions;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::models;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<Activity> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  // Activities are recorded after the change was made, failing to record the
+  // activity does not fail the change.
+  pub async fn record(&self, activity: Activity) {
+    let resource_id = activity.resource.clone();
+    if let Err(err) = self.create(activity).await {
+      error!(
+        "Failed to record activity of resource {}. Error {}",
+        &resource_id, err
+      );
+    }
+  }
+
+  // Records the activities of changes made to several resources at once.
+  pub async fn record_many(&self, activities: Vec<Activity>) {
+    if activities.is_empty() {
+      return;
+    }
+
+    let documents = activities
+      .iter()
+      .map(|activity| bson::to_document(activity).unwrap())
+      .collect::<Vec<Document>>();
+    let collection = <Activity as wither::Model>::collection(&self.database.conn);
+
+    if let Err(err) = collection.insert_many(documents, None).await {
+      error!(
+        "Failed to record {} activities. Error {}",
+        activities.len(),
+        err
+      );
+    }
+  }
+
+  pub async fn find_by_list(
+    &self,
+    list_id: &ObjectId,
+    skip: i64,
+    limit: i64,
+  ) -> Result<Vec<Activity>, Error> {
+    let options = FindOptions::builder()
+      .sort(doc! { "created_at": -1 })
+      .skip(skip)
+      .limit(limit)
+      .build();
+
+    self.find(create_list_query(list_id), Some(options)).await
+  }
+
+  // Returns the latest undoable change of the list that was not undone.
+  pub async fn find_last_by_list(&self, list_id: &ObjectId) -> Result<Option<Activity>, Error> {
+    let actions = Action::UNDOABLE
+      .iter()
+      .map(|action| bson::to_bson(action).unwrap())
+      .collect::<Vec<Bson>>();
+    let mut query = create_list_query(list_id);
+    query.insert("undone_at", Bson::Null);
+    query.insert("action", doc! { "$in": actions });
+    let options = FindOneOptions::builder()
+      .sort(doc! { "created_at": -1 })
+      .build();
+
+    self.find_one(query, Some(options)).await
+  }
+
+  // Returns the latest change of the resource with the specified action that
+  // was not undone.
+  pub async fn find_last_by_resource(
+    &self,
+    resource_id: &ObjectId,
+    action: Action,
+  ) -> Result<Option<Activity>, Error> {
+    let query = doc! {
+      "resource": resource_id,
+      "action": bson::to_bson(&action).unwrap(),
+      "undone_at": Bson::Null
+    };
+    let options = FindOneOptions::builder()
+      .sort(doc! { "created_at": -1 })
+      .build();
+
+    self.find_one(query, Some(options)).await
+  }
+
+  pub async fn set_undone_at(&self, activity_id: &ObjectId) -> Result<(), Error> {
+    self
+      .update_one(
+        doc! { "_id": activity_id },
+        doc! { "$set": { "undone_at": Bson::DateTime(date::now().into()) } },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+}
+
+// Activities of the list including the resources moved to other lists.
+fn create_list_query(list_id: &ObjectId) -> Document {
+  doc! { "$or": [{ "list": list_id }, { "previous.list": list_id }] }
+}
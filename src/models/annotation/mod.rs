@@ -0,0 +1,126 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+use strum::EnumString;
+use validator::Validate;
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+use crate::lib::date;
+use crate::lib::serde::serialize_bson_datetime_as_iso_string;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+
+#[derive(Debug, Clone, Serialize, Deserialize, EnumString)]
+pub enum HighlightSource {
+  #[serde(rename = "text")]
+  #[strum(serialize = "text")]
+  Text,
+  #[serde(rename = "html")]
+  #[strum(serialize = "html")]
+  Html,
+}
+
+// A highlighted range of the resource stored content. Offsets are expressed in
+// characters and the range end is exclusive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Highlight {
+  pub source: HighlightSource,
+  pub start: i32,
+  pub end: i32,
+  pub quote: String,
+}
+
+#[derive(Debug, Clone, Model, Validate, Serialize, Deserialize)]
+#[model(index(keys = r#"doc!{ "user": 1 }"#))]
+#[model(index(keys = r#"doc!{ "resource": 1, "created_at": 1 }"#))]
+#[model(index(keys = r#"doc!{ "list": 1 }"#))]
+pub struct Annotation {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub user: ObjectId,
+  pub list: ObjectId,
+  pub resource: ObjectId,
+  pub highlight: Option<Highlight>,
+  // Free-form markdown note.
+  #[validate(length(max = 10000))]
+  pub note: Option<String>,
+  pub created_at: DateTime,
+  pub updated_at: DateTime,
+}
+
+impl Annotation {
+  // Annotations need a highlight or a non blank note.
+  pub fn is_empty(&self) -> bool {
+    let has_note = self
+      .note
+      .as_ref()
+      .map(|note| !note.trim().is_empty())
+      .unwrap_or(false);
+
+    self.highlight.is_none() && !has_note
+  }
+
+  // Text used by the resource search index.
+  pub fn get_search_text(&self) -> String {
+    let quote = self
+      .highlight
+      .as_ref()
+      .map(|highlight| highlight.quote.as_str());
+    let note = self.note.as_deref();
+
+    vec![quote, note]
+      .into_iter()
+      .flatten()
+      .collect::<Vec<&str>>()
+      .join("\n")
+  }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnnotationUpdate {
+  pub note: Option<String>,
+  pub updated_at: Option<DateTime>,
+}
+
+impl AnnotationUpdate {
+  pub fn new(update: &mut Self) -> &mut Self {
+    update.updated_at = Some(date::now());
+    update
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateAnnotation {
+  #[serde(alias = "_id", serialize_with = "serialize_object_id_as_hex_string")]
+  pub id: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub user: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub list: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub resource: ObjectId,
+  pub highlight: Option<Highlight>,
+  pub note: Option<String>,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub created_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub updated_at: DateTime,
+}
+
+impl From<Annotation> for PrivateAnnotation {
+  fn from(annotation: Annotation) -> Self {
+    Self {
+      id: annotation.id.unwrap(),
+      user: annotation.user,
+      list: annotation.list,
+      resource: annotation.resource,
+      highlight: annotation.highlight,
+      note: annotation.note,
+      created_at: annotation.created_at,
+      updated_at: annotation.updated_at,
+    }
+  }
+}
@@ -0,0 +1,52 @@
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::mongodb::options::FindOptions;
+
+use crate::database;
+use crate::errors::Error;
+use crate::models;
+use crate::models::annotation::Annotation;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<Annotation> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  pub async fn find_by_resource(&self, resource_id: &ObjectId) -> Result<Vec<Annotation>, Error> {
+    let sort = doc! { "created_at": 1 };
+    let options = FindOptions::builder().sort(sort).build();
+
+    self
+      .find(doc! { "resource": resource_id }, Some(options))
+      .await
+  }
+
+  // Returns the annotations text that is denormalized into the resource to be
+  // used by the search index.
+  pub async fn get_search_text(&self, resource_id: &ObjectId) -> Result<Option<String>, Error> {
+    let annotations = self.find_by_resource(resource_id).await?;
+    if annotations.is_empty() {
+      return Ok(None);
+    }
+
+    let text = annotations
+      .iter()
+      .map(|annotation| annotation.get_search_text())
+      .collect::<Vec<String>>()
+      .join("\n");
+
+    Ok(Some(text))
+  }
+}
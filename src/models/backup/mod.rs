@@ -0,0 +1,109 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+use crate::lib::backup::RestoreSummary;
+use crate::lib::date;
+use crate::lib::serde::serialize_bson_datetime_as_iso_string;
+use crate::lib::serde::serialize_bson_datetime_option_as_iso_string;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+  Pending,
+  Running,
+  Completed,
+  Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+  // Archive of the account created by the create_backup job.
+  #[default]
+  Backup,
+  // Uploaded archive restored by the restore_backup job.
+  Restore,
+}
+
+// Account backup created by the create_backup job or restored by the
+// restore_backup job, the archive is stored in the blob store.
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[model(index(keys = r#"doc!{ "user": 1, "created_at": -1 }"#))]
+pub struct Backup {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub user: ObjectId,
+  #[serde(default)]
+  pub kind: Kind,
+  pub status: Status,
+  // Archive format version.
+  pub version: i32,
+  // Compressed archive size in bytes.
+  pub size: Option<i64>,
+  pub error: Option<String>,
+  // Restored documents, only set for completed restores.
+  #[serde(default)]
+  pub summary: Option<RestoreSummary>,
+  pub created_at: DateTime,
+  pub updated_at: DateTime,
+  pub completed_at: Option<DateTime>,
+}
+
+impl Backup {
+  pub fn new(user: ObjectId, kind: Kind, version: i32) -> Self {
+    let now = date::now();
+    Self {
+      id: None,
+      user,
+      kind,
+      status: Status::Pending,
+      version,
+      size: None,
+      error: None,
+      summary: None,
+      created_at: now,
+      updated_at: now,
+      completed_at: None,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateBackup {
+  #[serde(alias = "_id", serialize_with = "serialize_object_id_as_hex_string")]
+  pub id: ObjectId,
+  pub kind: Kind,
+  pub status: Status,
+  pub version: i32,
+  pub size: Option<i64>,
+  pub error: Option<String>,
+  pub summary: Option<RestoreSummary>,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub created_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub updated_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub completed_at: Option<DateTime>,
+}
+
+impl From<Backup> for PrivateBackup {
+  fn from(backup: Backup) -> Self {
+    Self {
+      id: backup.id.unwrap(),
+      kind: backup.kind,
+      status: backup.status,
+      version: backup.version,
+      size: backup.size,
+      error: backup.error,
+      summary: backup.summary,
+      created_at: backup.created_at,
+      updated_at: backup.updated_at,
+      completed_at: backup.completed_at,
+    }
+  }
+}
@@ -0,0 +1,109 @@
+use wither::bson;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::backup::RestoreSummary;
+use crate::lib::date;
+use crate::models;
+use crate::models::backup::Backup;
+use crate::models::backup::Status;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<Backup> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  pub async fn set_running(&self, backup_id: &ObjectId) -> Result<(), Error> {
+    self
+      .update_one(
+        doc! { "_id": backup_id },
+        doc! {
+          "$set": {
+            "status": bson::to_bson(&Status::Running).unwrap(),
+            "updated_at": Bson::DateTime(date::now().into())
+          }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn set_completed(&self, backup_id: &ObjectId, size: i64) -> Result<(), Error> {
+    let now = date::now();
+    self
+      .update_one(
+        doc! { "_id": backup_id },
+        doc! {
+          "$set": {
+            "status": bson::to_bson(&Status::Completed).unwrap(),
+            "size": size,
+            "updated_at": Bson::DateTime(now.into()),
+            "completed_at": Bson::DateTime(now.into())
+          }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn set_restored(
+    &self,
+    backup_id: &ObjectId,
+    summary: &RestoreSummary,
+  ) -> Result<(), Error> {
+    let now = date::now();
+    self
+      .update_one(
+        doc! { "_id": backup_id },
+        doc! {
+          "$set": {
+            "status": bson::to_bson(&Status::Completed).unwrap(),
+            "summary": bson::to_bson(summary).unwrap(),
+            "updated_at": Bson::DateTime(now.into()),
+            "completed_at": Bson::DateTime(now.into())
+          }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn set_failed(&self, backup_id: &ObjectId, error: String) -> Result<(), Error> {
+    self
+      .update_one(
+        doc! { "_id": backup_id },
+        doc! {
+          "$set": {
+            "status": bson::to_bson(&Status::Failed).unwrap(),
+            "error": error,
+            "updated_at": Bson::DateTime(date::now().into())
+          }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+}
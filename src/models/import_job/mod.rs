@@ -0,0 +1,145 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+use crate::importers::ImportFormat;
+use crate::lib::date;
+use crate::lib::serde::serialize_bson_datetime_as_iso_string;
+use crate::lib::serde::serialize_bson_datetime_option_as_iso_string;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+use crate::models::activity::Source;
+use crate::models::import_job_item::ImportJobItem;
+use crate::models::import_job_item::ItemStatus;
+use crate::models::import_job_item::PrivateImportJobItem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+  Pending,
+  Running,
+  Completed,
+}
+
+// Tracks an import request, items are processed by the create_resources job
+// and their outcome is stored in the import job items collection. The job
+// keeps the count of items by status.
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[model(index(keys = r#"doc!{ "user": 1, "created_at": -1 }"#))]
+pub struct ImportJob {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub user: ObjectId,
+  pub list: ObjectId,
+  pub format: ImportFormat,
+  // Recorded in the activity of the created resources. Jobs created before
+  // the field existed were user imports.
+  #[serde(default = "default_source")]
+  pub source: Source,
+  pub status: Status,
+  pub total_count: i64,
+  pub pending_count: i64,
+  pub created_count: i64,
+  pub duplicate_count: i64,
+  pub invalid_count: i64,
+  pub failed_count: i64,
+  pub created_at: DateTime,
+  pub updated_at: DateTime,
+  pub completed_at: Option<DateTime>,
+}
+
+impl ImportJob {
+  pub fn new(
+    user: ObjectId,
+    list: ObjectId,
+    format: ImportFormat,
+    source: Source,
+    items: &[ImportJobItem],
+  ) -> Self {
+    let now = date::now();
+    let count = items.len() as i64;
+    let invalid_count = items
+      .iter()
+      .filter(|item| item.status == ItemStatus::Invalid)
+      .count() as i64;
+    let pending_count = count - invalid_count;
+
+    Self {
+      id: None,
+      user,
+      list,
+      format,
+      source,
+      // Imports without valid items have nothing to process.
+      status: match pending_count {
+        0 => Status::Completed,
+        _ => Status::Pending,
+      },
+      total_count: count,
+      pending_count,
+      created_count: 0,
+      duplicate_count: 0,
+      invalid_count,
+      failed_count: 0,
+      created_at: now,
+      updated_at: now,
+      completed_at: match pending_count {
+        0 => Some(now),
+        _ => None,
+      },
+    }
+  }
+}
+
+fn default_source() -> Source {
+  Source::Import
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateImportJob {
+  #[serde(alias = "_id", serialize_with = "serialize_object_id_as_hex_string")]
+  pub id: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub list: ObjectId,
+  pub format: ImportFormat,
+  pub source: Source,
+  pub status: Status,
+  pub items: Vec<PrivateImportJobItem>,
+  pub total_count: i64,
+  pub pending_count: i64,
+  pub created_count: i64,
+  pub duplicate_count: i64,
+  pub invalid_count: i64,
+  pub failed_count: i64,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub created_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub updated_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub completed_at: Option<DateTime>,
+}
+
+impl PrivateImportJob {
+  // Items are paginated, only the requested page is included.
+  pub fn new(job: ImportJob, items: Vec<ImportJobItem>) -> Self {
+    Self {
+      id: job.id.unwrap(),
+      list: job.list,
+      format: job.format,
+      source: job.source,
+      status: job.status,
+      items: items.into_iter().map(Into::into).collect(),
+      total_count: job.total_count,
+      pending_count: job.pending_count,
+      created_count: job.created_count,
+      duplicate_count: job.duplicate_count,
+      invalid_count: job.invalid_count,
+      failed_count: job.failed_count,
+      created_at: job.created_at,
+      updated_at: job.updated_at,
+      completed_at: job.completed_at,
+    }
+  }
+}
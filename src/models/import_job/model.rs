@@ -0,0 +1,127 @@
+use wither::bson;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::models;
+use crate::models::import_job::ImportJob;
+use crate::models::import_job::Status;
+use crate::models::import_job_item;
+use crate::models::import_job_item::ImportJobItem;
+use crate::models::import_job_item::ItemStatus;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+  import_job_item: import_job_item::model::Model,
+}
+
+impl models::Model<ImportJob> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    let import_job_item = import_job_item::model::Model::new(database.clone());
+
+    Self {
+      database,
+      import_job_item,
+    }
+  }
+
+  // Stores the outcome of the item at the given index and completes the job
+  // once every item was processed.
+  pub async fn set_item_result(
+    &self,
+    job_id: &ObjectId,
+    index: usize,
+    status: ItemStatus,
+    reason: Option<String>,
+    resource: Option<ObjectId>,
+  ) -> Result<(), Error> {
+    self
+      .import_job_item
+      .set_result(job_id, index, status, reason, resource)
+      .await?;
+
+    let now = date::now();
+    let mut inc = doc! { "pending_count": -1 };
+    inc.insert(status.count_field(), 1);
+
+    self
+      .update_one(
+        doc! { "_id": job_id },
+        doc! {
+          "$set": {
+            "status": to_bson(&Status::Running),
+            "updated_at": Bson::DateTime(now.into())
+          },
+          "$inc": inc
+        },
+        None,
+      )
+      .await?;
+
+    self
+      .update_one(
+        doc! { "_id": job_id, "pending_count": 0 },
+        doc! {
+          "$set": {
+            "status": to_bson(&Status::Completed),
+            "completed_at": Bson::DateTime(now.into())
+          }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  // Sets the failed items as pending again and returns them. The failed
+  // count is used as a guard, concurrent retries reset the items once.
+  pub async fn reset_failed_items(&self, job: &ImportJob) -> Result<Vec<ImportJobItem>, Error> {
+    let job_id = job.id.clone().unwrap();
+    let items = self.import_job_item.find_failed(&job_id).await?;
+
+    if items.is_empty() {
+      return Ok(items);
+    }
+
+    let count = items.len() as i64;
+    let result = self
+      .update_one(
+        doc! { "_id": &job_id, "failed_count": count },
+        doc! {
+          "$set": {
+            "status": to_bson(&Status::Pending),
+            "completed_at": Bson::Null,
+            "updated_at": Bson::DateTime(date::now().into())
+          },
+          "$inc": { "failed_count": -count, "pending_count": count }
+        },
+        None,
+      )
+      .await?;
+
+    if result.modified_count == 0 {
+      return Ok(vec![]);
+    }
+
+    let indexes = items.iter().map(|item| item.index).collect::<Vec<i32>>();
+    self.import_job_item.set_pending(&job_id, &indexes).await?;
+
+    Ok(items)
+  }
+}
+
+fn to_bson<T: serde::Serialize>(value: &T) -> Bson {
+  bson::to_bson(value).expect("Failed to serialize import job value")
+}
@@ -0,0 +1,105 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+use crate::importers::ImportItem;
+use crate::lib::date;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+  Pending,
+  Created,
+  // The URL already exists in the list.
+  Duplicate,
+  Invalid,
+  Failed,
+}
+
+impl ItemStatus {
+  // Field of the import job with the count of items with this status.
+  pub fn count_field(&self) -> &'static str {
+    match self {
+      ItemStatus::Pending => "pending_count",
+      ItemStatus::Created => "created_count",
+      ItemStatus::Duplicate => "duplicate_count",
+      ItemStatus::Invalid => "invalid_count",
+      ItemStatus::Failed => "failed_count",
+    }
+  }
+}
+
+// Entry of an import job. Items are stored in their own collection, imports
+// can have more entries than fit in a single document.
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[model(index(
+  keys = r#"doc!{ "job": 1, "index": 1 }"#,
+  options = r#"doc!{ "unique": true }"#
+))]
+#[model(index(keys = r#"doc!{ "job": 1, "status": 1 }"#))]
+pub struct ImportJobItem {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub job: ObjectId,
+  // Position of the entry in the imported file.
+  pub index: i32,
+  pub item: ImportItem,
+  // Lists are created for each folder when importing folders as lists.
+  pub list: ObjectId,
+  pub status: ItemStatus,
+  // Error message of invalid and failed items.
+  pub reason: Option<String>,
+  pub resource: Option<ObjectId>,
+  pub updated_at: DateTime,
+}
+
+impl ImportJobItem {
+  // Entries the importer could not parse are invalid from the start and are
+  // never queued.
+  pub fn new(job: &ObjectId, index: usize, item: ImportItem, list: ObjectId) -> Self {
+    let (status, reason) = match item.error.clone() {
+      Some(error) => (ItemStatus::Invalid, Some(error)),
+      None => (ItemStatus::Pending, None),
+    };
+
+    Self {
+      id: None,
+      job: job.clone(),
+      index: index as i32,
+      item,
+      list,
+      status,
+      reason,
+      resource: None,
+      updated_at: date::now(),
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateImportJobItem {
+  pub index: i32,
+  pub url: String,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub list: ObjectId,
+  pub status: ItemStatus,
+  pub reason: Option<String>,
+  pub resource: Option<String>,
+}
+
+impl From<ImportJobItem> for PrivateImportJobItem {
+  fn from(item: ImportJobItem) -> Self {
+    Self {
+      index: item.index,
+      url: item.item.url,
+      list: item.list,
+      status: item.status,
+      reason: item.reason,
+      resource: item.resource.map(|resource| resource.to_hex()),
+    }
+  }
+}
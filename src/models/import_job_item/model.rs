@@ -0,0 +1,118 @@
+use wither::bson;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::bson::Document;
+use wither::mongodb::options::FindOptions;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::models;
+use crate::models::import_job_item::ImportJobItem;
+use crate::models::import_job_item::ItemStatus;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<ImportJobItem> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  pub async fn create_many(&self, items: &[ImportJobItem]) -> Result<(), Error> {
+    if items.is_empty() {
+      return Ok(());
+    }
+
+    let documents = items
+      .iter()
+      .map(|item| bson::to_document(item).unwrap())
+      .collect::<Vec<Document>>();
+
+    <ImportJobItem as wither::Model>::collection(&self.database.conn)
+      .insert_many(documents, None)
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn find_by_job(
+    &self,
+    job_id: &ObjectId,
+    skip: i64,
+    limit: i64,
+  ) -> Result<Vec<ImportJobItem>, Error> {
+    let options = FindOptions::builder()
+      .sort(doc! { "index": 1 })
+      .skip(skip)
+      .limit(limit)
+      .build();
+
+    self.find(doc! { "job": job_id }, Some(options)).await
+  }
+
+  pub async fn find_failed(&self, job_id: &ObjectId) -> Result<Vec<ImportJobItem>, Error> {
+    let query = doc! { "job": job_id, "status": to_bson(&ItemStatus::Failed) };
+    let options = FindOptions::builder().sort(doc! { "index": 1 }).build();
+
+    self.find(query, Some(options)).await
+  }
+
+  pub async fn set_result(
+    &self,
+    job_id: &ObjectId,
+    index: usize,
+    status: ItemStatus,
+    reason: Option<String>,
+    resource: Option<ObjectId>,
+  ) -> Result<(), Error> {
+    let update = doc! {
+      "$set": {
+        "status": to_bson(&status),
+        "reason": to_bson(&reason),
+        "resource": to_bson(&resource),
+        "updated_at": Bson::DateTime(date::now().into())
+      }
+    };
+
+    self
+      .update_one(doc! { "job": job_id, "index": index as i32 }, update, None)
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn set_pending(&self, job_id: &ObjectId, indexes: &[i32]) -> Result<(), Error> {
+    let update = doc! {
+      "$set": {
+        "status": to_bson(&ItemStatus::Pending),
+        "reason": Bson::Null,
+        "updated_at": Bson::DateTime(date::now().into())
+      }
+    };
+
+    self
+      .update_many(
+        doc! { "job": job_id, "index": { "$in": indexes } },
+        update,
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+}
+
+fn to_bson<T: serde::Serialize>(value: &T) -> Bson {
+  bson::to_bson(value).expect("Failed to serialize import job item value")
+}
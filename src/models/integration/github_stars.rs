@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubStars {
+  // GitHub user whose starred repositories are added to the list.
+  pub username: String,
+}
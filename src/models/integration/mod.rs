@@ -1,6 +1,8 @@
+pub mod github_stars;
 pub mod model;
 pub mod rss;
 pub mod subscription;
+pub mod youtube_playlist;
 
 use serde::{Deserialize, Serialize};
 use strum::EnumString;
@@ -9,10 +11,13 @@ use wither::bson::{doc, oid::ObjectId};
 use wither::Model;
 
 use crate::lib::serde::serialize_bson_datetime_as_iso_string;
+use crate::lib::serde::serialize_bson_datetime_option_as_iso_string;
 use crate::lib::serde::serialize_object_id_as_hex_string;
+use crate::models::integration::github_stars::GitHubStars;
 use crate::models::integration::rss::Rss;
 use crate::models::integration::subscription::ListasSubscription;
 use crate::models::integration::subscription::PrivateListasSubscription;
+use crate::models::integration::youtube_playlist::YouTubePlaylist;
 
 #[derive(Debug, Clone, Serialize, Deserialize, EnumString)]
 pub enum Kind {
@@ -22,8 +27,18 @@ pub enum Kind {
   #[serde(rename = "listas-subscription")]
   #[strum(serialize = "listas-subscription")]
   ListasSubscription,
+  #[serde(rename = "github-stars")]
+  #[strum(serialize = "github-stars")]
+  GitHubStars,
+  #[serde(rename = "youtube-playlist")]
+  #[strum(serialize = "youtube-playlist")]
+  YouTubePlaylist,
 }
 
+// Kinds that are periodically polled by the poll_integrations job, the other
+// kinds are pushed to Listas.
+pub const POLLED_KINDS: &[&str] = &["github-stars", "youtube-playlist"];
+
 #[derive(Debug, Clone, Model, Serialize, Deserialize)]
 #[model(index(keys = r#"doc!{ "user": 1 }"#))]
 // Partial filter to make sure the user can subscribe to only one list at a
@@ -43,6 +58,9 @@ pub enum Kind {
       "partialFilterExpression": { "kind": "rss" }
     }"#
 ))]
+// Used by the poll_integrations job to poll the least recently polled
+// integrations first.
+#[model(index(keys = r#"doc!{ "kind": 1, "polled_at": 1 }"#))]
 pub struct Integration {
   #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
   pub id: Option<ObjectId>,
@@ -54,6 +72,10 @@ pub struct Integration {
 
   pub rss: Option<Rss>,
   pub listas_subscription: Option<ListasSubscription>,
+  pub github_stars: Option<GitHubStars>,
+  pub youtube_playlist: Option<YouTubePlaylist>,
+  // Last time the source of a polled integration was fetched.
+  pub polled_at: Option<DateTime>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +93,10 @@ pub struct PrivateIntegration {
   pub updated_at: DateTime,
   pub rss: Option<Rss>,
   pub listas_subscription: Option<PrivateListasSubscription>,
+  pub github_stars: Option<GitHubStars>,
+  pub youtube_playlist: Option<YouTubePlaylist>,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub polled_at: Option<DateTime>,
 }
 
 impl From<Integration> for PrivateIntegration {
@@ -84,6 +110,9 @@ impl From<Integration> for PrivateIntegration {
       kind: integration.kind.clone(),
       rss: integration.rss.clone(),
       listas_subscription: integration.listas_subscription.map(Into::into),
+      github_stars: integration.github_stars,
+      youtube_playlist: integration.youtube_playlist,
+      polled_at: integration.polled_at,
     }
   }
 }
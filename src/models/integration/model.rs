@@ -5,6 +5,7 @@ use crate::database;
 use crate::errors::Error;
 use crate::models;
 use crate::models::integration::Integration;
+use crate::models::integration_item;
 use crate::models::Model as ModelTrait;
 use crate::{models::integration::Kind, thirdparty::rss::Rss};
 
@@ -12,6 +13,7 @@ use crate::{models::integration::Kind, thirdparty::rss::Rss};
 pub struct Model {
   database: database::Database,
   rss: Rss,
+  integration_item: integration_item::model::Model,
 }
 
 impl models::Model<Integration> for Model {
@@ -22,7 +24,13 @@ impl models::Model<Integration> for Model {
 
 impl Model {
   pub fn new(database: database::Database, rss: Rss) -> Self {
-    Self { database, rss }
+    let integration_item = integration_item::model::Model::new(database.clone());
+
+    Self {
+      database,
+      rss,
+      integration_item,
+    }
   }
 
   pub async fn remove(&self, integration_id: &ObjectId) -> Result<(), Error> {
@@ -49,6 +57,11 @@ impl Model {
       .delete_one(doc! { "_id": integration.id.clone().unwrap() })
       .await?;
 
+    self
+      .integration_item
+      .remove_by_integration(integration_id)
+      .await?;
+
     Ok(())
   }
 }
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YouTubePlaylist {
+  // Public playlist whose videos are added to the list, E.g.
+  // "PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI".
+  pub playlist_id: String,
+}
@@ -0,0 +1,22 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+// Source item already fetched by a polled integration. Items are only created
+// once, resources the user removed or moved to another list are not created
+// again on the next poll.
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[model(index(
+  keys = r#"doc!{ "integration": 1, "url": 1 }"#,
+  options = r#"doc!{ "unique": true }"#
+))]
+pub struct IntegrationItem {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub integration: ObjectId,
+  pub url: String,
+  pub created_at: DateTime,
+}
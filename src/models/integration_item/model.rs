@@ -0,0 +1,76 @@
+use futures::future::try_join_all;
+use std::collections::HashSet;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::mongodb::options::UpdateOptions;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::models;
+use crate::models::integration_item::IntegrationItem;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<IntegrationItem> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  // Returns the URLs the integration already fetched.
+  pub async fn find_seen_urls(
+    &self,
+    integration_id: &ObjectId,
+    urls: &[String],
+  ) -> Result<HashSet<String>, Error> {
+    let items = self
+      .find(
+        doc! { "integration": integration_id, "url": { "$in": urls } },
+        None,
+      )
+      .await?;
+
+    Ok(items.into_iter().map(|item| item.url).collect())
+  }
+
+  // Upserts the URLs, concurrent polls of the same integration do not fail
+  // on the unique index.
+  pub async fn set_seen_urls(
+    &self,
+    integration_id: &ObjectId,
+    urls: &[String],
+  ) -> Result<(), Error> {
+    let now = Bson::DateTime(date::now().into());
+    let updates = urls.iter().map(|url| {
+      let options = UpdateOptions::builder().upsert(true).build();
+      self.update_one(
+        doc! { "integration": integration_id, "url": url },
+        doc! { "$setOnInsert": { "created_at": now.clone() } },
+        Some(options),
+      )
+    });
+
+    try_join_all(updates).await?;
+
+    Ok(())
+  }
+
+  pub async fn remove_by_integration(&self, integration_id: &ObjectId) -> Result<(), Error> {
+    self
+      .delete_many(doc! { "integration": integration_id })
+      .await?;
+
+    Ok(())
+  }
+}
@@ -15,6 +15,8 @@ use crate::lib::serde::serialize_object_id_as_hex_string;
 use crate::lib::util;
 use crate::models::resource::PrivateResource;
 
+const INBOUND_TOKEN_SIZE: usize = 24;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fork {
   pub list: ObjectId,
@@ -27,6 +29,20 @@ pub struct Fork {
   keys = r#"doc!{ "user": 1, "slug": 1 }"#,
   options = r#"doc!{ "unique": true }"#
 ))]
+#[model(index(
+  keys = r#"doc!{ "inbound_token": 1 }"#,
+  options = r#"doc!{
+    "unique": true,
+    "partialFilterExpression": { "inbound_token": { "$type": "string" } }
+  }"#
+))]
+#[model(index(
+  keys = r#"doc!{ "user": 1, "is_inbox": 1 }"#,
+  options = r#"doc!{
+    "unique": true,
+    "partialFilterExpression": { "is_inbox": true }
+  }"#
+))]
 pub struct List {
   #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
   pub id: Option<ObjectId>,
@@ -44,6 +60,27 @@ pub struct List {
   // * Integration creation and removal
   pub last_activity_at: DateTime,
   pub archived_at: Option<DateTime>,
+  // Applies the suggested tags to the resources added to the list.
+  pub auto_tag: Option<bool>,
+  // Replaces the links that stay not found with a Wayback Machine snapshot.
+  pub replace_broken_links: Option<bool>,
+  // Local part of the inbound email address of the list, links in the emails
+  // sent to this address are added to the list.
+  pub inbound_token: Option<String>,
+  // System list of the user where quick saves, emails and imports land when
+  // no list is specified. Each user has one and it can not be removed.
+  pub is_inbox: Option<bool>,
+}
+
+impl List {
+  pub fn is_inbox(&self) -> bool {
+    self.is_inbox.unwrap_or(false)
+  }
+
+  // Email local parts are case insensitive for most providers.
+  pub fn create_inbound_token() -> String {
+    util::create_random_string(INBOUND_TOKEN_SIZE).to_lowercase()
+  }
 }
 
 #[skip_serializing_none]
@@ -53,6 +90,8 @@ pub struct ListUpdate {
   pub description: Option<String>,
   pub tags: Option<Vec<String>>,
   pub is_public: Option<bool>,
+  pub auto_tag: Option<bool>,
+  pub replace_broken_links: Option<bool>,
   pub updated_at: Option<DateTime>,
 }
 
@@ -84,6 +123,9 @@ pub struct PrivateList {
   pub description: Option<String>,
   pub tags: Vec<String>,
   pub is_public: bool,
+  pub auto_tag: bool,
+  pub replace_broken_links: bool,
+  pub is_inbox: bool,
   #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
   pub created_at: DateTime,
   #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
@@ -1,12 +1,24 @@
 use futures::future::try_join_all;
 use futures::try_join;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 use wither::bson::{self, doc, oid::ObjectId, Bson};
 use wither::bson::{DateTime, Document};
+use wither::mongodb::options::FindOneAndUpdateOptions;
 use wither::mongodb::options::FindOneOptions;
 use wither::mongodb::options::FindOptions;
+use wither::mongodb::options::ReturnDocument;
 
+use crate::lib::list_suggestions;
+use crate::metadata::media::MediaExtractor;
+use crate::metadata::MetadataProvider;
 use crate::models;
+use crate::models::activity;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::annotation;
 use crate::models::integration;
 use crate::models::like;
 use crate::models::list::List;
@@ -14,10 +26,21 @@ use crate::models::list::ListResourceMetadata;
 use crate::models::list::PrivateList;
 use crate::models::resource;
 use crate::models::resource::Resource;
+use crate::models::resource_signature;
+use crate::models::share;
 use crate::models::Model as ModelTrait;
-use crate::thirdparty::traer::Traer;
 use crate::{database, thirdparty::rss::Rss};
-use crate::{errors::Error, lib::date};
+use crate::{errors::Error, lib::date, lib::rank, lib::util};
+
+pub const INBOX_TITLE: &str = "Inbox";
+
+// Number of the latest resources of the user used to describe their lists
+// when suggesting a list for a resource.
+const LIST_CORPUS_SIZE: i64 = 1000;
+
+// Times a resource write is retried when its position is taken by a
+// concurrent write.
+pub const MAX_POSITION_ATTEMPTS: usize = 5;
 
 #[derive(Clone)]
 pub struct Model {
@@ -25,6 +48,10 @@ pub struct Model {
   resource: resource::model::Model,
   integration: integration::model::Model,
   like: like::model::Model,
+  annotation: annotation::model::Model,
+  resource_signature: resource_signature::model::Model,
+  share: share::model::Model,
+  activity: activity::model::Model,
 }
 
 impl models::Model<List> for Model {
@@ -34,16 +61,29 @@ impl models::Model<List> for Model {
 }
 
 impl Model {
-  pub fn new(database: database::Database, rss: Rss, traer: Traer) -> Self {
-    let resource = resource::model::Model::new(database.clone(), traer);
+  pub fn new(
+    database: database::Database,
+    rss: Rss,
+    metadata: Arc<dyn MetadataProvider>,
+    media: MediaExtractor,
+  ) -> Self {
+    let resource = resource::model::Model::new(database.clone(), metadata, media);
     let integration = integration::model::Model::new(database.clone(), rss);
     let like = like::model::Model::new(database.clone());
+    let annotation = annotation::model::Model::new(database.clone());
+    let resource_signature = resource_signature::model::Model::new(database.clone());
+    let share = share::model::Model::new(database.clone());
+    let activity = activity::model::Model::new(database.clone());
 
     Self {
       database,
       resource,
       integration,
       like,
+      annotation,
+      resource_signature,
+      share,
+      activity,
     }
   }
 
@@ -66,6 +106,9 @@ impl Model {
       description: list.description.clone(),
       tags: list.tags.clone(),
       is_public: list.is_public,
+      auto_tag: list.auto_tag.unwrap_or(false),
+      replace_broken_links: list.replace_broken_links.unwrap_or(false),
+      is_inbox: list.is_inbox(),
       created_at: list.created_at,
       updated_at: list.updated_at,
       last_activity_at: list.last_activity_at,
@@ -118,10 +161,42 @@ impl Model {
     })
   }
 
+  // Replaces the inbound token of the list, emails sent to the previous
+  // address are ignored.
+  pub async fn rotate_inbound_token(&self, list_id: &ObjectId) -> Result<String, Error> {
+    let token = List::create_inbound_token();
+    self
+      .update_one(
+        doc! { "_id": list_id },
+        doc! { "$set": { "inbound_token": &token, "updated_at": Bson::DateTime(date::now().into()) } },
+        None,
+      )
+      .await?;
+
+    Ok(token)
+  }
+
   pub async fn archive(&self, list_id: &ObjectId) -> Result<(), Error> {
+    let query = doc! { "list": list_id, "completed_at": Bson::Null };
+    let resources = self.resource.find(query.clone(), None).await?;
+    let resource_ids = resources
+      .iter()
+      .filter_map(|resource| resource.id.clone())
+      .collect::<Vec<ObjectId>>();
+
+    self.resource.delete_many(query).await?;
+    self.record_removed_resources(&resources).await;
     self
-      .resource
-      .delete_many(doc! { "list": list_id, "completed_at": Bson::Null })
+      .annotation
+      .delete_many(doc! { "resource": { "$in": &resource_ids } })
+      .await?;
+    self
+      .resource_signature
+      .delete_many(doc! { "resource": { "$in": &resource_ids } })
+      .await?;
+    self
+      .share
+      .delete_many(doc! { "resource": { "$in": resource_ids } })
       .await?;
 
     self.remove_integrations(list_id).await?;
@@ -140,13 +215,38 @@ impl Model {
   }
 
   pub async fn remove(&self, list_id: &ObjectId) -> Result<(), Error> {
+    let resources = self.resource.find(doc! { "list": list_id }, None).await?;
     self.resource.delete_many(doc! { "list": list_id }).await?;
+    self.record_removed_resources(&resources).await;
+    self
+      .annotation
+      .delete_many(doc! { "list": list_id })
+      .await?;
+    self
+      .resource_signature
+      .delete_many(doc! { "list": list_id })
+      .await?;
+    self.share.delete_many(doc! { "list": list_id }).await?;
     self.remove_integrations(list_id).await?;
     self.delete_one(doc! { "_id": list_id }).await?;
 
     Ok(())
   }
 
+  // The removed resources are stored to be able to restore them, as the
+  // resources removed one by one.
+  async fn record_removed_resources(&self, resources: &[Resource]) {
+    let activities = resources
+      .iter()
+      .map(|resource| {
+        let previous = bson::to_document(resource).unwrap();
+        Activity::new(resource, Action::Remove, Source::User).with_previous(previous)
+      })
+      .collect::<Vec<Activity>>();
+
+    self.activity.record_many(activities).await;
+  }
+
   pub async fn remove_integrations(&self, list_id: &ObjectId) -> Result<(), Error> {
     let integrations = self
       .integration
@@ -161,7 +261,43 @@ impl Model {
     Ok(())
   }
 
-  pub async fn get_next_resource_position(&self, list_id: &ObjectId) -> Result<i32, Error> {
+  pub async fn get_next_resource_position(&self, list_id: &ObjectId) -> Result<String, Error> {
+    let positions = self.get_next_resource_positions(list_id, 1).await?;
+    let position = positions
+      .into_iter()
+      .next()
+      .expect("Failed to get next resource position");
+
+    Ok(position)
+  }
+
+  // Returns the positions for `count` resources appended at the end of the
+  // list.
+  pub async fn get_next_resource_positions(
+    &self,
+    list_id: &ObjectId,
+    count: usize,
+  ) -> Result<Vec<String>, Error> {
+    let mut positions = self.compute_next_resource_positions(list_id, count).await?;
+
+    let needs_rebalance = positions
+      .last()
+      .map(|position| rank::needs_rebalance(position))
+      .unwrap_or(false);
+
+    if needs_rebalance {
+      self.rebalance_positions(list_id).await?;
+      positions = self.compute_next_resource_positions(list_id, count).await?;
+    }
+
+    Ok(positions)
+  }
+
+  async fn compute_next_resource_positions(
+    &self,
+    list_id: &ObjectId,
+    count: usize,
+  ) -> Result<Vec<String>, Error> {
     let sort = doc! { "position": -1 };
     let options = FindOneOptions::builder().sort(sort).build();
     let last_resource = self
@@ -169,13 +305,212 @@ impl Model {
       .find_one(doc! { "list": list_id }, Some(options))
       .await?;
 
-    let position = last_resource
-      .map(|resource| resource.position + 1)
-      .unwrap_or(0);
+    let last_position = last_resource.map(|resource| resource.position);
+    let positions = rank::sequence(last_position.as_deref(), count);
+
+    Ok(positions)
+  }
+
+  // Returns the position for a resource inserted right after the previous
+  // resource, or at the top of the list when no previous resource is
+  // specified. Returns None when the previous resource does not belong to the
+  // list.
+  pub async fn get_position_after(
+    &self,
+    list_id: &ObjectId,
+    previous_id: Option<&ObjectId>,
+    resource_id: &ObjectId,
+  ) -> Result<Option<String>, Error> {
+    let mut position = self
+      .compute_position_after(list_id, previous_id, resource_id)
+      .await?;
+
+    let needs_rebalance = position
+      .as_ref()
+      .map(|position| rank::needs_rebalance(position))
+      .unwrap_or(false);
+
+    if needs_rebalance {
+      self.rebalance_positions(list_id).await?;
+      position = self
+        .compute_position_after(list_id, previous_id, resource_id)
+        .await?;
+    }
 
     Ok(position)
   }
 
+  async fn compute_position_after(
+    &self,
+    list_id: &ObjectId,
+    previous_id: Option<&ObjectId>,
+    resource_id: &ObjectId,
+  ) -> Result<Option<String>, Error> {
+    let previous_position = match previous_id {
+      None => None,
+      Some(previous_id) => {
+        let query = doc! { "_id": previous_id, "list": list_id };
+        match self.resource.get_position(query).await? {
+          Some(position) => Some(position),
+          None => return Ok(None),
+        }
+      }
+    };
+
+    let mut query = doc! { "list": list_id, "_id": { "$ne": resource_id } };
+    if let Some(ref previous_position) = previous_position {
+      query.insert("position", doc! { "$gt": previous_position });
+    }
+
+    let sort = doc! { "position": 1 };
+    let options = FindOneOptions::builder().sort(sort).build();
+    let next_position = self
+      .resource
+      .find_one(query, Some(options))
+      .await?
+      .map(|resource| resource.position);
+
+    let position = rank::between(previous_position.as_deref(), next_position.as_deref());
+    Ok(Some(position))
+  }
+
+  // Reassigns evenly distributed positions to the list resources keeping
+  // their current order. Resource positions are read without deserializing
+  // them, this way lists with legacy integer positions can be migrated.
+  //
+  // Resources moving to a lower position are updated first in ascending
+  // order, then the ones moving to a higher position in descending order.
+  // This way every intermediate state keeps the list order and no update
+  // collides with a position that is still in use.
+  pub async fn rebalance_positions(&self, list_id: &ObjectId) -> Result<(), Error> {
+    debug!("Rebalancing resource positions for list {}", list_id);
+
+    let pipeline = vec![
+      doc! { "$match": { "list": list_id } },
+      doc! { "$sort": { "position": 1, "_id": 1 } },
+      doc! { "$project": { "_id": true, "position": true } },
+    ];
+
+    let resources = self
+      .resource
+      .aggregate::<ResourcePosition>(pipeline)
+      .await?;
+    let positions = rank::distribute(resources.len());
+
+    let mut lower = vec![];
+    let mut higher = vec![];
+    for (resource, position) in resources.into_iter().zip(positions) {
+      match resource.position {
+        Bson::String(ref current) if current == &position => continue,
+        Bson::String(ref current) if current > &position => lower.push((resource.id, position)),
+        _ => higher.push((resource.id, position)),
+      }
+    }
+
+    for (resource_id, position) in lower.into_iter().chain(higher.into_iter().rev()) {
+      self
+        .resource
+        .update_one(
+          doc! { "_id": resource_id },
+          doc! { "$set": { "position": position } },
+          None,
+        )
+        .await?;
+    }
+
+    Ok(())
+  }
+
+  // Validates and inserts the resource. Positions are unique per list, when
+  // a concurrent insert takes the resource position, the resource is
+  // appended at the end of the list.
+  pub async fn create_resource(&self, mut resource: Resource) -> Result<Resource, Error> {
+    let mut attempts = 0;
+
+    loop {
+      match self.resource.build(resource.clone()).await {
+        Err(err) if err.is_duplicate_key() && attempts < MAX_POSITION_ATTEMPTS => {
+          attempts += 1;
+          resource.position = self.get_next_resource_position(&resource.list).await?;
+        }
+        result => return result,
+      }
+    }
+  }
+
+  // Moves the resource into the target list at the specified position. When
+  // a concurrent change takes the position, the resource is appended at the
+  // end of the target list.
+  pub async fn move_resource(
+    &self,
+    resource: &Resource,
+    list_id: &ObjectId,
+    mut position: String,
+  ) -> Result<(), Error> {
+    let resource_id = resource.id.clone().expect("Failed to unwrap Resource ID");
+    let mut attempts = 0;
+
+    loop {
+      let update = doc! {
+        "$set": {
+          "list": list_id,
+          "position": &position,
+          "updated_at": Bson::DateTime(date::now().into())
+        }
+      };
+
+      match self
+        .resource
+        .update_one(doc! { "_id": &resource_id }, update, None)
+        .await
+      {
+        Err(err) if err.is_duplicate_key() && attempts < MAX_POSITION_ATTEMPTS => {
+          attempts += 1;
+          position = self.get_next_resource_position(list_id).await?;
+        }
+        result => {
+          result?;
+          break;
+        }
+      }
+    }
+
+    self
+      .annotation
+      .update_many(
+        doc! { "resource": &resource_id },
+        doc! { "$set": { "list": list_id } },
+        None,
+      )
+      .await?;
+
+    let is_public = self
+      .exists(doc! { "_id": list_id, "is_public": true })
+      .await?;
+    self
+      .resource_signature
+      .update_many(
+        doc! { "resource": &resource_id },
+        doc! { "$set": { "list": list_id, "is_public": is_public } },
+        None,
+      )
+      .await?;
+
+    self
+      .share
+      .update_many(
+        doc! { "resource": &resource_id },
+        doc! { "$set": { "list": list_id } },
+        None,
+      )
+      .await?;
+
+    self.update_last_activity_at(&resource.list).await?;
+    self.update_last_activity_at(list_id).await?;
+
+    Ok(())
+  }
+
   pub async fn get_last_completed_resource(
     &self,
     user_id: &ObjectId,
@@ -197,7 +532,15 @@ impl Model {
     user_id: &ObjectId,
     list_id: &ObjectId,
   ) -> Result<Option<Resource>, Error> {
-    let query = doc! { "user": user_id, "list": list_id, "completed_at": Bson::Null };
+    let query = doc! {
+      "user": user_id,
+      "list": list_id,
+      "completed_at": Bson::Null,
+      "$or": [
+        { "snoozed_until": Bson::Null },
+        { "snoozed_until": { "$lte": Bson::DateTime(date::now().into()) } }
+      ]
+    };
     let sort = doc! { "position": 1 };
     let options = FindOneOptions::builder().sort(sort).build();
 
@@ -219,6 +562,116 @@ impl Model {
     self.like.count(doc! { "list": list_id }).await
   }
 
+  // Lists are matched by slug, importing the same folder twice reuses the
+  // list created by the first import.
+  pub async fn find_or_create_by_title(
+    &self,
+    user_id: &ObjectId,
+    title: &str,
+  ) -> Result<List, Error> {
+    let slug = util::to_slug_case(title);
+    let list = self
+      .find_one(doc! { "user": user_id, "slug": &slug }, None)
+      .await?;
+
+    if let Some(list) = list {
+      return Ok(list);
+    }
+
+    let now = date::now();
+    let list = List {
+      id: None,
+      user: user_id.clone(),
+      title: title.to_owned(),
+      description: None,
+      is_public: false,
+      tags: vec![],
+      slug,
+      fork: None,
+      created_at: now,
+      updated_at: now,
+      last_activity_at: now,
+      archived_at: None,
+      auto_tag: None,
+      replace_broken_links: None,
+      inbound_token: Some(List::create_inbound_token()),
+      is_inbox: None,
+    };
+
+    self.create(list).await
+  }
+
+  // Users created before the inbox existed get it on first use. A list the
+  // user already called "Inbox" becomes the inbox.
+  pub async fn find_or_create_inbox(&self, user_id: &ObjectId) -> Result<List, Error> {
+    let list = self
+      .find_one(doc! { "user": user_id, "is_inbox": true }, None)
+      .await?;
+
+    if let Some(list) = list {
+      return Ok(list);
+    }
+
+    let options = FindOneAndUpdateOptions::builder()
+      .return_document(ReturnDocument::After)
+      .build();
+
+    let list = self
+      .find_one_and_update(
+        doc! {
+          "user": user_id,
+          "slug": util::to_slug_case(INBOX_TITLE),
+          "archived_at": Bson::Null
+        },
+        doc! { "$set": { "is_inbox": true } },
+        Some(options),
+      )
+      .await?;
+
+    if let Some(list) = list {
+      return Ok(list);
+    }
+
+    let now = date::now();
+    let list = List {
+      id: None,
+      user: user_id.clone(),
+      title: INBOX_TITLE.to_owned(),
+      description: None,
+      is_public: false,
+      tags: vec![],
+      slug: self.get_available_slug(user_id, INBOX_TITLE).await?,
+      fork: None,
+      created_at: now,
+      updated_at: now,
+      last_activity_at: now,
+      archived_at: None,
+      auto_tag: None,
+      replace_broken_links: None,
+      inbound_token: Some(List::create_inbound_token()),
+      is_inbox: Some(true),
+    };
+
+    self.create(list).await
+  }
+
+  // Appends a number to the slug when the user already has a list with it.
+  pub async fn get_available_slug(&self, user_id: &ObjectId, title: &str) -> Result<String, Error> {
+    let slug = util::to_slug_case(title);
+    let mut candidate = slug.clone();
+    let mut count = 1;
+
+    while self
+      .exists(doc! { "user": user_id, "slug": &candidate })
+      .await?
+    {
+      count += 1;
+      candidate = format!("{}-{}", slug, count);
+    }
+
+    Ok(candidate)
+  }
+
   pub async fn update_last_activity_at(&self, list_id: &ObjectId) -> Result<(), Error> {
     let update = doc! {
       "$set": {
@@ -232,6 +685,73 @@ impl Model {
 
     Ok(())
   }
+  // Returns the lists of the user that better match the resource content,
+  // the list of the resource is never suggested.
+  pub async fn suggest_lists(&self, resource: &Resource) -> Result<Vec<List>, Error> {
+    let lists = self
+      .find(
+        doc! {
+          "user": &resource.user,
+          "_id": { "$ne": &resource.list },
+          "archived_at": Bson::Null
+        },
+        None,
+      )
+      .await?;
+
+    if lists.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let pipeline = vec![
+      doc! { "$match": { "user": &resource.user, "list": { "$ne": &resource.list } } },
+      doc! { "$sort": { "created_at": -1 } },
+      doc! { "$limit": LIST_CORPUS_SIZE },
+      doc! { "$project": { "list": 1, "title": 1, "tags": 1 } },
+    ];
+
+    let mut documents: HashMap<ObjectId, Vec<String>> = HashMap::new();
+    for document in self
+      .resource
+      .aggregate::<ListCorpusDocument>(pipeline)
+      .await?
+    {
+      let words = documents.entry(document.list).or_default();
+      words.extend(document.title);
+      words.extend(document.tags);
+    }
+
+    let corpus = lists
+      .iter()
+      .map(|list| {
+        let mut words = vec![list.title.clone()];
+        words.extend(list.description.clone());
+        words.extend(list.tags.clone());
+        if let Some(resource_words) = documents.get(list.id.as_ref().unwrap()) {
+          words.extend(resource_words.clone());
+        }
+        words.join(" ")
+      })
+      .collect::<Vec<String>>();
+
+    let content = vec![
+      resource.title.clone(),
+      resource.description.clone(),
+      resource.text.clone(),
+      Some(resource.tags.join(" ")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>()
+    .join(" ");
+
+    let suggestions = list_suggestions::suggest(&content, &corpus)
+      .into_iter()
+      .map(|index| lists[index].clone())
+      .collect();
+
+    Ok(suggestions)
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -240,3 +760,20 @@ pub struct ResourceMetadata {
   pub completed_count: i64,
   pub last_completed_at: Option<DateTime>,
 }
+
+// Resources of the user used to describe their lists.
+#[derive(Debug, Serialize, Deserialize)]
+struct ListCorpusDocument {
+  list: ObjectId,
+  title: Option<String>,
+  #[serde(default)]
+  tags: Vec<String>,
+}
+
+// Resource position read as is, legacy positions are integers.
+#[derive(Debug, Serialize, Deserialize)]
+struct ResourcePosition {
+  #[serde(rename = "_id")]
+  id: ObjectId,
+  position: Bson,
+}
@@ -1,7 +1,16 @@
+pub mod activity;
+pub mod annotation;
+pub mod backup;
+pub mod import_job;
+pub mod import_job_item;
 pub mod integration;
+pub mod integration_item;
 pub mod like;
 pub mod list;
+pub mod reading_session;
 pub mod resource;
+pub mod resource_signature;
+pub mod share;
 pub mod user;
 
 use async_trait::async_trait;
@@ -17,6 +26,7 @@ use wither::bson::{self, oid::ObjectId};
 use wither::mongodb::options::FindOneAndUpdateOptions;
 use wither::mongodb::options::FindOneOptions;
 use wither::mongodb::options::FindOptions;
+use wither::mongodb::options::UpdateModifications;
 use wither::mongodb::options::UpdateOptions;
 use wither::mongodb::results::DeleteResult;
 use wither::mongodb::results::UpdateResult;
@@ -24,8 +34,9 @@ use wither::ModelCursor;
 
 use crate::database::Database;
 use crate::errors::Error;
+use crate::metadata::media::MediaExtractor;
+use crate::metadata::MetadataProvider;
 use crate::thirdparty::rss::Rss;
-use crate::thirdparty::traer::Traer;
 
 #[derive(Clone)]
 pub struct Models {
@@ -45,23 +56,60 @@ pub struct ModelsInner {
   pub list: list::model::Model,
   pub resource: resource::model::Model,
   pub integration: integration::model::Model,
+  pub integration_item: integration_item::model::Model,
   pub like: like::model::Model,
+  pub annotation: annotation::model::Model,
+  pub reading_session: reading_session::model::Model,
+  pub resource_signature: resource_signature::model::Model,
+  pub share: share::model::Model,
+  pub activity: activity::model::Model,
+  pub import_job: import_job::model::Model,
+  pub import_job_item: import_job_item::model::Model,
+  pub backup: backup::model::Model,
 }
 
 impl Models {
-  pub fn new(database: Database, rss: Rss, traer: Traer) -> Self {
-    let list = list::model::Model::new(database.clone(), rss.clone(), traer.clone());
-    let resource = resource::model::Model::new(database.clone(), traer);
+  pub fn new(
+    database: Database,
+    rss: Rss,
+    metadata: Arc<dyn MetadataProvider>,
+    media: MediaExtractor,
+  ) -> Self {
+    let list = list::model::Model::new(
+      database.clone(),
+      rss.clone(),
+      metadata.clone(),
+      media.clone(),
+    );
+    let resource = resource::model::Model::new(database.clone(), metadata, media);
     let user = user::model::Model::new(database.clone());
     let integration = integration::model::Model::new(database.clone(), rss);
-    let like = like::model::Model::new(database);
+    let integration_item = integration_item::model::Model::new(database.clone());
+    let like = like::model::Model::new(database.clone());
+    let annotation = annotation::model::Model::new(database.clone());
+    let reading_session = reading_session::model::Model::new(database.clone());
+    let resource_signature = resource_signature::model::Model::new(database.clone());
+    let share = share::model::Model::new(database.clone());
+    let activity = activity::model::Model::new(database.clone());
+    let import_job = import_job::model::Model::new(database.clone());
+    let import_job_item = import_job_item::model::Model::new(database.clone());
+    let backup = backup::model::Model::new(database);
 
     let inner = Arc::new(ModelsInner {
       user,
       list,
       resource,
       integration,
+      integration_item,
       like,
+      annotation,
+      reading_session,
+      resource_signature,
+      share,
+      activity,
+      import_job,
+      import_job_item,
+      backup,
     });
 
     Self { inner }
@@ -73,6 +121,15 @@ impl Models {
     self.resource.sync_indexes().await?;
     self.like.sync_indexes().await?;
     self.integration.sync_indexes().await?;
+    self.integration_item.sync_indexes().await?;
+    self.annotation.sync_indexes().await?;
+    self.reading_session.sync_indexes().await?;
+    self.resource_signature.sync_indexes().await?;
+    self.share.sync_indexes().await?;
+    self.activity.sync_indexes().await?;
+    self.import_job.sync_indexes().await?;
+    self.import_job_item.sync_indexes().await?;
+    self.backup.sync_indexes().await?;
 
     Ok(())
   }
@@ -174,14 +231,16 @@ pub trait Model<T: wither::Model + Send> {
       .map_err(Error::Mongo)
   }
 
-  async fn update_many(
+  // Accepts an update document or an aggregation pipeline.
+  async fn update_many<U>(
     &self,
     query: Document,
-    update: Document,
+    update: U,
     options: Option<UpdateOptions>,
   ) -> Result<UpdateResult, Error>
   where
     T: wither::Model + Send,
+    U: Into<UpdateModifications> + Send + 'static,
   {
     let db = self.get_database();
     T::collection(&db.conn)
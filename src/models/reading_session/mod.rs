@@ -0,0 +1,25 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+// Time spent reading a resource reported by the client. Sessions are kept
+// when the resource is removed, they are used to compute the user reading
+// metrics.
+#[derive(Debug, Clone, Model, Validate, Serialize, Deserialize)]
+#[model(index(keys = r#"doc!{ "user": 1, "created_at": 1 }"#))]
+#[model(index(keys = r#"doc!{ "resource": 1 }"#))]
+pub struct ReadingSession {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub user: ObjectId,
+  pub list: ObjectId,
+  pub resource: ObjectId,
+  // Duration in seconds.
+  #[validate(range(min = 1, max = 86400))]
+  pub duration: i64,
+  pub created_at: DateTime,
+}
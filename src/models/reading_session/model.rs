@@ -0,0 +1,20 @@
+use crate::database;
+use crate::models;
+use crate::models::reading_session::ReadingSession;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<ReadingSession> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+}
@@ -3,12 +3,14 @@ pub mod model;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_with::skip_serializing_none;
+use strum::EnumString;
 use url::Url;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 use wither::bson::DateTime;
 use wither::bson::{doc, oid::ObjectId};
 use wither::Model;
 
+use crate::lib::serde::deserialize_position;
 use crate::lib::serde::serialize_bson_datetime_as_iso_string;
 use crate::lib::serde::serialize_bson_datetime_option_as_iso_string;
 use crate::lib::serde::serialize_object_id_as_hex_string;
@@ -19,15 +21,29 @@ use crate::lib::{date, util};
 #[model(index(keys = r#"doc!{ "user": 1 }"#))]
 #[model(index(keys = r#"doc!{ "user": 1, "list": 1, "completed_at": 1 }"#))]
 #[model(index(keys = r#"doc!{ "user": 1, "list": 1, "url": 1 }"#))]
+// Positions are unique per list, concurrent inserts computing the same
+// position are retried. Legacy integer positions are not indexed.
+#[model(index(
+  keys = r#"doc!{ "list": 1, "position": 1 }"#,
+  options = r#"doc!{
+    "unique": true,
+    "partialFilterExpression": { "position": { "$type": "string" } }
+  }"#
+))]
+#[model(index(keys = r#"doc!{ "last_checked_at": 1 }"#))]
+#[model(index(keys = r#"doc!{ "populated_at": 1 }"#))]
+#[model(index(keys = r#"doc!{ "refresh_attempted_at": 1, "populated_at": 1 }"#))]
+#[model(index(keys = r#"doc!{ "signature_indexed_at": 1, "populated_at": 1 }"#))]
 pub struct Resource {
   #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
   pub id: Option<ObjectId>,
   pub user: ObjectId,
   pub list: ObjectId,
-  #[validate(url)]
+  #[validate(url, custom = "validate_web_url")]
   pub url: String,
   pub title: Option<String>,
-  pub position: i32,
+  #[serde(deserialize_with = "deserialize_position")]
+  pub position: String,
   pub description: Option<String>,
   pub thumbnail: Option<String>,
   pub tags: Vec<String>,
@@ -40,6 +56,134 @@ pub struct Resource {
   pub updated_at: DateTime,
   pub completed_at: Option<DateTime>,
   pub populated_at: Option<DateTime>,
+  // Date of the last refresh of the resource metadata, set even when the
+  // refresh fails, this way failing resources are not retried every run.
+  pub refresh_attempted_at: Option<DateTime>,
+  // Date of the last similarity signature computation, cleared when the
+  // content used by the signature changes.
+  pub signature_indexed_at: Option<DateTime>,
+  // Annotation notes and highlights, denormalized to be used by the search
+  // index.
+  pub annotations_text: Option<String>,
+  // Estimated reading time in seconds.
+  pub estimated_reading_time: Option<i64>,
+  pub progress: Option<ReadingProgress>,
+  // Snoozed resources are not surfaced as the next resource of the list until
+  // the specified date.
+  pub snoozed_until: Option<DateTime>,
+  pub review: Option<Review>,
+  // Date of the last offline snapshot of the resource page.
+  pub archived_at: Option<DateTime>,
+  pub favicon: Option<String>,
+  // Detected when the resource is populated.
+  pub kind: Option<Kind>,
+  pub media: Option<MediaMetadata>,
+  pub health: Option<LinkHealth>,
+  pub last_checked_at: Option<DateTime>,
+  // Fields set by the user, these fields are not overwritten when the
+  // resource is populated. Missing in resources created before edits were
+  // tracked, in that case only empty fields are populated.
+  pub edited_fields: Option<Vec<String>>,
+  // Tags computed from the resource content when the resource is populated.
+  pub suggested_tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkHealth {
+  // HTTP status of the last response, missing when the request failed.
+  pub status: Option<i32>,
+  // URLs followed when the resource URL redirects.
+  pub redirects: Vec<String>,
+  pub is_broken: bool,
+  pub error: Option<String>,
+  // Resource URL before being replaced by a Wayback Machine snapshot.
+  pub original_url: Option<String>,
+  // Consecutive checks that returned a not found status.
+  #[serde(default)]
+  pub not_found_count: i32,
+}
+
+impl LinkHealth {
+  pub fn broken(status: Option<u16>, redirects: Vec<String>, error: Option<String>) -> Self {
+    Self {
+      status: status.map(i32::from),
+      redirects,
+      is_broken: true,
+      error,
+      original_url: None,
+      not_found_count: 0,
+    }
+  }
+
+  pub fn is_not_found(&self) -> bool {
+    matches!(self.status, Some(404) | Some(410))
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Kind {
+  Article,
+  Video,
+  Pdf,
+  Podcast,
+  Repo,
+  Tweet,
+}
+
+// Kind specific metadata, fields are set depending on the resource kind.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MediaMetadata {
+  // Video or podcast duration in seconds.
+  pub duration: Option<i64>,
+  // PDF page count.
+  pub page_count: Option<i64>,
+  // Repository stars and main language.
+  pub stars: Option<i64>,
+  pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingProgress {
+  pub percentage: Option<f64>,
+  pub scroll_position: Option<i64>,
+  // Total time spent reading the resource in seconds.
+  pub time_spent: i64,
+  pub updated_at: DateTime,
+}
+
+// Spaced repetition schedule used to resurface completed resources. Once the
+// review is due the resource is marked as not completed, completing it again
+// schedules the next review using a longer interval.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Review {
+  // Interval in days between the last completion and the next review.
+  pub interval: i64,
+  pub repetitions: i32,
+  pub due_at: Option<DateTime>,
+}
+
+const REVIEW_EASE_FACTOR: f64 = 2.5;
+
+impl Review {
+  // Returns the review schedule after the resource is completed (SM-2
+  // intervals without grading).
+  pub fn next(&self) -> Self {
+    let interval = match self.repetitions {
+      0 => 1,
+      1 => 6,
+      _ => (self.interval as f64 * REVIEW_EASE_FACTOR).round() as i64,
+    };
+    let due_at = chrono::Utc::now() + chrono::Duration::days(interval);
+
+    Self {
+      interval,
+      repetitions: self.repetitions + 1,
+      due_at: Some(due_at.into()),
+    }
+  }
 }
 
 impl Resource {
@@ -59,7 +203,37 @@ impl Resource {
         "html": this.html,
         "created_at": date::to_rfc3339(this.created_at),
         "updated_at": date::to_rfc3339(this.updated_at),
-        "completed_at": this.completed_at.map(date::to_rfc3339)
+        "completed_at": this.completed_at.map(date::to_rfc3339),
+        "estimated_reading_time": this.estimated_reading_time,
+        "progress": this.progress.map(PrivateReadingProgress::from),
+        "snoozed_until": this.snoozed_until.map(date::to_rfc3339),
+        "review": this.review.map(PrivateReview::from),
+        "archived_at": this.archived_at.map(date::to_rfc3339),
+        "favicon": this.favicon,
+        "kind": this.kind,
+        "media": this.media,
+        "health": this.health,
+        "last_checked_at": this.last_checked_at.map(date::to_rfc3339),
+        "suggested_tags": self.get_suggested_tags()
+    })
+  }
+
+  // Returns whether a populated value can overwrite the specified field.
+  pub fn can_populate(&self, field: &str, is_empty: bool) -> bool {
+    match self.edited_fields {
+      Some(ref edited_fields) => !edited_fields.iter().any(|edited| edited == field),
+      None => is_empty,
+    }
+  }
+
+  // Suggestions already applied to the resource are not suggested again.
+  pub fn get_suggested_tags(&self) -> Option<Vec<String>> {
+    self.suggested_tags.as_ref().map(|suggested_tags| {
+      suggested_tags
+        .iter()
+        .filter(|tag| !self.tags.contains(tag))
+        .cloned()
+        .collect()
     })
   }
 
@@ -71,12 +245,14 @@ impl Resource {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Validate, Serialize, Deserialize)]
 pub struct ResourceUpdate {
   pub list: Option<ObjectId>,
+  #[validate(url, custom = "validate_web_url")]
   pub url: Option<String>,
   pub title: Option<String>,
   pub description: Option<String>,
+  pub thumbnail: Option<String>,
   pub tags: Option<Vec<String>>,
   pub updated_at: Option<DateTime>,
 }
@@ -96,6 +272,20 @@ impl ResourceUpdate {
     update.updated_at = Some(date::now());
     update
   }
+
+  pub fn get_edited_fields(&self) -> Vec<String> {
+    let mut fields = vec![];
+    if self.title.is_some() {
+      fields.push("title".to_string());
+    }
+    if self.description.is_some() {
+      fields.push("description".to_string());
+    }
+    if self.thumbnail.is_some() {
+      fields.push("thumbnail".to_string());
+    }
+    fields
+  }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -108,7 +298,9 @@ pub struct PrivateResource {
   pub list: ObjectId,
   pub url: String,
   pub title: Option<String>,
-  pub position: i32,
+  // Aggregates deserialize into this struct, legacy positions are read too.
+  #[serde(deserialize_with = "deserialize_position")]
+  pub position: String,
   pub description: Option<String>,
   pub thumbnail: Option<String>,
   pub tags: Vec<String>,
@@ -118,10 +310,25 @@ pub struct PrivateResource {
   pub updated_at: DateTime,
   #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
   pub completed_at: Option<DateTime>,
+  pub estimated_reading_time: Option<i64>,
+  pub progress: Option<PrivateReadingProgress>,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub snoozed_until: Option<DateTime>,
+  pub review: Option<PrivateReview>,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub archived_at: Option<DateTime>,
+  pub favicon: Option<String>,
+  pub kind: Option<Kind>,
+  pub media: Option<MediaMetadata>,
+  pub health: Option<LinkHealth>,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub last_checked_at: Option<DateTime>,
+  pub suggested_tags: Option<Vec<String>>,
 }
 
 impl From<Resource> for PrivateResource {
   fn from(resource: Resource) -> Self {
+    let suggested_tags = resource.get_suggested_tags();
     Self {
       id: resource.id.unwrap(),
       user: resource.user,
@@ -135,6 +342,62 @@ impl From<Resource> for PrivateResource {
       created_at: resource.created_at,
       updated_at: resource.updated_at,
       completed_at: resource.completed_at,
+      estimated_reading_time: resource.estimated_reading_time,
+      progress: resource.progress.map(Into::into),
+      snoozed_until: resource.snoozed_until,
+      review: resource.review.map(Into::into),
+      archived_at: resource.archived_at,
+      favicon: resource.favicon,
+      kind: resource.kind,
+      media: resource.media,
+      health: resource.health,
+      last_checked_at: resource.last_checked_at,
+      suggested_tags,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateReadingProgress {
+  pub percentage: Option<f64>,
+  pub scroll_position: Option<i64>,
+  pub time_spent: i64,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub updated_at: DateTime,
+}
+
+impl From<ReadingProgress> for PrivateReadingProgress {
+  fn from(progress: ReadingProgress) -> Self {
+    Self {
+      percentage: progress.percentage,
+      scroll_position: progress.scroll_position,
+      time_spent: progress.time_spent,
+      updated_at: progress.updated_at,
+    }
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateReview {
+  pub interval: i64,
+  pub repetitions: i32,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub due_at: Option<DateTime>,
+}
+
+impl From<Review> for PrivateReview {
+  fn from(review: Review) -> Self {
+    Self {
+      interval: review.interval,
+      repetitions: review.repetitions,
+      due_at: review.due_at,
     }
   }
 }
+
+fn validate_web_url(url: &str) -> Result<(), ValidationError> {
+  match util::is_web_url(url) {
+    true => Ok(()),
+    false => Err(ValidationError::new("web_url")),
+  }
+}
@@ -1,23 +1,51 @@
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use url::Url;
 use validator::Validate;
+use wither::bson;
 use wither::bson::doc;
 use wither::bson::oid::ObjectId;
 use wither::bson::Bson;
 use wither::bson::Document;
+use wither::mongodb::options::FindOptions;
+use wither::Model as WitherModel;
 
 use crate::database;
 use crate::errors::Error;
 use crate::lib::date;
+use crate::lib::reading_time;
+use crate::lib::tag_suggestions;
+use crate::metadata::media::MediaExtractor;
+use crate::metadata::MetadataProvider;
+use crate::metadata::PageMetadata;
 use crate::models;
+use crate::models::activity;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::list::List;
+use crate::models::resource::LinkHealth;
 use crate::models::resource::Resource;
 use crate::models::Model as ModelTrait;
-use crate::thirdparty::traer::Traer;
+
+// Number of the latest resources of the user used to compute the document
+// frequency of the tag suggestions keywords.
+const TAG_CORPUS_SIZE: i64 = 500;
 
 #[derive(Clone)]
 pub struct Model {
   pub database: database::Database,
-  pub traer: Traer,
+  pub metadata: Arc<dyn MetadataProvider>,
+  pub media: MediaExtractor,
+  activity: activity::model::Model,
+}
+
+// Resources of the user used to rank the tag suggestions.
+#[derive(Debug, Serialize, Deserialize)]
+struct TagCorpusDocument {
+  title: Option<String>,
+  text: Option<String>,
+  tags: Vec<String>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -34,8 +62,19 @@ impl models::Model<Resource> for Model {
 }
 
 impl Model {
-  pub fn new(database: database::Database, traer: Traer) -> Self {
-    Self { database, traer }
+  pub fn new(
+    database: database::Database,
+    metadata: Arc<dyn MetadataProvider>,
+    media: MediaExtractor,
+  ) -> Self {
+    let activity = activity::model::Model::new(database.clone());
+
+    Self {
+      database,
+      metadata,
+      media,
+      activity,
+    }
   }
 
   // TODO improve fn name, maybe create and model one could be called insert.
@@ -44,7 +83,7 @@ impl Model {
     self.create(resource).await
   }
 
-  pub async fn get_position(&self, query: Document) -> Result<Option<i32>, Error> {
+  pub async fn get_position(&self, query: Document) -> Result<Option<String>, Error> {
     let resource = self.find_one(query, None).await?;
 
     match resource {
@@ -53,16 +92,226 @@ impl Model {
     }
   }
 
-  pub async fn get_metadata(&self, url: &Url) -> Result<Option<ResourceMetadata>, Error> {
-    let traer_response = self.traer.get_slim_content_from_url(url).await?;
-    if !traer_response.success {
-      return Ok(None);
+  pub async fn update_annotations_text(
+    &self,
+    resource_id: &ObjectId,
+    text: Option<String>,
+  ) -> Result<(), Error> {
+    let text = match text {
+      Some(text) => Bson::String(text),
+      None => Bson::Null,
+    };
+
+    self
+      .update_one(
+        doc! { "_id": resource_id },
+        doc! { "$set": { "annotations_text": text } },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn set_archived_at(&self, resource_id: &ObjectId) -> Result<(), Error> {
+    self
+      .update_one(
+        doc! { "_id": resource_id },
+        doc! { "$set": { "archived_at": Bson::DateTime(date::now().into()) } },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  // Returns the resources that were never checked or whose last health check
+  // is older than the specified amount of days.
+  pub async fn find_health_check_pending(
+    &self,
+    recheck_after_days: i64,
+    limit: i64,
+  ) -> Result<Vec<Resource>, Error> {
+    let checked_before = chrono::Utc::now() - chrono::Duration::days(recheck_after_days);
+    let query = doc! {
+      "$or": [
+        { "last_checked_at": Bson::Null },
+        { "last_checked_at": { "$lte": Bson::DateTime(checked_before) } }
+      ]
+    };
+
+    let options = FindOptions::builder()
+      .sort(doc! { "last_checked_at": 1 })
+      .projection(doc! { "html": false, "text": false })
+      .limit(limit)
+      .build();
+
+    self.find(query, Some(options)).await
+  }
+
+  // Returns the populated resources without a signature or which content
+  // changed since their signature was computed.
+  pub async fn find_signature_pending(&self, limit: i64) -> Result<Vec<Resource>, Error> {
+    let query = doc! {
+      "signature_indexed_at": Bson::Null,
+      "populated_at": { "$ne": Bson::Null }
+    };
+    let options = FindOptions::builder()
+      .projection(doc! { "html": false })
+      .limit(limit)
+      .build();
+
+    self.find(query, Some(options)).await
+  }
+
+  pub async fn set_signature_indexed_at(&self, resource_id: &ObjectId) -> Result<(), Error> {
+    self
+      .update_one(
+        doc! { "_id": resource_id },
+        doc! { "$set": { "signature_indexed_at": Bson::DateTime(date::now().into()) } },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  // Returns the resources populated before the specified amount of days.
+  // Resources which refresh was attempted in that period are skipped, the
+  // resources never attempted are returned first.
+  pub async fn find_stale(
+    &self,
+    stale_after_days: i64,
+    limit: i64,
+  ) -> Result<Vec<Resource>, Error> {
+    let stale_before =
+      Bson::DateTime(chrono::Utc::now() - chrono::Duration::days(stale_after_days));
+    let query = doc! {
+      "populated_at": { "$lte": &stale_before },
+      "$or": [
+        { "refresh_attempted_at": Bson::Null },
+        { "refresh_attempted_at": { "$lte": &stale_before } }
+      ]
+    };
+
+    let options = FindOptions::builder()
+      .sort(doc! { "refresh_attempted_at": 1, "populated_at": 1 })
+      .projection(doc! { "html": false, "text": false })
+      .limit(limit)
+      .build();
+
+    self.find(query, Some(options)).await
+  }
+
+  pub async fn set_refresh_attempted_at(&self, resource_ids: Vec<ObjectId>) -> Result<(), Error> {
+    self
+      .update_many(
+        doc! { "_id": { "$in": resource_ids } },
+        doc! { "$set": { "refresh_attempted_at": Bson::DateTime(date::now().into()) } },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  // Stores the health check result, the resource URL is replaced when a
+  // replacement URL is specified.
+  pub async fn update_health(
+    &self,
+    resource_id: &ObjectId,
+    health: LinkHealth,
+    url: Option<String>,
+  ) -> Result<(), Error> {
+    let mut update = doc! {
+      "health": bson::to_bson(&health).unwrap(),
+      "last_checked_at": Bson::DateTime(date::now().into())
+    };
+
+    if let Some(url) = url {
+      update.insert("url", url);
+      update.insert("updated_at", Bson::DateTime(date::now().into()));
+    }
+
+    self
+      .update_one(doc! { "_id": resource_id }, doc! { "$set": update }, None)
+      .await?;
+
+    Ok(())
+  }
+
+  // Clears the snooze date of the resources that are ready to be surfaced.
+  pub async fn unsnooze(&self) -> Result<(), Error> {
+    let now = Bson::DateTime(date::now().into());
+    let options = FindOptions::builder()
+      .projection(doc! { "html": false, "text": false })
+      .build();
+    let resources = self
+      .find(doc! { "snoozed_until": { "$lte": &now } }, Some(options))
+      .await?;
+
+    if resources.is_empty() {
+      return Ok(());
     }
 
+    let resource_ids = resources
+      .iter()
+      .filter_map(|resource| resource.id.clone())
+      .collect::<Vec<ObjectId>>();
+    self
+      .update_many(
+        doc! { "_id": { "$in": resource_ids }, "snoozed_until": { "$lte": &now } },
+        doc! { "$set": { "snoozed_until": Bson::Null, "updated_at": &now } },
+        None,
+      )
+      .await?;
+
+    let activities = resources
+      .iter()
+      .map(|resource| {
+        let previous = doc! { "snoozed_until": bson::to_bson(&resource.snoozed_until).unwrap() };
+        Activity::new(resource, Action::Unsnooze, Source::System).with_previous(previous)
+      })
+      .collect::<Vec<Activity>>();
+    self.activity.record_many(activities).await;
+
+    Ok(())
+  }
+
+  // Marks as not completed the completed resources with a due review.
+  pub async fn resurface_reviews(&self) -> Result<(), Error> {
+    let now = Bson::DateTime(date::now().into());
+    self
+      .update_many(
+        doc! {
+          "completed_at": { "$ne": Bson::Null },
+          "review.due_at": { "$lte": &now }
+        },
+        doc! {
+          "$set": {
+            "completed_at": Bson::Null,
+            "review.due_at": Bson::Null,
+            "updated_at": &now
+          }
+        },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+
+  pub async fn get_metadata(&self, url: &Url) -> Result<Option<ResourceMetadata>, Error> {
+    let metadata = self.metadata.get_slim_content(url).await?;
+    let metadata = match metadata {
+      Some(metadata) => metadata,
+      None => return Ok(None),
+    };
+
     let metadata = ResourceMetadata {
-      title: traer_response.data.title,
-      description: traer_response.data.description,
-      thumbnail: traer_response.data.image,
+      title: metadata.title,
+      description: metadata.description,
+      thumbnail: metadata.image,
     };
 
     Ok(Some(metadata))
@@ -82,33 +331,114 @@ impl Model {
     };
 
     let url = resource.get_url();
-    let metadata = self.traer.get_content_from_url(&url).await?;
-    let metadata = match metadata {
-      None => return Ok(()),
-      Some(metadata) => metadata,
+    // Non HTML resources like PDFs have no page metadata but can have kind
+    // specific metadata.
+    let metadata = self.metadata.get_content(&url).await?;
+
+    self.populate_with_metadata(resource, metadata).await
+  }
+
+  // Populates the resource from the page HTML captured by the client, the
+  // page is not fetched again.
+  // The metadata is parsed by the caller, E.g. from the page HTML captured by
+  // the client.
+  pub async fn populate_from_metadata(
+    &self,
+    resource_id: ObjectId,
+    metadata: PageMetadata,
+  ) -> Result<(), Error> {
+    let resource = self.find_by_id(&resource_id).await?;
+    let resource = match resource {
+      Some(resource) => resource,
+      None => {
+        error!(
+          "Resource with ID {} not found when updating resource metadata",
+          resource_id
+        );
+        return Ok(());
+      }
     };
 
+    self.populate_with_metadata(resource, Some(metadata)).await
+  }
+
+  async fn populate_with_metadata(
+    &self,
+    resource: Resource,
+    metadata: Option<PageMetadata>,
+  ) -> Result<(), Error> {
+    let resource_id = resource.id.clone().unwrap();
+    let url = resource.get_url();
+    let media = match self.media.extract(&url, metadata.as_ref()).await {
+      Ok(media) => Some(media),
+      Err(err) => {
+        error!(
+          "Failed to extract media metadata for resource {}. Error {}",
+          &resource_id, err
+        );
+        None
+      }
+    };
+
+    let metadata = metadata.unwrap_or_default();
     let mut update = doc! {};
+    let mut media_title = None;
+
+    if let Some(media) = media {
+      update.insert("kind", bson::to_bson(&media.kind).unwrap());
+      if let Some(media_metadata) = media.metadata {
+        update.insert("media", bson::to_bson(&media_metadata).unwrap());
+      }
+      media_title = media.title;
+    }
 
-    // Ensure that fields that the user can edit are not overwritten.
-    match metadata.title {
-      Some(title) if resource.title.is_none() => {
+    let content = tag_suggestions::Content {
+      title: metadata
+        .title
+        .as_deref()
+        .or(media_title.as_deref())
+        .or(resource.title.as_deref()),
+      text: metadata.text.as_deref().or(resource.text.as_deref()),
+      publisher: metadata.publisher.as_deref(),
+    };
+    // Tag suggestions are optional, the resource is populated without them
+    // when the corpus can not be queried.
+    let suggested_tags = match self.suggest_tags(&resource, &content).await {
+      Ok(suggested_tags) => suggested_tags,
+      Err(err) => {
+        error!(
+          "Failed to suggest tags for resource {}. Error {}",
+          &resource_id, err
+        );
+        vec![]
+      }
+    };
+
+    // Ensure that fields edited by the user are not overwritten.
+    match metadata.title.or(media_title) {
+      Some(title) if resource.can_populate("title", resource.title.is_none()) => {
         update.insert("title", title);
       }
       _ => (),
     };
 
     match metadata.description {
-      Some(description) if resource.description.is_none() => {
+      Some(description) if resource.can_populate("description", resource.description.is_none()) => {
         update.insert("description", description);
       }
       _ => (),
     };
 
+    // Thumbnails were always overwritten before edits were tracked.
+    match metadata.image {
+      Some(image) if resource.can_populate("thumbnail", true) => {
+        update.insert("thumbnail", image);
+      }
+      _ => (),
+    };
+
     // Fields that are not editable by the user can be overwritten.
-    if let Some(image) = metadata.image {
-      update.insert("thumbnail", image);
-    }
+    let estimated_reading_time = reading_time::estimate(metadata.text.as_deref(), metadata.length);
     if let Some(html) = metadata.html {
       update.insert("html", html);
     }
@@ -118,26 +448,124 @@ impl Model {
     if let Some(length) = metadata.length {
       update.insert("length", length);
     }
+    if let Some(estimated_reading_time) = estimated_reading_time {
+      update.insert("estimated_reading_time", estimated_reading_time);
+    }
     if let Some(publisher) = metadata.publisher {
       update.insert("publisher", publisher);
     }
     if let Some(author) = metadata.author {
       update.insert("author", author);
     }
+    if let Some(logo) = metadata.logo {
+      update.insert("favicon", logo);
+    }
+    // Values set before the resource is refreshed are recorded when they
+    // are overwritten, the first populate replaces the placeholder values.
+    let mut overwritten = Document::new();
+    if resource.populated_at.is_some() {
+      let current = [
+        ("title", &resource.title),
+        ("description", &resource.description),
+        ("thumbnail", &resource.thumbnail),
+      ];
+      for (field, value) in current.iter() {
+        if let (Some(value), Ok(updated)) = (value, update.get_str(field)) {
+          if value != updated {
+            overwritten.insert(*field, value.clone());
+          }
+        }
+      }
+    }
 
     // Metadata was available for the specified resource but for some reason
-    // the Treaer API returned no attributes.
-    let has_update = !update.is_empty();
+    // the metadata provider returned no attributes.
+    let has_update = !update.is_empty() || !suggested_tags.is_empty();
     if !has_update {
       return Ok(());
     }
 
+    // Suggestions are only applied the first time the resource is populated,
+    // otherwise tags removed by the user would be added again on refresh.
+    let is_first_populate = resource.populated_at.is_none();
+    let apply_suggestions = is_first_populate
+      && !suggested_tags.is_empty()
+      && self.is_auto_tag_enabled(&resource).await?;
+
+    // Applied suggestions and suggestions from previous populates that are no
+    // longer computed are cleared.
+    match apply_suggestions || suggested_tags.is_empty() {
+      true => update.insert("suggested_tags", Bson::Null),
+      false => update.insert("suggested_tags", suggested_tags.clone()),
+    };
+
     update.insert("populated_at", Bson::DateTime(date::now().into()));
+    update.insert("signature_indexed_at", Bson::Null);
+    let mut update = doc! { "$set": update };
+
+    if apply_suggestions {
+      update.insert("$addToSet", doc! { "tags": { "$each": suggested_tags } });
+    }
 
     self
-      .update_one(doc! { "_id": resource_id }, doc! { "$set": update }, None)
+      .update_one(doc! { "_id": &resource_id }, update, None)
       .await?;
 
+    if !overwritten.is_empty() {
+      let activity = Activity::new(&resource, Action::Update, Source::System);
+      self
+        .activity
+        .record(activity.with_previous(overwritten))
+        .await;
+    }
+
     Ok(())
   }
+
+  async fn suggest_tags(
+    &self,
+    resource: &Resource,
+    content: &tag_suggestions::Content<'_>,
+  ) -> Result<Vec<String>, Error> {
+    let pipeline = vec![
+      doc! {
+        "$match": {
+          "user": &resource.user,
+          "_id": { "$ne": resource.id.clone().unwrap() }
+        }
+      },
+      doc! { "$sort": { "created_at": -1 } },
+      doc! { "$limit": TAG_CORPUS_SIZE },
+      doc! { "$project": { "title": 1, "text": 1, "tags": 1 } },
+    ];
+
+    let documents = self.aggregate::<TagCorpusDocument>(pipeline).await?;
+
+    // The corpus documents use the same fields as the suggested content,
+    // otherwise the document frequency of body words would be underestimated.
+    let mut vocabulary = vec![];
+    let mut corpus = vec![];
+    for document in documents {
+      corpus.push(format!(
+        "{} {}",
+        document.title.unwrap_or_default(),
+        document.text.unwrap_or_default()
+      ));
+      vocabulary.extend(document.tags);
+    }
+    vocabulary.sort();
+    vocabulary.dedup();
+
+    let suggestions = tag_suggestions::suggest(content, &corpus, &vocabulary, &resource.tags);
+    Ok(suggestions)
+  }
+
+  async fn is_auto_tag_enabled(&self, resource: &Resource) -> Result<bool, Error> {
+    let list = List::find_one(&self.database.conn, doc! { "_id": &resource.list }, None)
+      .await
+      .map_err(Error::Wither)?;
+
+    let is_enabled = list.and_then(|list| list.auto_tag).unwrap_or(false);
+    Ok(is_enabled)
+  }
 }
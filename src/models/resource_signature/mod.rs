@@ -0,0 +1,32 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+// MinHash signature of the resource content, used to find similar resources.
+// Signatures are computed by a background job after the resource is
+// populated.
+#[derive(Debug, Clone, Model, Serialize, Deserialize)]
+#[model(index(
+  keys = r#"doc!{ "resource": 1 }"#,
+  options = r#"doc!{ "unique": true }"#
+))]
+#[model(index(keys = r#"doc!{ "bands": 1, "user": 1 }"#))]
+#[model(index(keys = r#"doc!{ "bands": 1, "is_public": 1 }"#))]
+pub struct ResourceSignature {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub resource: ObjectId,
+  pub user: ObjectId,
+  pub list: ObjectId,
+  // Denormalized visibility of the list, used to find the candidates the
+  // user can access.
+  #[serde(default)]
+  pub is_public: bool,
+  pub minhash: Vec<i64>,
+  // Locality sensitive hashing bands of the MinHash signature.
+  pub bands: Vec<i64>,
+  pub updated_at: DateTime,
+}
@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet};
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::mongodb::options::FindOptions;
+use wither::mongodb::options::UpdateOptions;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::lib::minhash;
+use crate::lib::tag_suggestions::tokenize;
+use crate::models;
+use crate::models::resource::Resource;
+use crate::models::resource_signature::ResourceSignature;
+use crate::models::Model as ModelTrait;
+
+// Number of the most frequent words of the resource text used as features.
+// Limiting the text words keeps short and long resources comparable.
+const MAX_TEXT_FEATURES: usize = 50;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<ResourceSignature> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  // Computes and stores the signature of the resource. Resources without
+  // content get an empty signature so they are not indexed again until they
+  // are updated.
+  pub async fn index(&self, resource: &Resource, is_public: bool) -> Result<(), Error> {
+    let resource_id = resource.id.clone().unwrap();
+    let minhash = minhash::signature(&get_features(resource)).unwrap_or_default();
+
+    let bands = minhash::bands(&minhash);
+    let update = doc! {
+      "$set": {
+        "user": &resource.user,
+        "list": &resource.list,
+        "is_public": is_public,
+        "minhash": minhash,
+        "bands": bands,
+        "updated_at": Bson::DateTime(date::now().into())
+      }
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+
+    self
+      .update_one(doc! { "resource": &resource_id }, update, Some(options))
+      .await?;
+
+    Ok(())
+  }
+
+  // Returns the signatures sharing at least one band with the signature of
+  // the specified resource. Only the resources of the user and the resources
+  // of public lists are candidates.
+  pub async fn find_candidates(
+    &self,
+    signature: &ResourceSignature,
+    user_id: &ObjectId,
+    limit: i64,
+  ) -> Result<Vec<ResourceSignature>, Error> {
+    let options = FindOptions::builder().limit(limit).build();
+
+    self
+      .find(
+        doc! {
+          "$or": [
+            { "bands": { "$in": &signature.bands }, "user": user_id },
+            { "bands": { "$in": &signature.bands }, "is_public": true }
+          ],
+          "resource": { "$ne": &signature.resource }
+        },
+        Some(options),
+      )
+      .await
+  }
+
+  pub async fn set_list_visibility(
+    &self,
+    list_id: &ObjectId,
+    is_public: bool,
+  ) -> Result<(), Error> {
+    self
+      .update_many(
+        doc! { "list": list_id },
+        doc! { "$set": { "is_public": is_public } },
+        None,
+      )
+      .await?;
+
+    Ok(())
+  }
+}
+
+// Resource features are the words of the title and description, the tags
+// and the most frequent words of the text.
+fn get_features(resource: &Resource) -> HashSet<String> {
+  let mut features = HashSet::new();
+
+  for value in [&resource.title, &resource.description]
+    .iter()
+    .copied()
+    .flatten()
+  {
+    features.extend(tokenize(value));
+  }
+
+  features.extend(resource.tags.iter().map(|tag| format!("tag:{}", tag)));
+
+  if let Some(ref text) = resource.text {
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for word in tokenize(text) {
+      *frequencies.entry(word).or_insert(0) += 1;
+    }
+
+    let mut words = frequencies.into_iter().collect::<Vec<(String, usize)>>();
+    words.sort_by(|(a_word, a), (b_word, b)| b.cmp(a).then_with(|| a_word.cmp(b_word)));
+    features.extend(
+      words
+        .into_iter()
+        .take(MAX_TEXT_FEATURES)
+        .map(|(word, _)| word),
+    );
+  }
+
+  features
+}
@@ -0,0 +1,76 @@
+pub mod model;
+
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use wither::bson::DateTime;
+use wither::bson::{doc, oid::ObjectId};
+use wither::Model;
+
+use crate::lib::serde::serialize_bson_datetime_as_iso_string;
+use crate::lib::serde::serialize_bson_datetime_option_as_iso_string;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+
+// Public permalink of a single resource. Shared resources are visible to
+// anyone with the link until the share is revoked, regardless of the list
+// visibility.
+#[derive(Debug, Clone, Model, Validate, Serialize, Deserialize)]
+#[model(index(
+  keys = r#"doc!{ "short_id": 1 }"#,
+  options = r#"doc!{ "unique": true }"#
+))]
+#[model(index(keys = r#"doc!{ "resource": 1 }"#))]
+#[model(index(keys = r#"doc!{ "list": 1 }"#))]
+pub struct Share {
+  #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+  pub id: Option<ObjectId>,
+  pub short_id: String,
+  pub user: ObjectId,
+  pub list: ObjectId,
+  pub resource: ObjectId,
+  // Note displayed with the shared resource.
+  #[validate(length(max = 10000))]
+  pub note: Option<String>,
+  // Whether the resource annotations are displayed with the shared resource.
+  pub include_annotations: bool,
+  pub views_count: i64,
+  pub created_at: DateTime,
+  pub updated_at: DateTime,
+  pub revoked_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PrivateShare {
+  #[serde(alias = "_id", serialize_with = "serialize_object_id_as_hex_string")]
+  pub id: ObjectId,
+  pub short_id: String,
+  pub url: String,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  pub resource: ObjectId,
+  pub note: Option<String>,
+  pub include_annotations: bool,
+  pub views_count: i64,
+  #[serde(serialize_with = "serialize_bson_datetime_as_iso_string")]
+  pub created_at: DateTime,
+  #[serde(serialize_with = "serialize_bson_datetime_option_as_iso_string")]
+  pub revoked_at: Option<DateTime>,
+}
+
+impl Share {
+  pub fn get_url(&self, base_url: &str) -> String {
+    format!("{}/r/{}", base_url, self.short_id)
+  }
+
+  pub fn to_private_schema(&self, base_url: &str) -> PrivateShare {
+    PrivateShare {
+      id: self.id.clone().unwrap(),
+      short_id: self.short_id.clone(),
+      url: self.get_url(base_url),
+      resource: self.resource.clone(),
+      note: self.note.clone(),
+      include_annotations: self.include_annotations,
+      views_count: self.views_count,
+      created_at: self.created_at,
+      revoked_at: self.revoked_at,
+    }
+  }
+}
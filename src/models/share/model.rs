@@ -0,0 +1,64 @@
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::mongodb::options::FindOneAndUpdateOptions;
+use wither::mongodb::options::ReturnDocument;
+
+use crate::database;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::models;
+use crate::models::share::Share;
+use crate::models::Model as ModelTrait;
+
+#[derive(Clone)]
+pub struct Model {
+  pub database: database::Database,
+}
+
+impl models::Model<Share> for Model {
+  fn get_database(&self) -> &database::Database {
+    &self.database
+  }
+}
+
+impl Model {
+  pub fn new(database: database::Database) -> Self {
+    Self { database }
+  }
+
+  // Returns the share with the specified short ID when it was not revoked,
+  // counting the view.
+  pub async fn view(&self, short_id: &str) -> Result<Option<Share>, Error> {
+    let options = FindOneAndUpdateOptions::builder()
+      .return_document(ReturnDocument::After)
+      .build();
+
+    self
+      .find_one_and_update(
+        doc! { "short_id": short_id, "revoked_at": Bson::Null },
+        doc! { "$inc": { "views_count": 1 } },
+        Some(options),
+      )
+      .await
+  }
+
+  pub async fn revoke(
+    &self,
+    share_id: &ObjectId,
+    user_id: &ObjectId,
+  ) -> Result<Option<Share>, Error> {
+    let now = Bson::DateTime(date::now().into());
+    let options = FindOneAndUpdateOptions::builder()
+      .return_document(ReturnDocument::After)
+      .build();
+
+    self
+      .find_one_and_update(
+        doc! { "_id": share_id, "user": user_id },
+        doc! { "$set": { "revoked_at": &now, "updated_at": &now } },
+        Some(options),
+      )
+      .await
+  }
+}
@@ -0,0 +1,114 @@
+use maud::{html, Markup, DOCTYPE};
+
+use crate::lib::util;
+use crate::models::annotation::Annotation;
+use crate::models::resource::Resource;
+use crate::models::share::Share;
+
+const STYLE: &str = "body { font-family: Arial, Helvetica, sans-serif; max-width: 680px; \
+  margin: 40px auto; padding: 0 20px; color: #222; line-height: 1.5; } \
+  img.thumbnail { max-width: 100%; border-radius: 4px; } \
+  blockquote { border-left: 3px solid #ddd; margin: 0; padding-left: 12px; color: #555; } \
+  footer { margin-top: 40px; font-size: 14px; color: #888; }";
+
+// Public preview of a shared resource. OpenGraph and Twitter card tags are
+// included so the link renders a rich preview when posted elsewhere.
+pub fn create_shared_resource_page(
+  share: &Share,
+  resource: &Resource,
+  annotations: &[Annotation],
+  share_url: &str,
+) -> Markup {
+  let title = resource.title.as_deref().unwrap_or(&resource.url);
+  // Resources created before URL schemes were validated can have
+  // javascript: or data: URLs, those are not rendered as links.
+  let url = Some(resource.url.as_str()).filter(|url| util::is_web_url(url));
+  let description = share
+    .note
+    .as_deref()
+    .or(resource.description.as_deref())
+    .unwrap_or_default();
+
+  html! {
+      (DOCTYPE)
+      html lang="en" {
+          head {
+              meta charset="utf-8";
+              meta name="viewport" content="width=device-width, initial-scale=1";
+              title { (title) " | Listas" }
+              meta name="description" content=(description);
+              meta property="og:type" content="article";
+              meta property="og:site_name" content="Listas";
+              meta property="og:title" content=(title);
+              meta property="og:description" content=(description);
+              meta property="og:url" content=(share_url);
+              @if let Some(ref thumbnail) = resource.thumbnail {
+                  meta property="og:image" content=(thumbnail);
+                  meta name="twitter:image" content=(thumbnail);
+                  meta name="twitter:card" content="summary_large_image";
+              } @else {
+                  meta name="twitter:card" content="summary";
+              }
+              meta name="twitter:title" content=(title);
+              meta name="twitter:description" content=(description);
+              link rel="canonical" href=(share_url);
+              style type="text/css" { (STYLE) }
+          }
+          body {
+              article {
+                  @if let Some(url) = url {
+                      h1 { a href=(url) rel="noopener" { (title) } }
+                  } @else {
+                      h1 { (title) }
+                  }
+                  @if let Some(ref publisher) = resource.publisher {
+                      p { small { (publisher) } }
+                  }
+                  @if let Some(ref thumbnail) = resource.thumbnail {
+                      img.thumbnail src=(thumbnail) alt=(title);
+                  }
+                  @if let Some(ref description) = resource.description {
+                      p { (description) }
+                  }
+                  @if let Some(ref note) = share.note {
+                      h3 { "Note" }
+                      p { (note) }
+                  }
+                  @if !annotations.is_empty() {
+                      h3 { "Annotations" }
+                      @for annotation in annotations {
+                          @if let Some(ref highlight) = annotation.highlight {
+                              blockquote { (highlight.quote) }
+                          }
+                          @if let Some(ref note) = annotation.note {
+                              p { (note) }
+                          }
+                      }
+                  }
+                  @if let Some(url) = url {
+                      p { a href=(url) rel="noopener" { "Read the original" } }
+                  }
+              }
+              footer { "Shared with " a href="https://listas.io" { "Listas" } }
+          }
+      }
+  }
+}
+
+pub fn create_not_found_page() -> Markup {
+  html! {
+      (DOCTYPE)
+      html lang="en" {
+          head {
+              meta charset="utf-8";
+              title { "Not found | Listas" }
+              meta name="robots" content="noindex";
+              style type="text/css" { (STYLE) }
+          }
+          body {
+              h1 { "This link is no longer available" }
+              p { "The shared resource was removed or the link was revoked." }
+          }
+      }
+  }
+}
@@ -0,0 +1,226 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use wither::bson;
+use wither::bson::doc;
+
+use crate::auth;
+use crate::auth::UserID;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::lib::id::ID;
+use crate::lib::pagination::Pagination;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::PrivateActivity;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/lists/{id}/activity")
+      .route(web::get().to(query_activity))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/lists/{id}/activity/undo")
+      .route(web::post().to(undo_last_activity))
+      .wrap(auth),
+  );
+}
+
+async fn query_activity(
+  ctx: Ctx,
+  id: ID,
+  user_id: UserID,
+  pagination: web::Query<Pagination>,
+) -> Response {
+  let list_id = id.0;
+  let user_id = user_id.0;
+  let skip = pagination.skip.unwrap_or(0);
+  let limit = pagination.limit.unwrap_or(50);
+
+  let list_exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !list_exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let activities = ctx
+    .models
+    .activity
+    .find_by_list(&list_id, skip, limit)
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect::<Vec<PrivateActivity>>();
+
+  debug!("Returning list activity");
+  let res = HttpResponse::Ok().json(activities);
+  Ok(res)
+}
+
+// Reverts the latest change of the list that was not undone yet.
+async fn undo_last_activity(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let list_id = id.0;
+  let user_id = user_id.0;
+
+  let list_exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !list_exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let activity = match ctx.models.activity.find_last_by_list(&list_id).await? {
+    Some(activity) => activity,
+    None => {
+      debug!("No activity to undo, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  if !activity.action.is_undoable() {
+    debug!("Last activity can not be undone, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let previous = activity.previous.clone().unwrap_or_default();
+  let res = match activity.action {
+    Action::Remove => restore_resource(&ctx, &activity, previous).await?,
+    Action::Move => {
+      let previous_list = previous.get_object_id("list").ok();
+      let previous_position = previous.get_str("position").ok();
+      let (previous_list, previous_position) = match (previous_list, previous_position) {
+        (Some(list), Some(position)) => (list.clone(), position.to_owned()),
+        _ => {
+          debug!("Move activity has no previous list, returning 400 status code");
+          return Ok(HttpResponse::BadRequest().finish());
+        }
+      };
+
+      let is_list_owner = ctx
+        .models
+        .list
+        .exists(doc! { "_id": &previous_list, "user": &user_id })
+        .await?;
+
+      let resource = ctx
+        .models
+        .resource
+        .find_one(doc! { "_id": &activity.resource, "user": &user_id }, None)
+        .await?;
+
+      match resource {
+        Some(resource) if is_list_owner => {
+          ctx
+            .models
+            .list
+            .move_resource(&resource, &previous_list, previous_position)
+            .await?;
+          None
+        }
+        _ => {
+          debug!("Resource or previous list not found, returning 404 status code");
+          Some(HttpResponse::NotFound().finish())
+        }
+      }
+    }
+    _ => {
+      // Restored values can be part of the resource similarity signature.
+      let mut previous = previous;
+      if activity.action == Action::Update {
+        previous.insert("signature_indexed_at", bson::Bson::Null);
+      }
+      let result = ctx
+        .models
+        .resource
+        .update_one(
+          doc! { "_id": &activity.resource, "user": &user_id },
+          doc! { "$set": previous },
+          None,
+        )
+        .await?;
+
+      if result.matched_count == 0 {
+        debug!("Resource not found, returning 404 status code");
+        Some(HttpResponse::NotFound().finish())
+      } else {
+        None
+      }
+    }
+  };
+
+  if let Some(res) = res {
+    return Ok(res);
+  }
+
+  let activity_id = activity.id.clone().unwrap();
+  ctx.models.activity.set_undone_at(&activity_id).await?;
+
+  ctx
+    .models
+    .list
+    .update_last_activity_at(&list_id)
+    .await
+    .map_err(|err| {
+      error!(
+        "Failed to update last activity for list {}. Error {}",
+        &list_id, err
+      )
+    })?;
+
+  let activity = Activity {
+    undone_at: Some(date::now()),
+    ..activity
+  };
+
+  debug!("Activity undone, returning 200 status code");
+  let activity: PrivateActivity = activity.into();
+  let res = HttpResponse::Ok().json(activity);
+  Ok(res)
+}
+
+// Inserts the removed resource again. Annotations are removed with the
+// resource and can not be restored.
+async fn restore_resource(
+  ctx: &Ctx,
+  activity: &Activity,
+  previous: bson::Document,
+) -> actix_web::Result<Option<HttpResponse>> {
+  let resource = bson::from_document::<Resource>(previous).map_err(Error::from)?;
+
+  let is_list_owner = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &resource.list, "user": &activity.user })
+    .await?;
+
+  if !is_list_owner {
+    debug!("Resource list not found, returning 404 status code");
+    return Ok(Some(HttpResponse::NotFound().finish()));
+  }
+
+  // The resource signature was removed with the resource.
+  let resource = Resource {
+    signature_indexed_at: None,
+    ..resource
+  };
+  ctx.models.list.create_resource(resource).await?;
+  Ok(None)
+}
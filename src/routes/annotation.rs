@@ -0,0 +1,337 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::Deserialize;
+use validator::Validate;
+use wither::bson;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::mongodb;
+use wither::mongodb::options::FindOneAndUpdateOptions;
+
+use crate::auth::UserID;
+use crate::errors::Error;
+use crate::lib::id::ID;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::annotation::Annotation;
+use crate::models::annotation::AnnotationUpdate;
+use crate::models::annotation::Highlight;
+use crate::models::annotation::HighlightSource;
+use crate::models::annotation::PrivateAnnotation;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+use crate::{auth, lib::date};
+
+#[derive(Deserialize)]
+pub struct HighlightCreate {
+  pub source: Option<HighlightSource>,
+  pub start: i32,
+  pub end: i32,
+}
+
+#[derive(Deserialize)]
+pub struct AnnotationCreate {
+  pub note: Option<String>,
+  pub highlight: Option<HighlightCreate>,
+}
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+type AnnotationCreateBody = web::Json<AnnotationCreate>;
+type AnnotationUpdateBody = web::Json<AnnotationUpdate>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/resources/{id}/annotations")
+      .route(web::get().to(query_annotations))
+      .route(web::post().to(create_annotation))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/annotations/{id}")
+      .route(web::put().to(update_annotation))
+      .route(web::delete().to(remove_annotation))
+      .wrap(auth),
+  );
+}
+
+async fn query_annotations(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let resource_exists = ctx
+    .models
+    .resource
+    .exists(doc! { "_id": &resource_id, "user": &user_id })
+    .await?;
+
+  if !resource_exists {
+    debug!("Resource not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let annotations = ctx
+    .models
+    .annotation
+    .find_by_resource(&resource_id)
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect::<Vec<PrivateAnnotation>>();
+
+  debug!("Returning annotations");
+  let res = HttpResponse::Ok().json(annotations);
+  Ok(res)
+}
+
+async fn create_annotation(
+  ctx: Ctx,
+  id: ID,
+  body: AnnotationCreateBody,
+  user_id: UserID,
+) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let resource = ctx
+    .models
+    .resource
+    .find_one(doc! { "_id": &resource_id, "user": &user_id }, None)
+    .await?;
+
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let body = body.into_inner();
+  if body.note.is_none() && body.highlight.is_none() {
+    debug!("Annotation has no note nor highlight, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let highlight = match body.highlight {
+    None => None,
+    Some(highlight) => match create_highlight(&resource, highlight) {
+      Some(highlight) => Some(highlight),
+      None => {
+        debug!("Highlight range is not valid, returning 400 status code");
+        return Ok(HttpResponse::BadRequest().finish());
+      }
+    },
+  };
+
+  let now = date::now();
+  let annotation = Annotation {
+    id: None,
+    user: user_id,
+    list: resource.list.clone(),
+    resource: resource_id.clone(),
+    highlight,
+    note: body.note,
+    created_at: now,
+    updated_at: now,
+  };
+
+  if annotation.is_empty() {
+    debug!("Annotation has no note nor highlight, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  match annotation.validate() {
+    Ok(_) => (),
+    Err(_err) => {
+      debug!("Failed creating Annotation, payload is not valid. Returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let annotation = ctx.models.annotation.create(annotation).await?;
+  sync_resource_annotations_text(&ctx, &resource_id).await?;
+
+  ctx
+    .models
+    .activity
+    .record(Activity::from_annotation(
+      &annotation,
+      Action::CreateAnnotation,
+    ))
+    .await;
+
+  debug!("Returning created annotation");
+  let annotation: PrivateAnnotation = annotation.into();
+  let res = HttpResponse::Created().json(annotation);
+  Ok(res)
+}
+
+async fn update_annotation(
+  ctx: Ctx,
+  id: ID,
+  body: AnnotationUpdateBody,
+  user_id: UserID,
+) -> Response {
+  let annotation_id = id.0;
+  let user_id = user_id.0;
+
+  let annotation = ctx
+    .models
+    .annotation
+    .find_one(doc! { "_id": &annotation_id, "user": &user_id }, None)
+    .await?;
+
+  let annotation = match annotation {
+    Some(annotation) => annotation,
+    None => {
+      debug!("Annotation not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let mut body = body.into_inner();
+  let body = AnnotationUpdate::new(&mut body);
+
+  // The updated annotation is validated before it is written.
+  let updated = Annotation {
+    note: body.note.clone().or_else(|| annotation.note.clone()),
+    ..annotation.clone()
+  };
+
+  if updated.is_empty() {
+    debug!("Annotation has no note nor highlight, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  match updated.validate() {
+    Ok(_) => (),
+    Err(_err) => {
+      debug!("Failed updating Annotation, payload is not valid. Returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let previous = doc! { "note": bson::to_bson(&annotation.note).unwrap() };
+  let update = bson::to_document(&body).unwrap();
+  let options = FindOneAndUpdateOptions::builder()
+    .return_document(mongodb::options::ReturnDocument::After)
+    .build();
+
+  let annotation = ctx
+    .models
+    .annotation
+    .find_one_and_update(
+      doc! { "_id": &annotation_id, "user": &user_id },
+      doc! { "$set": update },
+      Some(options),
+    )
+    .await?;
+
+  let annotation = match annotation {
+    Some(annotation) => annotation,
+    None => {
+      debug!("Annotation not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  sync_resource_annotations_text(&ctx, &annotation.resource).await?;
+
+  let activity = Activity::from_annotation(&annotation, Action::UpdateAnnotation);
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
+  debug!("Returning updated annotation");
+  let annotation: PrivateAnnotation = annotation.into();
+  let res = HttpResponse::Ok().json(annotation);
+  Ok(res)
+}
+
+async fn remove_annotation(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let annotation_id = id.0;
+  let user_id = user_id.0;
+
+  let annotation = ctx
+    .models
+    .annotation
+    .find_one(doc! { "_id": &annotation_id, "user": &user_id }, None)
+    .await?;
+
+  let annotation = match annotation {
+    Some(annotation) => annotation,
+    None => {
+      debug!("Annotation not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  ctx
+    .models
+    .annotation
+    .delete_one(doc! { "_id": &annotation_id, "user": &user_id })
+    .await?;
+
+  sync_resource_annotations_text(&ctx, &annotation.resource).await?;
+
+  let activity = Activity::from_annotation(&annotation, Action::RemoveAnnotation);
+  let previous = bson::to_document(&annotation).unwrap();
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
+  debug!("Annotation removed, returning 204 status code");
+  let res = HttpResponse::NoContent().finish();
+  Ok(res)
+}
+
+// Validates the highlighted range against the resource stored content and
+// returns the highlight including the quoted text.
+fn create_highlight(resource: &Resource, highlight: HighlightCreate) -> Option<Highlight> {
+  let source = highlight.source.unwrap_or(HighlightSource::Text);
+  let content = match source {
+    HighlightSource::Text => resource.text.as_ref()?,
+    HighlightSource::Html => resource.html.as_ref()?,
+  };
+
+  if highlight.start < 0 || highlight.end <= highlight.start {
+    return None;
+  }
+
+  let start = highlight.start as usize;
+  let end = highlight.end as usize;
+  if end > content.chars().count() {
+    return None;
+  }
+
+  let quote = content
+    .chars()
+    .skip(start)
+    .take(end - start)
+    .collect::<String>();
+
+  Some(Highlight {
+    source,
+    start: highlight.start,
+    end: highlight.end,
+    quote,
+  })
+}
+
+async fn sync_resource_annotations_text(ctx: &Ctx, resource_id: &ObjectId) -> Result<(), Error> {
+  let text = ctx.models.annotation.get_search_text(resource_id).await?;
+  ctx
+    .models
+    .resource
+    .update_annotations_text(resource_id, text)
+    .await
+}
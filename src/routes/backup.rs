@@ -0,0 +1,188 @@
+use actix_web::error::BlockingError;
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use wither::bson::doc;
+use wither::mongodb::options::FindOptions;
+
+use crate::auth;
+use crate::auth::UserID;
+use crate::errors::Error;
+use crate::jobs::create_backup;
+use crate::jobs::restore_backup;
+use crate::lib::backup;
+use crate::lib::id::ID;
+use crate::models::backup::Backup;
+use crate::models::backup::Kind;
+use crate::models::backup::PrivateBackup;
+use crate::models::backup::Status;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+// Maximum size of the uploaded archive when restoring a backup.
+const MAX_RESTORE_SIZE: usize = 100 * 1024 * 1024;
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/backups")
+      .route(web::get().to(query_backups))
+      .route(web::post().to(create_backup))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/backups/restore")
+      .app_data(web::PayloadConfig::new(MAX_RESTORE_SIZE))
+      .route(web::post().to(restore_backup))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/backups/{id}")
+      .route(web::get().to(find_backup))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/backups/{id}/download")
+      .route(web::get().to(download_backup))
+      .wrap(auth),
+  );
+}
+
+async fn query_backups(ctx: Ctx, user_id: UserID) -> Response {
+  let user_id = user_id.0;
+  let options = FindOptions::builder()
+    .sort(doc! { "created_at": -1 })
+    .build();
+
+  let backups = ctx
+    .models
+    .backup
+    .find(doc! { "user": &user_id }, Some(options))
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect::<Vec<PrivateBackup>>();
+
+  debug!("Returning backups");
+  let res = HttpResponse::Ok().json(backups);
+  Ok(res)
+}
+
+async fn create_backup(ctx: Ctx, user_id: UserID) -> Response {
+  let user_id = user_id.0;
+  let backup = Backup::new(user_id, Kind::Backup, backup::BACKUP_VERSION);
+  let backup = ctx.models.backup.create(backup).await?;
+
+  let payload = create_backup::JobPayload {
+    backup: backup.id.clone().unwrap().to_hex(),
+  };
+  ctx.jobs.queue("create_backup", payload).await;
+
+  debug!("Backup queued, returning 202 status code");
+  let backup: PrivateBackup = backup.into();
+  let res = HttpResponse::Accepted().json(backup);
+  Ok(res)
+}
+
+async fn find_backup(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let backup_id = id.0;
+  let user_id = user_id.0;
+
+  let backup = ctx
+    .models
+    .backup
+    .find_one(doc! { "_id": &backup_id, "user": &user_id }, None)
+    .await?;
+
+  let backup = match backup {
+    Some(backup) => backup,
+    None => {
+      debug!("Backup not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  debug!("Returning backup");
+  let backup: PrivateBackup = backup.into();
+  let res = HttpResponse::Ok().json(backup);
+  Ok(res)
+}
+
+async fn download_backup(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let backup_id = id.0;
+  let user_id = user_id.0;
+
+  let backup_exists = ctx
+    .models
+    .backup
+    .exists(doc! {
+      "_id": &backup_id,
+      "user": &user_id,
+      "kind": wither::bson::to_bson(&Kind::Backup).unwrap(),
+      "status": wither::bson::to_bson(&Status::Completed).unwrap()
+    })
+    .await?;
+
+  if !backup_exists {
+    debug!("Completed backup not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let data = match ctx.archiver.get_backup(&backup_id).await? {
+    Some(data) => data,
+    None => {
+      debug!("Backup archive not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  debug!("Returning backup archive");
+  let content_disposition = format!(
+    "attachment; filename=\"listas-backup-{}.json.gz\"",
+    backup_id
+  );
+  let res = HttpResponse::Ok()
+    .content_type("application/gzip")
+    .header("Content-Disposition", content_disposition)
+    .body(data);
+  Ok(res)
+}
+
+// Restores an archive downloaded from any environment into the account of
+// the requesting user. The archive is validated and stored, the restore_backup
+// job restores it and the summary is stored in the returned backup.
+async fn restore_backup(ctx: Ctx, body: web::Bytes, user_id: UserID) -> Response {
+  let user_id = user_id.0;
+  let data = body.to_vec();
+
+  let (data, version) = web::block(move || {
+    let archive = backup::decode(&data)?;
+    Ok::<(Vec<u8>, i32), Error>((data, archive.version))
+  })
+  .await
+  .map_err(|err| match err {
+    BlockingError::Error(err) => err,
+    BlockingError::Canceled => Error::Backup("Archive validation was cancelled".to_owned()),
+  })?;
+
+  let backup = Backup::new(user_id, Kind::Restore, version);
+  let backup = ctx.models.backup.create(backup).await?;
+  let backup_id = backup.id.clone().unwrap();
+  ctx.archiver.put_backup(&backup_id, data).await?;
+
+  let payload = restore_backup::JobPayload {
+    backup: backup_id.to_hex(),
+  };
+  ctx.jobs.queue("restore_backup", payload).await;
+
+  debug!("Backup restore queued, returning 202 status code");
+  let backup: PrivateBackup = backup.into();
+  let res = HttpResponse::Accepted().json(backup);
+  Ok(res)
+}
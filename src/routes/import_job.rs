@@ -0,0 +1,110 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use wither::bson::doc;
+
+use crate::auth;
+use crate::auth::UserID;
+use crate::jobs::create_resources::create_payloads;
+use crate::lib::id::ID;
+use crate::lib::pagination::Pagination;
+use crate::models::import_job::PrivateImportJob;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+// Items returned when the request has no limit.
+const DEFAULT_ITEMS_LIMIT: i64 = 50;
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/imports/{id}")
+      .route(web::get().to(get_import_job))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/imports/{id}/retry")
+      .route(web::post().to(retry_import_job))
+      .wrap(auth),
+  );
+}
+
+// The job items are paginated with the skip and limit query parameters.
+async fn get_import_job(
+  ctx: Ctx,
+  id: ID,
+  user_id: UserID,
+  pagination: web::Query<Pagination>,
+) -> Response {
+  let job_id = id.0;
+  let user_id = user_id.0;
+
+  let job = ctx
+    .models
+    .import_job
+    .find_one(doc! { "_id": &job_id, "user": &user_id }, None)
+    .await?;
+
+  let job = match job {
+    Some(job) => job,
+    None => {
+      debug!("Import job not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let skip = pagination.skip.unwrap_or(0);
+  let limit = pagination.limit.unwrap_or(DEFAULT_ITEMS_LIMIT);
+  let items = ctx
+    .models
+    .import_job_item
+    .find_by_job(&job_id, skip, limit)
+    .await?;
+
+  debug!("Returning import job");
+  let job = PrivateImportJob::new(job, items);
+  let res = HttpResponse::Ok().json(job);
+  Ok(res)
+}
+
+// Queues the failed URLs of the import job again.
+async fn retry_import_job(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let job_id = id.0;
+  let user_id = user_id.0;
+
+  let job = ctx
+    .models
+    .import_job
+    .find_one(doc! { "_id": &job_id, "user": &user_id }, None)
+    .await?;
+
+  let job = match job {
+    Some(job) => job,
+    None => {
+      debug!("Import job not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let items = ctx.models.import_job.reset_failed_items(&job).await?;
+  if items.is_empty() {
+    debug!("Import job has no failed items, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  for payload in create_payloads(&job, &items) {
+    ctx.jobs.queue("create_resources", payload).await;
+  }
+
+  let job = ctx.models.import_job.find_by_id(&job_id).await?.unwrap();
+
+  // Only the retried items are returned.
+  debug!("Import job retried, returning 200 status code");
+  let job = PrivateImportJob::new(job, items);
+  let res = HttpResponse::Ok().json(job);
+  Ok(res)
+}
@@ -0,0 +1,196 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+
+use crate::actors::subscription;
+use crate::auth;
+use crate::auth::UserID;
+use crate::lib::util::to_object_id;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+const MAX_TRIAGE_ITEMS: usize = 100;
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+
+#[derive(Deserialize)]
+struct TriageItem {
+  resource: String,
+  list: String,
+}
+
+#[derive(Deserialize)]
+struct TriageBody {
+  items: Vec<TriageItem>,
+}
+
+#[derive(Serialize)]
+struct SkippedItem {
+  resource: String,
+  reason: String,
+}
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/inbox")
+      .route(web::get().to(get_inbox))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/inbox/triage")
+      .route(web::post().to(triage_inbox))
+      .wrap(auth),
+  );
+}
+
+async fn get_inbox(ctx: Ctx, user: UserID) -> Response {
+  let user_id = user.0;
+
+  let list = ctx.models.list.find_or_create_inbox(&user_id).await?;
+  let list = ctx.models.list.to_private_schema(&list).await?;
+
+  debug!("Returning inbox list");
+  let res = HttpResponse::Ok().json(list);
+  Ok(res)
+}
+
+// Moves inbox resources to other lists of the user, resources are appended
+// at the end of the target lists. Items that can not be moved are skipped.
+async fn triage_inbox(ctx: Ctx, body: web::Json<TriageBody>, user: UserID) -> Response {
+  let user_id = user.0;
+  let body = body.into_inner();
+
+  if body.items.is_empty() || body.items.len() > MAX_TRIAGE_ITEMS {
+    debug!("Triage items count is not valid, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let mut items = vec![];
+  for item in body.items {
+    items.push((to_object_id(item.resource)?, to_object_id(item.list)?));
+  }
+
+  let inbox = ctx.models.list.find_or_create_inbox(&user_id).await?;
+  let inbox_id = inbox.id.unwrap();
+
+  let resource_ids = items
+    .iter()
+    .map(|(resource_id, _)| resource_id.clone())
+    .collect::<Vec<ObjectId>>();
+  let resources = ctx
+    .models
+    .resource
+    .find(
+      doc! { "_id": { "$in": resource_ids }, "user": &user_id, "list": &inbox_id },
+      None,
+    )
+    .await?
+    .into_iter()
+    .map(|resource| (resource.id.clone().unwrap(), resource))
+    .collect::<HashMap<ObjectId, Resource>>();
+
+  let list_ids = items
+    .iter()
+    .map(|(_, list_id)| list_id.clone())
+    .collect::<Vec<ObjectId>>();
+  let lists = ctx
+    .models
+    .list
+    .find(
+      doc! { "_id": { "$in": list_ids }, "user": &user_id, "archived_at": Bson::Null },
+      None,
+    )
+    .await?
+    .into_iter()
+    .filter_map(|list| list.id)
+    .collect::<Vec<ObjectId>>();
+
+  let mut skipped = vec![];
+  let mut seen: HashSet<ObjectId> = HashSet::new();
+  let mut moves: HashMap<ObjectId, Vec<Resource>> = HashMap::new();
+
+  for (resource_id, list_id) in items {
+    // The same resource sent twice is moved to the first list.
+    let is_duplicated = !seen.insert(resource_id.clone());
+    let reason = match resources.get(&resource_id) {
+      _ if is_duplicated => Some("Resource is duplicated"),
+      None => Some("Resource not found in the inbox"),
+      Some(_) if list_id == inbox_id => Some("Resource already belongs to the inbox"),
+      Some(_) if !lists.contains(&list_id) => Some("List not found"),
+      Some(_) => None,
+    };
+
+    if let Some(reason) = reason {
+      skipped.push(SkippedItem {
+        resource: resource_id.to_hex(),
+        reason: reason.to_owned(),
+      });
+      continue;
+    }
+
+    moves
+      .entry(list_id)
+      .or_default()
+      .push(resources[&resource_id].clone());
+  }
+
+  let mut moved = vec![];
+  for (list_id, resources) in moves {
+    let positions = ctx
+      .models
+      .list
+      .get_next_resource_positions(&list_id, resources.len())
+      .await?;
+
+    for (resource, position) in resources.into_iter().zip(positions) {
+      let resource_id = resource.id.clone().unwrap();
+
+      ctx
+        .models
+        .list
+        .move_resource(&resource, &list_id, position)
+        .await?;
+
+      let activity = Activity {
+        list: list_id.clone(),
+        ..Activity::new(&resource, Action::Move, Source::User)
+      };
+      let previous = doc! { "list": &resource.list, "position": &resource.position };
+      ctx
+        .models
+        .activity
+        .record(activity.with_previous(previous))
+        .await;
+
+      ctx
+        .actors
+        .subscription
+        .try_send(subscription::on_resource_created::ResourceCreated {
+          resource_id: resource_id.clone(),
+        })
+        .map_err(|err| error!("Failed to send message to subscription actor, {}", err))?;
+
+      moved.push(resource_id.to_hex());
+    }
+  }
+
+  debug!("Inbox triaged, returning 200 status code");
+  let res = HttpResponse::Ok().json(json!({
+    "moved": moved,
+    "skipped": skipped
+  }));
+  Ok(res)
+}
@@ -1,17 +1,28 @@
 use actix_web::{web, HttpResponse};
 use actix_web_httpauth::middleware::HttpAuthentication;
-use serde::Deserialize;
+use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use url::Url;
 use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::mongodb::options::FindOptions;
 
 use crate::auth;
 use crate::auth::UserID;
+use crate::errors::Error;
+use crate::jobs::poll_integrations;
 use crate::lib::date;
 use crate::lib::id::ID;
+use crate::lib::opml;
 use crate::lib::util::parse_url;
 use crate::lib::util::to_object_id;
 use crate::models::integration;
+use crate::models::integration::github_stars::GitHubStars;
 use crate::models::integration::rss::Rss;
+use crate::models::integration::youtube_playlist::YouTubePlaylist;
 use crate::models::integration::PrivateIntegration;
 use crate::models::Model as ModelTrait;
 use crate::Context;
@@ -22,6 +33,33 @@ struct RSSPayload {
   url: String,
 }
 
+#[derive(Deserialize)]
+struct OpmlPayload {
+  payload: String,
+  // List for the feeds that are not inside a folder, defaults to a "Feeds"
+  // list.
+  list: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SkippedFeed {
+  url: String,
+  reason: &'static str,
+}
+
+#[derive(Deserialize)]
+struct GitHubStarsPayload {
+  list: String,
+  username: String,
+}
+
+#[derive(Deserialize)]
+struct YouTubePlaylistPayload {
+  list: String,
+  // Playlist ID or URL, E.g. "https://www.youtube.com/playlist?list=<id>".
+  playlist: String,
+}
+
 #[derive(Deserialize)]
 struct SubscriptionPayload {
   subscribe_from: String,
@@ -37,6 +75,11 @@ type Ctx = web::Data<Context>;
 type Response = actix_web::Result<HttpResponse>;
 type RSSCreateBody = web::Json<RSSPayload>;
 type SubscriptionCreateBody = web::Json<SubscriptionPayload>;
+type OpmlImportBody = web::Json<OpmlPayload>;
+type GitHubStarsCreateBody = web::Json<GitHubStarsPayload>;
+type YouTubePlaylistCreateBody = web::Json<YouTubePlaylistPayload>;
+
+const DEFAULT_FEEDS_LIST_TITLE: &str = "Feeds";
 
 pub fn create_router(cfg: &mut web::ServiceConfig) {
   let auth = HttpAuthentication::bearer(auth::validator);
@@ -53,6 +96,25 @@ pub fn create_router(cfg: &mut web::ServiceConfig) {
       .wrap(auth.clone()),
   );
 
+  cfg.service(
+    web::resource("/integrations/rss/opml")
+      .route(web::get().to(export_rss_opml))
+      .route(web::post().to(import_rss_opml))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/integrations/github-stars")
+      .route(web::post().to(create_github_stars_integration))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/integrations/youtube-playlist")
+      .route(web::post().to(create_youtube_playlist_integration))
+      .wrap(auth.clone()),
+  );
+
   cfg.service(
     web::resource("/integrations/listas-subscription")
       .route(web::post().to(create_subscription_integration))
@@ -112,27 +174,7 @@ async fn create_rss_integration(ctx: Ctx, body: RSSCreateBody, user_id: UserID)
     return Ok(HttpResponse::BadRequest().finish());
   }
 
-  let now = date::now();
-  let subscription = ctx.rss.subscribe(&url).await?;
-  let integration = ctx
-    .models
-    .integration
-    .create(integration::Integration {
-      id: None,
-      user: user_id.clone(),
-      list: list_id.clone(),
-      created_at: now,
-      updated_at: now,
-      kind: integration::Kind::from_str("rss").unwrap(),
-      listas_subscription: None,
-      rss: Some(Rss {
-        url: subscription.url,
-        subscription_id: subscription.id,
-        // feed_type: subscription.feed_type,
-        metadata: subscription.metadata,
-      }),
-    })
-    .await?;
+  let integration = create_rss(&ctx, &user_id, &list_id, &url).await?;
 
   // // TODO: Move this to an actor / background job
   // let resources = ctx
@@ -188,6 +230,440 @@ async fn create_rss_integration(ctx: Ctx, body: RSSCreateBody, user_id: UserID)
   Ok(res)
 }
 
+async fn create_github_stars_integration(
+  ctx: Ctx,
+  body: GitHubStarsCreateBody,
+  user_id: UserID,
+) -> Response {
+  let list_id = to_object_id(body.list.clone())?;
+  let user_id = user_id.0;
+  let username = body.username.trim().trim_start_matches('@').to_owned();
+
+  if !is_valid_github_username(&username) {
+    debug!("GitHub username is not valid, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let list_exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !list_exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let integration_exists = ctx
+    .models
+    .integration
+    .exists(doc! {
+      "list": &list_id,
+      "kind": "github-stars",
+      "github_stars.username": &username
+    })
+    .await?;
+
+  if integration_exists {
+    debug!("List already has the GitHub stars integration, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let now = date::now();
+  let integration = ctx
+    .models
+    .integration
+    .create(integration::Integration {
+      id: None,
+      user: user_id,
+      list: list_id,
+      created_at: now,
+      updated_at: now,
+      kind: integration::Kind::from_str("github-stars").unwrap(),
+      rss: None,
+      listas_subscription: None,
+      github_stars: Some(GitHubStars { username }),
+      youtube_playlist: None,
+      polled_at: None,
+    })
+    .await?;
+
+  queue_poll(&ctx, &integration).await;
+
+  debug!("Returning integration and 200 status code");
+  let integration: PrivateIntegration = integration.into();
+  let res = HttpResponse::Ok().json(integration);
+  Ok(res)
+}
+
+async fn create_youtube_playlist_integration(
+  ctx: Ctx,
+  body: YouTubePlaylistCreateBody,
+  user_id: UserID,
+) -> Response {
+  let list_id = to_object_id(body.list.clone())?;
+  let user_id = user_id.0;
+
+  let playlist_id = match get_youtube_playlist_id(&body.playlist) {
+    Some(playlist_id) => playlist_id,
+    None => {
+      debug!("YouTube playlist is not valid, returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let list_exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !list_exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let integration_exists = ctx
+    .models
+    .integration
+    .exists(doc! {
+      "list": &list_id,
+      "kind": "youtube-playlist",
+      "youtube_playlist.playlist_id": &playlist_id
+    })
+    .await?;
+
+  if integration_exists {
+    debug!("List already has the YouTube playlist integration, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let now = date::now();
+  let integration = ctx
+    .models
+    .integration
+    .create(integration::Integration {
+      id: None,
+      user: user_id,
+      list: list_id,
+      created_at: now,
+      updated_at: now,
+      kind: integration::Kind::from_str("youtube-playlist").unwrap(),
+      rss: None,
+      listas_subscription: None,
+      github_stars: None,
+      youtube_playlist: Some(YouTubePlaylist { playlist_id }),
+      polled_at: None,
+    })
+    .await?;
+
+  queue_poll(&ctx, &integration).await;
+
+  debug!("Returning integration and 200 status code");
+  let integration: PrivateIntegration = integration.into();
+  let res = HttpResponse::Ok().json(integration);
+  Ok(res)
+}
+
+// The source is polled right away instead of waiting for the scheduler.
+async fn queue_poll(ctx: &Ctx, integration: &integration::Integration) {
+  let payload = poll_integrations::JobPayload {
+    integration: integration.id.as_ref().map(|id| id.to_hex()),
+  };
+  ctx.jobs.queue("poll_integrations", payload).await;
+}
+
+// GitHub usernames have up to 39 alphanumeric characters or single hyphens
+// and can not start or end with a hyphen.
+fn is_valid_github_username(username: &str) -> bool {
+  !username.is_empty()
+    && username.len() <= 39
+    && !username.starts_with('-')
+    && !username.ends_with('-')
+    && !username.contains("--")
+    && username
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn get_youtube_playlist_id(playlist: &str) -> Option<String> {
+  let playlist = playlist.trim();
+  let playlist_id = match Url::parse(playlist) {
+    Ok(url) => url
+      .query_pairs()
+      .find(|(key, _)| key == "list")
+      .map(|(_, value)| value.into_owned())?,
+    Err(_) => playlist.to_owned(),
+  };
+
+  let is_valid = !playlist_id.is_empty()
+    && playlist_id
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+  match is_valid {
+    true => Some(playlist_id),
+    false => None,
+  }
+}
+
+async fn create_rss(
+  ctx: &Ctx,
+  user_id: &ObjectId,
+  list_id: &ObjectId,
+  url: &Url,
+) -> Result<integration::Integration, Error> {
+  let now = date::now();
+  let subscription = ctx.rss.subscribe(url).await?;
+  ctx
+    .models
+    .integration
+    .create(integration::Integration {
+      id: None,
+      user: user_id.clone(),
+      list: list_id.clone(),
+      created_at: now,
+      updated_at: now,
+      kind: integration::Kind::from_str("rss").unwrap(),
+      listas_subscription: None,
+      github_stars: None,
+      youtube_playlist: None,
+      polled_at: None,
+      rss: Some(Rss {
+        url: subscription.url,
+        subscription_id: subscription.id,
+        // feed_type: subscription.feed_type,
+        metadata: subscription.metadata,
+      }),
+    })
+    .await
+}
+
+// Creates an RSS integration for each feed of the OPML file. Feeds inside an
+// outline folder are added to the list with the folder title, the list is
+// created when it does not exist.
+async fn import_rss_opml(ctx: Ctx, body: OpmlImportBody, user_id: UserID) -> Response {
+  let user_id = user_id.0;
+  let body = body.into_inner();
+
+  let feeds = match opml::parse(&body.payload) {
+    Ok(feeds) => feeds,
+    Err(err) => {
+      debug!(
+        "Failed to parse OPML file, returning 400 status code. Error: {}",
+        err
+      );
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let default_list = match body.list {
+    Some(list_id) => {
+      let list_id = to_object_id(list_id)?;
+      let list = ctx
+        .models
+        .list
+        .find_one(doc! { "_id": &list_id, "user": &user_id }, None)
+        .await?;
+
+      match list {
+        Some(list) => Some(list.id.unwrap()),
+        None => {
+          debug!("List not found, returning 404 status code");
+          return Ok(HttpResponse::NotFound().finish());
+        }
+      }
+    }
+    None => None,
+  };
+
+  let mut urls = ctx
+    .models
+    .integration
+    .find(doc! { "user": &user_id, "kind": "rss" }, None)
+    .await?
+    .into_iter()
+    .filter_map(|integration| integration.rss.map(|rss| rss.url))
+    .collect::<HashSet<String>>();
+
+  let mut skipped = vec![];
+  let mut folder_lists: HashMap<String, ObjectId> = HashMap::new();
+  let mut pending = vec![];
+
+  for feed in feeds {
+    let url = match parse_url(&feed.url) {
+      Ok(url) => url,
+      Err(_) => {
+        skipped.push(SkippedFeed {
+          url: feed.url,
+          reason: "invalid_url",
+        });
+        continue;
+      }
+    };
+
+    if !urls.insert(url.to_string()) {
+      skipped.push(SkippedFeed {
+        url: feed.url,
+        reason: "already_exists",
+      });
+      continue;
+    }
+
+    let title = match (feed.folder.is_empty(), &default_list) {
+      (true, Some(list_id)) => {
+        pending.push((list_id.clone(), url));
+        continue;
+      }
+      (true, None) => DEFAULT_FEEDS_LIST_TITLE.to_owned(),
+      (false, _) => feed.folder.join(" / "),
+    };
+
+    let list_id = match folder_lists.get(&title) {
+      Some(list_id) => list_id.clone(),
+      None => {
+        let list = ctx
+          .models
+          .list
+          .find_or_create_by_title(&user_id, &title)
+          .await?;
+        let list_id = list.id.unwrap();
+        folder_lists.insert(title, list_id.clone());
+        list_id
+      }
+    };
+
+    pending.push((list_id, url));
+  }
+
+  let results = futures::stream::iter(pending.into_iter().map(|(list_id, url)| {
+    let ctx = ctx.clone();
+    let user_id = user_id.clone();
+
+    async move {
+      match ctx.rss.is_valid_url(&url).await {
+        Ok(true) => (),
+        Ok(false) => return Err((url, "invalid_feed")),
+        Err(err) => {
+          error!("Failed to validate RSS feed {}. Error: {}", &url, err);
+          return Err((url, "failed"));
+        }
+      };
+
+      create_rss(&ctx, &user_id, &list_id, &url)
+        .await
+        .map_err(|err| {
+          error!(
+            "Failed to create RSS integration for {}. Error: {}",
+            &url, err
+          );
+          (url, "failed")
+        })
+    }
+  }))
+  .buffer_unordered(10)
+  .collect::<Vec<Result<integration::Integration, (Url, &'static str)>>>()
+  .await;
+
+  let mut integrations = vec![];
+  for result in results {
+    match result {
+      Ok(integration) => integrations.push(integration),
+      Err((url, reason)) => skipped.push(SkippedFeed {
+        url: url.to_string(),
+        reason,
+      }),
+    }
+  }
+
+  let list_ids = integrations
+    .iter()
+    .map(|integration| integration.list.clone())
+    .collect::<HashSet<ObjectId>>();
+
+  for list_id in list_ids {
+    ctx
+      .models
+      .list
+      .update_last_activity_at(&list_id)
+      .await
+      .map_err(|err| {
+        error!(
+          "Failed to update last activity for list {}. Error {}",
+          &list_id, err
+        )
+      })?;
+  }
+
+  let integrations = integrations
+    .into_iter()
+    .map(Into::into)
+    .collect::<Vec<PrivateIntegration>>();
+
+  debug!("Returning imported integrations and 200 status code");
+  let res = HttpResponse::Ok().json(json!({ "integrations": integrations, "skipped": skipped }));
+  Ok(res)
+}
+
+// Exports the RSS integrations grouped in a folder for each list.
+async fn export_rss_opml(ctx: Ctx, user_id: UserID) -> Response {
+  let user_id = user_id.0;
+
+  let integrations = ctx
+    .models
+    .integration
+    .find(doc! { "user": &user_id, "kind": "rss" }, None)
+    .await?;
+
+  let list_ids = integrations
+    .iter()
+    .map(|integration| integration.list.clone())
+    .collect::<HashSet<ObjectId>>()
+    .into_iter()
+    .collect::<Vec<ObjectId>>();
+
+  let options = FindOptions::builder().sort(doc! { "title": 1 }).build();
+  let lists = ctx
+    .models
+    .list
+    .find(doc! { "_id": { "$in": list_ids } }, Some(options))
+    .await?;
+
+  let folders = lists
+    .into_iter()
+    .map(|list| {
+      let feeds = integrations
+        .iter()
+        .filter(|integration| Some(&integration.list) == list.id.as_ref())
+        .filter_map(|integration| integration.rss.as_ref())
+        .map(|rss| opml::Feed {
+          url: rss.url.clone(),
+          title: None,
+          folder: vec![list.title.clone()],
+        })
+        .collect();
+
+      opml::Folder {
+        title: list.title,
+        feeds,
+      }
+    })
+    .collect::<Vec<opml::Folder>>();
+
+  let document = opml::create("Listas feeds", &folders);
+
+  debug!("Returning OPML export");
+  let res = HttpResponse::Ok()
+    .content_type("text/x-opml; charset=utf-8")
+    .header(
+      "Content-Disposition",
+      "attachment; filename=\"listas-feeds.opml\"",
+    )
+    .body(document.into_string());
+  Ok(res)
+}
+
 async fn create_subscription_integration(
   ctx: Ctx,
   body: SubscriptionCreateBody,
@@ -260,6 +736,9 @@ async fn create_subscription_integration(
       listas_subscription: Some(integration::subscription::ListasSubscription {
         list: following_list_id.clone(),
       }),
+      github_stars: None,
+      youtube_playlist: None,
+      polled_at: None,
     })
     .await?;
 
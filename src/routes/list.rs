@@ -1,35 +1,50 @@
 use actix_web::{web, HttpResponse};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use futures::stream::StreamExt;
+use futures::try_join;
 use serde::Deserialize;
 use serde_json::json;
 use wither::bson;
 use wither::bson::doc;
+use wither::bson::Bson;
 use wither::mongodb;
 use wither::mongodb::options::FindOneAndUpdateOptions;
+use wither::mongodb::options::FindOptions;
 
 use crate::auth::UserID;
 use crate::errors::Error;
+use crate::exporters;
+use crate::exporters::ExportFormat;
 use crate::lib::date;
 use crate::lib::id::ID;
 use crate::lib::util;
 use crate::models::list;
 use crate::models::list::List;
 use crate::models::list::ListUpdate;
+use crate::models::resource::PrivateResource;
 use crate::models::resource::Resource;
 use crate::models::Model as ModelTrait;
 use crate::Context;
 use crate::{actors::subscription, auth};
 
+const REFRESH_CHUNK_SIZE: usize = 50;
+
 type Response = actix_web::Result<HttpResponse>;
 type Ctx = web::Data<Context>;
 
+#[derive(Deserialize)]
+struct ExportQuery {
+  format: ExportFormat,
+}
+
 #[derive(Deserialize)]
 struct ListCreateBody {
   pub title: String,
   pub is_public: bool,
   pub description: Option<String>,
   pub tags: Option<Vec<String>>,
+  pub auto_tag: Option<bool>,
+  pub replace_broken_links: Option<bool>,
 }
 
 pub fn create_router(cfg: &mut web::ServiceConfig) {
@@ -60,6 +75,31 @@ pub fn create_router(cfg: &mut web::ServiceConfig) {
   cfg.service(
     web::resource("/lists/{id}/archive")
       .route(web::post().to(archive_list))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/lists/{id}/refresh")
+      .route(web::post().to(refresh_list))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/lists/{id}/health")
+      .route(web::get().to(get_list_health))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/lists/{id}/export")
+      .route(web::get().to(export_list))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/lists/{id}/inbound-email")
+      .route(web::get().to(get_inbound_email))
+      .route(web::post().to(rotate_inbound_email))
       .wrap(auth),
   );
 }
@@ -124,6 +164,10 @@ async fn create_list(ctx: Ctx, body: web::Json<ListCreateBody>, user: UserID) ->
     updated_at: now,
     last_activity_at: now,
     archived_at: None,
+    auto_tag: body.auto_tag,
+    replace_broken_links: body.replace_broken_links,
+    inbound_token: Some(List::create_inbound_token()),
+    is_inbox: None,
   };
 
   let list = ctx.models.list.create(list).await?;
@@ -159,6 +203,14 @@ async fn update_list(ctx: web::Data<Context>, id: ID, body: web::Json<ListUpdate
     }
   };
 
+  if let Some(is_public) = body.is_public {
+    ctx
+      .models
+      .resource_signature
+      .set_list_visibility(&list_id, is_public)
+      .await?;
+  }
+
   let list_has_become_private = !body.is_public.unwrap_or(true);
   if list_has_become_private {
     debug!("Removing related list subscription integration");
@@ -217,6 +269,10 @@ async fn fork_list(ctx: web::Data<Context>, id: ID, user: UserID) -> Response {
     updated_at: now,
     last_activity_at: now,
     archived_at: None,
+    auto_tag: list.auto_tag,
+    replace_broken_links: list.replace_broken_links,
+    inbound_token: Some(List::create_inbound_token()),
+    is_inbox: None,
     fork: Some(list::Fork {
       list: list.id.clone().unwrap(),
       user: list.user.clone(),
@@ -255,6 +311,21 @@ async fn fork_list(ctx: web::Data<Context>, id: ID, user: UserID) -> Response {
       length: None,
       publisher: None,
       populated_at: None,
+      refresh_attempted_at: None,
+      signature_indexed_at: None,
+      annotations_text: None,
+      estimated_reading_time: None,
+      progress: None,
+      snoozed_until: None,
+      review: None,
+      archived_at: None,
+      favicon: None,
+      kind: None,
+      media: None,
+      health: None,
+      last_checked_at: None,
+      edited_fields: resource.edited_fields.clone(),
+      suggested_tags: resource.suggested_tags.clone(),
     };
 
     async move {
@@ -296,6 +367,11 @@ async fn remove_list(ctx: web::Data<Context>, id: ID, user: UserID) -> Response
     }
   };
 
+  if list.is_inbox() {
+    debug!("Can not remove the inbox list, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
   debug!("Removing list");
   ctx.models.list.remove(&list_id).await?;
 
@@ -315,6 +391,181 @@ async fn remove_list(ctx: web::Data<Context>, id: ID, user: UserID) -> Response
   Ok(res)
 }
 
+async fn refresh_list(ctx: Ctx, id: ID, user: UserID) -> Response {
+  let user_id = user.0;
+  let list_id = id.0;
+
+  let list_exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !list_exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let options = FindOptions::builder()
+    .projection(doc! { "html": false, "text": false })
+    .build();
+
+  let resource_ids = ctx
+    .models
+    .resource
+    .find(doc! { "list": &list_id }, Some(options))
+    .await?
+    .into_iter()
+    .map(|resource| resource.id.unwrap().to_string())
+    .collect::<Vec<String>>();
+
+  // Resources are queued in chunks to avoid long running jobs.
+  for chunk in resource_ids.chunks(REFRESH_CHUNK_SIZE) {
+    ctx.jobs.queue("populate_resources", chunk.to_vec()).await;
+  }
+
+  debug!("List refresh queued, returning 202 status code");
+  let res = HttpResponse::Accepted().finish();
+  Ok(res)
+}
+
+async fn get_list_health(ctx: Ctx, id: ID, user: UserID) -> Response {
+  let user_id = user.0;
+  let list_id = id.0;
+
+  let list_exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !list_exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let (count, checked_count) = try_join!(
+    ctx.models.resource.count(doc! { "list": &list_id }),
+    ctx.models.resource.count(doc! {
+      "list": &list_id,
+      "last_checked_at": { "$ne": Bson::Null }
+    }),
+  )?;
+
+  let options = FindOptions::builder()
+    .sort(doc! { "position": 1 })
+    .projection(doc! { "html": false, "text": false })
+    .build();
+
+  let broken = ctx
+    .models
+    .resource
+    .find(
+      doc! { "list": &list_id, "health.is_broken": true },
+      Some(options),
+    )
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect::<Vec<PrivateResource>>();
+
+  debug!("Returning list health report");
+  let res = HttpResponse::Ok().json(json!({
+    "count": count,
+    "checked_count": checked_count,
+    "broken_count": broken.len(),
+    "broken": broken
+  }));
+  Ok(res)
+}
+
+async fn export_list(ctx: Ctx, id: ID, user: UserID, qs: web::Query<ExportQuery>) -> Response {
+  let user_id = user.0;
+  let list_id = id.0;
+
+  let list = ctx
+    .models
+    .list
+    .find_one(doc! { "_id": &list_id, "user": &user_id }, None)
+    .await?;
+
+  let list = match list {
+    Some(list) => list,
+    None => {
+      debug!("List not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let resources = exporters::find_export_resources(&ctx.models, &list_id, true).await?;
+  let exporter = qs.format.exporter();
+  let content_disposition = exporters::get_content_disposition(&list, exporter.as_ref());
+  let body = exporter.export(&list, resources);
+
+  debug!("Returning list export");
+  let res = HttpResponse::Ok()
+    .content_type(exporter.content_type())
+    .header("Content-Disposition", content_disposition)
+    .body(body);
+  Ok(res)
+}
+
+// Lists created before inbound emails existed get their token on the first
+// request.
+async fn get_inbound_email(ctx: Ctx, id: ID, user: UserID) -> Response {
+  let user_id = user.0;
+  let list_id = id.0;
+
+  let list = ctx
+    .models
+    .list
+    .find_one(doc! { "_id": &list_id, "user": &user_id }, None)
+    .await?;
+
+  let list = match list {
+    Some(list) => list,
+    None => {
+      debug!("List not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let token = match list.inbound_token {
+    Some(token) => token,
+    None => ctx.models.list.rotate_inbound_token(&list_id).await?,
+  };
+
+  debug!("Returning list inbound email address");
+  let res = HttpResponse::Ok().json(json!({
+    "address": format!("{}@{}", token, ctx.settings.inbound_email.domain)
+  }));
+  Ok(res)
+}
+
+async fn rotate_inbound_email(ctx: Ctx, id: ID, user: UserID) -> Response {
+  let user_id = user.0;
+  let list_id = id.0;
+
+  let exists = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !exists {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let token = ctx.models.list.rotate_inbound_token(&list_id).await?;
+
+  debug!("List inbound email address rotated, returning 200 status code");
+  let res = HttpResponse::Ok().json(json!({
+    "address": format!("{}@{}", token, ctx.settings.inbound_email.domain)
+  }));
+  Ok(res)
+}
+
 async fn archive_list(ctx: web::Data<Context>, id: ID, user: UserID) -> Response {
   let user_id = user.0;
   let list_id = id.0;
@@ -333,6 +584,11 @@ async fn archive_list(ctx: web::Data<Context>, id: ID, user: UserID) -> Response
     }
   };
 
+  if list.is_inbox() {
+    debug!("Can not archive the inbox list, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
   if list.archived_at.is_some() {
     debug!("List was already archived, returning 400 status code");
     return Ok(HttpResponse::BadRequest().finish());
@@ -4,6 +4,8 @@ use wither::bson::doc;
 use wither::bson::Bson;
 
 use crate::auth::AuthenticationMetadata;
+use crate::exporters;
+use crate::exporters::ExportFormat;
 use crate::models::resource::PrivateResource;
 use crate::models::Model as ModelTrait;
 use crate::Context;
@@ -16,6 +18,11 @@ struct Params {
   list_slug: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct ExportQuery {
+  format: ExportFormat,
+}
+
 #[derive(Deserialize)]
 struct Query {
   completed: Option<bool>,
@@ -31,6 +38,9 @@ pub fn create_router(cfg: &mut web::ServiceConfig) {
     web::resource("users/{user_slug}/lists/{list_slug}/resources")
       .route(web::get().to(query_resources)),
   );
+  cfg.service(
+    web::resource("users/{user_slug}/lists/{list_slug}/export").route(web::get().to(export_list)),
+  );
 }
 
 async fn query_lists(
@@ -239,3 +249,65 @@ async fn query_resources(
   let res = HttpResponse::Ok().json(resources);
   Ok(res)
 }
+
+// Public lists can be exported by anyone, the notes are only included when
+// the owner exports the list.
+async fn export_list(
+  ctx: web::Data<Context>,
+  params: web::Path<Params>,
+  auth: AuthenticationMetadata,
+  qs: web::Query<ExportQuery>,
+) -> Response {
+  let list_slug = params.list_slug.clone().unwrap();
+  let user_slug = &params.user_slug;
+
+  let user = ctx
+    .models
+    .user
+    .find_one(doc! { "slug": user_slug }, None)
+    .await?;
+
+  let user = match user {
+    Some(user) => user,
+    None => {
+      debug!(
+        "User not found for slug {}, returning 404 status code to the user",
+        user_slug
+      );
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let user_id = user.id.unwrap();
+  let is_authenticated = auth.is_authenticated;
+  let is_self = is_authenticated && auth.user_id.clone().unwrap() == user_id;
+  let mut query = doc! { "user": &user_id, "slug": &list_slug };
+  if !is_self {
+    query.insert("is_public", true);
+  }
+
+  let list = ctx.models.list.find_one(query, None).await?;
+  let list = match list {
+    Some(list) => list,
+    None => {
+      debug!(
+        "List not found for slug {}, returning 404 status code to the user",
+        list_slug
+      );
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let list_id = list.id.clone().unwrap();
+  let resources = exporters::find_export_resources(&ctx.models, &list_id, is_self).await?;
+  let exporter = qs.format.exporter();
+  let content_disposition = exporters::get_content_disposition(&list, exporter.as_ref());
+  let body = exporter.export(&list, resources);
+
+  debug!("Returning list export");
+  let res = HttpResponse::Ok()
+    .content_type(exporter.content_type())
+    .header("Content-Disposition", content_disposition)
+    .body(body);
+  Ok(res)
+}
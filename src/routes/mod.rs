@@ -1,4 +1,9 @@
+pub mod activity;
+pub mod annotation;
+pub mod backup;
 pub mod discover;
+pub mod import_job;
+pub mod inbox;
 pub mod index;
 pub mod integration;
 pub mod like;
@@ -7,5 +12,9 @@ pub mod list_by_slug;
 pub mod resource;
 pub mod resource_import;
 pub mod resource_metadata;
+pub mod resource_related;
+pub mod save;
+pub mod share;
+pub mod tag;
 pub mod user;
 pub mod webhooks;
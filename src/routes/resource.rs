@@ -1,9 +1,10 @@
 use actix_web::{web, HttpResponse};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use validator::Validate;
 use wither::bson;
-use wither::bson::{doc, Bson};
+use wither::bson::{doc, Bson, Document};
 use wither::mongodb;
 use wither::mongodb::options::FindOneAndUpdateOptions;
 
@@ -12,9 +13,20 @@ use crate::auth::UserID;
 use crate::lib::id::ID;
 use crate::lib::util;
 use crate::lib::util::to_object_id;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::annotation::PrivateAnnotation;
+use crate::models::list::model::MAX_POSITION_ATTEMPTS;
+use crate::models::reading_session::ReadingSession;
+use crate::models::resource::Kind;
+use crate::models::resource::PrivateReadingProgress;
 use crate::models::resource::PrivateResource;
+use crate::models::resource::PrivateReview;
+use crate::models::resource::ReadingProgress;
 use crate::models::resource::Resource;
 use crate::models::resource::ResourceUpdate;
+use crate::models::resource::Review;
 use crate::models::Model as ModelTrait;
 use crate::Context;
 use crate::{auth, lib::date};
@@ -27,6 +39,7 @@ struct Query {
   completed: Option<bool>,
   sort: Option<String>,
   search_text: Option<String>,
+  kind: Option<Kind>,
   skip: Option<u32>,
   limit: Option<u32>,
 }
@@ -39,6 +52,14 @@ pub struct ResourceCreate {
   pub description: Option<String>,
   pub thumbnail: Option<String>,
   pub tags: Option<Vec<String>>,
+  // Schedules the resource to be surfaced later (RFC 3339 date).
+  pub snoozed_until: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Snooze {
+  // RFC 3339 date.
+  pub until: String,
 }
 
 #[derive(Deserialize)]
@@ -47,10 +68,30 @@ pub struct PositionUpdate {
   pub previus: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct ResourceMove {
+  pub list: String,
+  pub previus: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+pub struct ProgressUpdate {
+  #[validate(range(min = 0.0, max = 100.0))]
+  pub percentage: Option<f64>,
+  #[validate(range(min = 0))]
+  pub scroll_position: Option<i64>,
+  // Seconds spent reading since the last progress update.
+  #[validate(range(min = 0, max = 86400))]
+  pub time_spent: Option<i64>,
+}
+
 type Response = actix_web::Result<HttpResponse>;
 type Ctx = web::Data<Context>;
 type ResourceCreateBody = web::Json<ResourceCreate>;
 type PositionUpdateBody = web::Json<PositionUpdate>;
+type ResourceMoveBody = web::Json<ResourceMove>;
+type ProgressUpdateBody = web::Json<ProgressUpdate>;
+type SnoozeBody = web::Json<Snooze>;
 
 pub fn create_router(cfg: &mut web::ServiceConfig) {
   let auth = HttpAuthentication::bearer(auth::validator);
@@ -88,6 +129,49 @@ pub fn create_router(cfg: &mut web::ServiceConfig) {
       .wrap(auth.clone()),
   );
 
+  cfg.service(
+    web::resource("/resources/{id}/move")
+      .route(web::post().to(move_resource))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/resources/{id}/refresh")
+      .route(web::post().to(refresh_resource))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/resources/{id}/archive")
+      .route(web::get().to(get_resource_archive))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/resources/{id}/snooze")
+      .route(web::post().to(snooze_resource))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/resources/{id}/unsnooze")
+      .route(web::post().to(unsnooze_resource))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/resources/{id}/review")
+      .route(web::post().to(enable_resource_review))
+      .route(web::delete().to(disable_resource_review))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/resources/{id}/progress")
+      .route(web::post().to(update_progress))
+      .wrap(auth.clone()),
+  );
+
   cfg.service(
     web::resource("/resources")
       .route(web::get().to(query_resources))
@@ -114,8 +198,86 @@ async fn get_resource_by_id(ctx: Ctx, id: ID, user_id: UserID) -> Response {
     }
   };
 
+  let annotations = ctx
+    .models
+    .annotation
+    .find_by_resource(&resource_id)
+    .await?
+    .into_iter()
+    .map(Into::into)
+    .collect::<Vec<PrivateAnnotation>>();
+
+  let mut resource = resource.to_json();
+  resource["annotations"] = json!(annotations);
+
   debug!("Returning resource");
-  let res = HttpResponse::Ok().json(resource.to_json());
+  let res = HttpResponse::Ok().json(resource);
+  Ok(res)
+}
+
+async fn refresh_resource(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let resource_exists = ctx
+    .models
+    .resource
+    .exists(doc! { "_id": &resource_id, "user": &user_id })
+    .await?;
+
+  if !resource_exists {
+    debug!("Resource not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  ctx
+    .jobs
+    .queue("populate_resources", vec![resource_id.to_string()])
+    .await;
+
+  debug!("Resource refresh queued, returning 202 status code");
+  let res = HttpResponse::Accepted().finish();
+  Ok(res)
+}
+
+async fn get_resource_archive(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let is_archived = ctx
+    .models
+    .resource
+    .exists(doc! {
+      "_id": &resource_id,
+      "user": &user_id,
+      "archived_at": { "$ne": Bson::Null }
+    })
+    .await?;
+
+  if !is_archived {
+    debug!("Archived resource not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let archive = match ctx.archiver.get(&resource_id).await? {
+    Some(archive) => archive,
+    None => {
+      debug!("Resource archive not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  // Snapshots are sanitized when created, the policy prevents running any
+  // remaining active content in the API origin.
+  debug!("Returning resource archive");
+  let res = HttpResponse::Ok()
+    .content_type("text/html; charset=utf-8")
+    .header(
+      "Content-Security-Policy",
+      "sandbox; default-src 'none'; img-src data: http: https:",
+    )
+    .header("X-Content-Type-Options", "nosniff")
+    .body(archive);
   Ok(res)
 }
 
@@ -146,7 +308,7 @@ async fn query_resources(ctx: Ctx, user_id: UserID, qs: web::Query<Query>) -> Re
     must.push(doc! {
       "text": {
         "query": search_text,
-        "path": ["title", "description", "tags"],
+        "path": ["title", "description", "tags", "annotations_text"],
         "fuzzy": {
           "maxEdits": 2,
           "prefixLength": 3
@@ -179,6 +341,14 @@ async fn query_resources(ctx: Ctx, user_id: UserID, qs: web::Query<Query>) -> Re
     });
   }
 
+  if let Some(kind) = qs.kind {
+    pipeline.push(doc! {
+      "$match": {
+        "kind": bson::to_bson(&kind).unwrap()
+      }
+    });
+  }
+
   // TODO: Remove this $sort stage because it can drastically slow down query
   // results.
   // https://docs.atlas.mongodb.com/reference/atlas-search/performance/#-sort-aggregation-stage-usage
@@ -219,6 +389,11 @@ async fn create_resource(ctx: Ctx, body: ResourceCreateBody, user_id: UserID) ->
   let list_id = to_object_id(body.list.clone())?;
   let user_id = user_id.0;
   let url = util::parse_url(body.url.clone().as_str())?;
+  if !util::is_web_url(url.as_str()) {
+    debug!("Failed creating Resource, URL scheme is not valid. Returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
   let tags = body
     .tags
     .clone()
@@ -244,8 +419,27 @@ async fn create_resource(ctx: Ctx, body: ResourceCreateBody, user_id: UserID) ->
     return Ok(HttpResponse::BadRequest().finish());
   }
 
+  let snoozed_until = match body.snoozed_until.as_deref().map(date::from_rfc3339) {
+    None => None,
+    Some(Ok(snoozed_until)) => Some(snoozed_until),
+    Some(Err(_)) => {
+      debug!("Failed creating Resource, snooze date is not valid. Returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
   let position = ctx.models.list.get_next_resource_position(&list_id).await?;
 
+  let edited_fields = [
+    ("title", body.title.is_some()),
+    ("description", body.description.is_some()),
+    ("thumbnail", body.thumbnail.is_some()),
+  ]
+  .iter()
+  .filter(|(_, is_edited)| *is_edited)
+  .map(|(field, _)| field.to_string())
+  .collect::<Vec<String>>();
+
   let resource = Resource {
     id: None,
     position,
@@ -265,6 +459,21 @@ async fn create_resource(ctx: Ctx, body: ResourceCreateBody, user_id: UserID) ->
     length: None,
     publisher: None,
     populated_at: None,
+    refresh_attempted_at: None,
+    signature_indexed_at: None,
+    annotations_text: None,
+    estimated_reading_time: None,
+    progress: None,
+    snoozed_until,
+    review: None,
+    archived_at: None,
+    favicon: None,
+    kind: None,
+    media: None,
+    health: None,
+    last_checked_at: None,
+    edited_fields: Some(edited_fields),
+    suggested_tags: None,
   };
 
   // TODO: Integrate validate method into a create method.
@@ -276,9 +485,15 @@ async fn create_resource(ctx: Ctx, body: ResourceCreateBody, user_id: UserID) ->
     }
   };
 
-  let resource = ctx.models.resource.create(resource).await?;
+  let resource = ctx.models.list.create_resource(resource).await?;
   let resource_id = resource.id.clone().unwrap();
 
+  ctx
+    .models
+    .activity
+    .record(Activity::new(&resource, Action::Create, Source::User))
+    .await;
+
   ctx
     .actors
     .subscription
@@ -315,6 +530,14 @@ async fn update_resource(ctx: Ctx, id: ID, body: ResourceUpdateBody, user_id: Us
   let resource_id = id.0;
   let user_id = user_id.0;
 
+  match body.validate() {
+    Ok(_) => (),
+    Err(_err) => {
+      debug!("Failed updating Resource, payload is not valid. Returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
   let resource = ctx
     .models
     .resource
@@ -333,11 +556,48 @@ async fn update_resource(ctx: Ctx, id: ID, body: ResourceUpdateBody, user_id: Us
   let body = ResourceUpdate::new(&mut body);
   let mut update = bson::to_document(&body).unwrap();
 
+  // Changing the resource list is handled as a move, this way the target
+  // list ownership is checked and the resource is appended to the target
+  // list.
+  update.remove("list");
   match &body.list {
     Some(list_id) if !resource.list.eq(list_id) => {
-      let last_position = ctx.models.list.get_next_resource_position(list_id).await?;
+      let is_list_owner = ctx
+        .models
+        .list
+        .exists(doc! { "_id": list_id, "user": &user_id })
+        .await?;
+
+      if !is_list_owner {
+        debug!("Target list not found, returning 404 status code");
+        return Ok(HttpResponse::NotFound().finish());
+      }
+
+      let position = ctx.models.list.get_next_resource_position(list_id).await?;
+      ctx
+        .models
+        .list
+        .move_resource(&resource, list_id, position)
+        .await?;
 
-      update.insert("position", last_position);
+      let activity = Activity {
+        list: list_id.clone(),
+        ..Activity::new(&resource, Action::Move, Source::User)
+      };
+      let previous = doc! { "list": &resource.list, "position": &resource.position };
+      ctx
+        .models
+        .activity
+        .record(activity.with_previous(previous))
+        .await;
+
+      ctx
+        .actors
+        .subscription
+        .try_send(subscription::on_resource_created::ResourceCreated {
+          resource_id: resource_id.clone(),
+        })
+        .map_err(|err| error!("Failed to send message to subscription actor, {}", err))?;
     }
     _ => {}
   };
@@ -346,12 +606,49 @@ async fn update_resource(ctx: Ctx, id: ID, body: ResourceUpdateBody, user_id: Us
     .return_document(mongodb::options::ReturnDocument::After)
     .build();
 
+  // The current values of the updated fields are stored to undo the update.
+  let current = bson::to_document(&resource).unwrap();
+  let previous = update
+    .keys()
+    .map(|key| (key.clone(), current.get(key).cloned().unwrap_or(Bson::Null)))
+    .collect::<Document>();
+
+  // The title, description and tags are part of the resource similarity
+  // signature.
+  if body.title.is_some() || body.description.is_some() || body.tags.is_some() {
+    update.insert("signature_indexed_at", Bson::Null);
+  }
+
+  let mut edited_fields = body.get_edited_fields();
+  // Resources created before edits were tracked only populate their empty
+  // fields, the fields with a value are seeded as edited to keep them.
+  if resource.edited_fields.is_none() && !edited_fields.is_empty() {
+    let legacy_fields = [
+      ("title", resource.title.is_some()),
+      ("description", resource.description.is_some()),
+      ("thumbnail", resource.thumbnail.is_some()),
+    ];
+    for (field, has_value) in legacy_fields.iter() {
+      if *has_value && !edited_fields.iter().any(|edited| edited == field) {
+        edited_fields.push(field.to_string());
+      }
+    }
+  }
+
+  let mut resource_update = doc! { "$set": update };
+  if !edited_fields.is_empty() {
+    resource_update.insert(
+      "$addToSet",
+      doc! { "edited_fields": { "$each": edited_fields } },
+    );
+  }
+
   let resource = ctx
     .models
     .resource
     .find_one_and_update(
       doc! { "_id": &resource_id, "user": &user_id },
-      doc! { "$set": update },
+      resource_update,
       Some(options),
     )
     .await?;
@@ -364,6 +661,16 @@ async fn update_resource(ctx: Ctx, id: ID, body: ResourceUpdateBody, user_id: Us
     }
   };
 
+  let has_changes = previous.keys().any(|key| key != "updated_at");
+  if has_changes {
+    let activity = Activity::new(&resource, Action::Update, Source::User);
+    ctx
+      .models
+      .activity
+      .record(activity.with_previous(previous))
+      .await;
+  }
+
   debug!("Returning updated resource");
   let res = HttpResponse::Ok().json(resource.to_json());
   Ok(res)
@@ -385,7 +692,7 @@ async fn remove_resource(ctx: Ctx, id: ID, user_id: UserID) -> Response {
   let result = ctx
     .models
     .resource
-    .delete_one(doc! { "_id": resource_id, "user": user_id })
+    .delete_one(doc! { "_id": &resource_id, "user": user_id })
     .await?;
 
   if result.deleted_count == 0 {
@@ -393,6 +700,37 @@ async fn remove_resource(ctx: Ctx, id: ID, user_id: UserID) -> Response {
     return Ok(HttpResponse::NotFound().finish());
   }
 
+  // The removed resource is stored to be able to restore it.
+  let activity = Activity::new(&resource, Action::Remove, Source::User);
+  let previous = bson::to_document(&resource).unwrap();
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
+  ctx
+    .models
+    .annotation
+    .delete_many(doc! { "resource": &resource_id })
+    .await?;
+
+  ctx
+    .models
+    .resource_signature
+    .delete_many(doc! { "resource": &resource_id })
+    .await?;
+
+  ctx
+    .models
+    .share
+    .delete_many(doc! { "resource": &resource_id })
+    .await?;
+
+  if resource.archived_at.is_some() {
+    ctx.archiver.remove(&resource_id).await?;
+  }
+
   ctx
     .models
     .list
@@ -433,16 +771,30 @@ async fn complete_resource(ctx: Ctx, id: ID, user_id: UserID) -> Response {
     return Ok(HttpResponse::BadRequest().finish());
   }
 
+  let mut update = doc! { "completed_at": Bson::DateTime(date::now().into()) };
+  let mut previous = doc! { "completed_at": Bson::Null };
+  if let Some(ref review) = resource.review {
+    update.insert("review", bson::to_bson(&review.next()).unwrap());
+    previous.insert("review", bson::to_bson(review).unwrap());
+  }
+
   ctx
     .models
     .resource
     .update_one(
       doc! { "_id": &resource_id, "user": &user_id },
-      doc! { "$set": { "completed_at": Bson::DateTime(date::now().into()) } },
+      doc! { "$set": update },
       None,
     )
     .await?;
 
+  let activity = Activity::new(&resource, Action::Complete, Source::User);
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
   debug!("Resource marked as completed, returning 202 status code");
   let res = HttpResponse::Accepted().finish();
   Ok(res)
@@ -471,16 +823,52 @@ async fn undo_complete_resource(ctx: Ctx, id: ID, user_id: UserID) -> Response {
     return Ok(HttpResponse::BadRequest().finish());
   }
 
+  // Completing the resource schedules its next review, the review it had
+  // before the completion is restored.
+  let completion = ctx
+    .models
+    .activity
+    .find_last_by_resource(&resource_id, Action::Complete)
+    .await?
+    .filter(|activity| Some(activity.created_at) >= resource.completed_at);
+
+  let mut update = doc! { "completed_at": Bson::Null };
+  let mut previous = doc! { "completed_at": bson::to_bson(&resource.completed_at).unwrap() };
+  let previous_review = completion
+    .as_ref()
+    .and_then(|activity| activity.previous.as_ref())
+    .and_then(|previous| previous.get("review"));
+
+  if let Some(review) = previous_review {
+    update.insert("review", review.clone());
+    previous.insert("review", bson::to_bson(&resource.review).unwrap());
+  }
+
   ctx
     .models
     .resource
     .update_one(
       doc! { "_id": &resource_id, "user": &user_id },
-      doc! { "$set": { "completed_at": Bson::Null } },
+      doc! { "$set": update },
       None,
     )
     .await?;
 
+  if let Some(completion) = completion {
+    ctx
+      .models
+      .activity
+      .set_undone_at(completion.id.as_ref().unwrap())
+      .await?;
+  }
+
+  let activity = Activity::new(&resource, Action::UndoComplete, Source::User);
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
   debug!("Resource unmarked as completed, returning 202 status code");
   let res = HttpResponse::Accepted().finish();
   Ok(res)
@@ -490,7 +878,7 @@ async fn update_position(ctx: Ctx, id: ID, user_id: UserID, body: PositionUpdate
   let resource_id = id.0;
   let list_id = to_object_id(body.list.clone())?;
   let user_id = user_id.0;
-  let previus_resource_id = body.previus.clone();
+  let previus_resource_id = body.previus.clone().map(to_object_id).transpose()?;
 
   let resource_exists = ctx
     .models
@@ -503,64 +891,415 @@ async fn update_position(ctx: Ctx, id: ID, user_id: UserID, body: PositionUpdate
     return Ok(HttpResponse::NotFound().finish());
   }
 
-  let position = match previus_resource_id {
-    // If previus position is not sent, the new resource position is 0, it will
-    // be inserted at the top of the list.
-    None => 0,
+  // If previus resource is not sent, the resource will be inserted at the top
+  // of the list. The position is computed again when a concurrent change
+  // takes it.
+  let mut attempts = 0;
+  let resource = loop {
+    let position = ctx
+      .models
+      .list
+      .get_position_after(&list_id, previus_resource_id.as_ref(), &resource_id)
+      .await?;
+
+    let position = match position {
+      Some(position) => position,
+      None => {
+        debug!("Resource not found, returning 404 status code");
+        return Ok(HttpResponse::NotFound().finish());
+      }
+    };
+
+    // Returns the resource before the update.
+    let result = ctx
+      .models
+      .resource
+      .find_one_and_update(
+        doc! { "_id": &resource_id },
+        doc! {
+          "$set": {
+            "position": position,
+            "updated_at": bson::to_bson(&date::now()).unwrap()
+          }
+        },
+        None,
+      )
+      .await;
+
+    match result {
+      Err(err) if err.is_duplicate_key() && attempts < MAX_POSITION_ATTEMPTS => attempts += 1,
+      result => break result?,
+    }
+  };
+
+  if let Some(resource) = resource {
+    let activity = Activity::new(&resource, Action::Reorder, Source::User);
+    let previous = doc! { "position": &resource.position };
+    ctx
+      .models
+      .activity
+      .record(activity.with_previous(previous))
+      .await;
+  }
+
+  debug!("Resource position updated, returning 202 status code");
+  let res = HttpResponse::Accepted().finish();
+  Ok(res)
+}
+
+async fn move_resource(ctx: Ctx, id: ID, user_id: UserID, body: ResourceMoveBody) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+  let list_id = to_object_id(body.list.clone())?;
+
+  let resource = ctx
+    .models
+    .resource
+    .find_one(doc! { "_id": &resource_id, "user": &user_id }, None)
+    .await?;
+
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  if resource.list == list_id {
+    debug!("Resource already belongs to the target list, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  let is_source_list_owner = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &resource.list, "user": &user_id })
+    .await?;
+
+  let is_target_list_owner = ctx
+    .models
+    .list
+    .exists(doc! { "_id": &list_id, "user": &user_id })
+    .await?;
+
+  if !is_source_list_owner || !is_target_list_owner {
+    debug!("List not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let position = match body.previus.clone() {
+    // If previus resource is not sent, the resource is appended at the end of
+    // the target list.
+    None => ctx.models.list.get_next_resource_position(&list_id).await?,
     Some(previus_resource_id) => {
       let previus_resource_id = to_object_id(previus_resource_id)?;
-      let query = doc! {
-          "_id": &previus_resource_id,
-          "user": &user_id,
-          "list": &list_id,
-      };
-      let position = match ctx.models.resource.get_position(query).await? {
+      let position = ctx
+        .models
+        .list
+        .get_position_after(&list_id, Some(&previus_resource_id), &resource_id)
+        .await?;
+
+      match position {
         Some(position) => position,
         None => {
           debug!("Resource not found, returning 404 status code");
           return Ok(HttpResponse::NotFound().finish());
         }
-      };
-
-      position + 1
+      }
     }
   };
 
   ctx
+    .models
+    .list
+    .move_resource(&resource, &list_id, position)
+    .await?;
+
+  let activity = Activity {
+    list: list_id.clone(),
+    ..Activity::new(&resource, Action::Move, Source::User)
+  };
+  let previous = doc! { "list": &resource.list, "position": &resource.position };
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
+  ctx
+    .actors
+    .subscription
+    .try_send(subscription::on_resource_created::ResourceCreated {
+      resource_id: resource_id.clone(),
+    })
+    .map_err(|err| error!("Failed to send message to subscription actor, {}", err))?;
+
+  debug!("Resource moved, returning 202 status code");
+  let res = HttpResponse::Accepted().finish();
+  Ok(res)
+}
+
+async fn snooze_resource(ctx: Ctx, id: ID, user_id: UserID, body: SnoozeBody) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let until = match date::from_rfc3339(body.until.as_str()) {
+    Ok(until) if until > date::now() => until,
+    _ => {
+      debug!("Snooze date is not valid, returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  // Returns the resource before the update.
+  let resource = ctx
     .models
     .resource
-    .update_many(
+    .find_one_and_update(
+      doc! { "_id": &resource_id, "user": &user_id, "completed_at": Bson::Null },
       doc! {
-          "_id": doc! { "$ne": &resource_id },
-          "user": &user_id,
-          "list": &list_id,
-          "position": doc! { "$gte": &position },
+        "$set": {
+          "snoozed_until": Bson::DateTime(until.into()),
+          "updated_at": Bson::DateTime(date::now().into())
+        }
       },
-      doc! { "$inc": { "position": 1 } },
       None,
     )
     .await?;
 
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Uncompleted resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let activity = Activity::new(&resource, Action::Snooze, Source::User);
+  let previous = doc! { "snoozed_until": bson::to_bson(&resource.snoozed_until).unwrap() };
   ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
+  debug!("Resource snoozed, returning 202 status code");
+  let res = HttpResponse::Accepted().finish();
+  Ok(res)
+}
+
+async fn unsnooze_resource(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  // Returns the resource before the update.
+  let resource = ctx
     .models
     .resource
-    .update_one(
-      doc! { "_id": &resource_id },
+    .find_one_and_update(
+      doc! { "_id": &resource_id, "user": &user_id },
       doc! {
         "$set": {
-          "position": position,
-          "updated_at": bson::to_bson(&date::now()).unwrap()
+          "snoozed_until": Bson::Null,
+          "updated_at": Bson::DateTime(date::now().into())
         }
       },
       None,
     )
     .await?;
 
-  debug!("Resource position updated, returning 202 status code");
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let activity = Activity::new(&resource, Action::Unsnooze, Source::User);
+  let previous = doc! { "snoozed_until": bson::to_bson(&resource.snoozed_until).unwrap() };
+  ctx
+    .models
+    .activity
+    .record(activity.with_previous(previous))
+    .await;
+
+  debug!("Resource unsnoozed, returning 202 status code");
   let res = HttpResponse::Accepted().finish();
   Ok(res)
 }
 
+async fn enable_resource_review(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let resource = ctx
+    .models
+    .resource
+    .find_one(doc! { "_id": &resource_id, "user": &user_id }, None)
+    .await?;
+
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  if resource.review.is_some() {
+    debug!("Resource review was already enabled, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  // Completed resources are scheduled right away, otherwise the first review
+  // is scheduled once the resource is completed.
+  let review = if resource.completed_at.is_some() {
+    Review::default().next()
+  } else {
+    Review::default()
+  };
+
+  ctx
+    .models
+    .resource
+    .update_one(
+      doc! { "_id": &resource_id, "user": &user_id },
+      doc! { "$set": { "review": bson::to_bson(&review).unwrap() } },
+      None,
+    )
+    .await?;
+
+  debug!("Returning resource review");
+  let review: PrivateReview = review.into();
+  let res = HttpResponse::Ok().json(review);
+  Ok(res)
+}
+
+async fn disable_resource_review(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let result = ctx
+    .models
+    .resource
+    .update_one(
+      doc! { "_id": &resource_id, "user": &user_id },
+      doc! { "$set": { "review": Bson::Null } },
+      None,
+    )
+    .await?;
+
+  if result.matched_count == 0 {
+    debug!("Resource not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  debug!("Resource review disabled, returning 204 status code");
+  let res = HttpResponse::NoContent().finish();
+  Ok(res)
+}
+
+async fn update_progress(ctx: Ctx, id: ID, user_id: UserID, body: ProgressUpdateBody) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  match body.validate() {
+    Ok(_) => (),
+    Err(_err) => {
+      debug!("Failed updating progress, payload is not valid. Returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let resource = ctx
+    .models
+    .resource
+    .find_one(doc! { "_id": &resource_id, "user": &user_id }, None)
+    .await?;
+
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let now = date::now();
+  let time_spent = body.time_spent.unwrap_or(0);
+
+  // The progress is initialized once, following updates are applied with
+  // field operators so concurrent updates do not lose the time spent.
+  if resource.progress.is_none() {
+    let progress = ReadingProgress {
+      percentage: None,
+      scroll_position: None,
+      time_spent: 0,
+      updated_at: now,
+    };
+
+    ctx
+      .models
+      .resource
+      .update_one(
+        doc! { "_id": &resource_id, "user": &user_id, "progress": Bson::Null },
+        doc! { "$set": { "progress": bson::to_bson(&progress).unwrap() } },
+        None,
+      )
+      .await?;
+  }
+
+  let mut set = doc! { "progress.updated_at": Bson::DateTime(now.into()) };
+  if let Some(percentage) = body.percentage {
+    set.insert("progress.percentage", percentage);
+  }
+  if let Some(scroll_position) = body.scroll_position {
+    set.insert("progress.scroll_position", scroll_position);
+  }
+
+  let options = FindOneAndUpdateOptions::builder()
+    .return_document(mongodb::options::ReturnDocument::After)
+    .build();
+
+  let progress = ctx
+    .models
+    .resource
+    .find_one_and_update(
+      doc! { "_id": &resource_id, "user": &user_id },
+      doc! { "$set": set, "$inc": { "progress.time_spent": time_spent } },
+      Some(options),
+    )
+    .await?
+    .and_then(|resource| resource.progress);
+
+  let progress = match progress {
+    Some(progress) => progress,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  if time_spent > 0 {
+    let session = ReadingSession {
+      id: None,
+      user: user_id,
+      list: resource.list,
+      resource: resource_id,
+      duration: time_spent,
+      created_at: now,
+    };
+    ctx.models.reading_session.create(session).await?;
+  }
+
+  debug!("Returning resource progress");
+  let progress: PrivateReadingProgress = progress.into();
+  let res = HttpResponse::Ok().json(progress);
+  Ok(res)
+}
+
 async fn get_resource_metrics(ctx: Ctx, user_id: UserID, qs: web::Query<Query>) -> Response {
   let user_id = user_id.0;
   let mut pipeline = vec![];
@@ -588,7 +1327,7 @@ async fn get_resource_metrics(ctx: Ctx, user_id: UserID, qs: web::Query<Query>)
     must.push(doc! {
       "text": {
         "query": search_text,
-        "path": ["title", "description", "tags"]
+        "path": ["title", "description", "tags", "annotations_text"]
       }
     });
   }
@@ -1,13 +1,19 @@
 use actix_web::{web, HttpResponse};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use serde_json::json;
+use std::collections::HashMap;
 use wither::bson::doc;
+use wither::bson::oid::ObjectId;
 
 use crate::auth;
 use crate::auth::UserID;
-use crate::jobs::create_resources::JobPayload;
+use crate::importers::ImportFormat;
+use crate::jobs::create_resources::create_payloads;
 use crate::lib::util::to_object_id;
+use crate::models::activity::Source;
+use crate::models::import_job::ImportJob;
+use crate::models::import_job_item::ImportJobItem;
 use crate::models::Model as ModelTrait;
 use crate::Context;
 
@@ -26,61 +32,90 @@ pub fn create_router(cfg: &mut web::ServiceConfig) {
 
 #[derive(Serialize, Deserialize)]
 struct RequestBody {
-  list: String,
+  // Defaults to the inbox list of the user.
+  list: Option<String>,
   payload: String,
+  format: Option<ImportFormat>,
+  // Creates a list for each bookmark folder, bookmarks without folder are
+  // imported to the specified list.
+  folders_as_lists: Option<bool>,
 }
 
 async fn import_resources(ctx: Ctx, body: web::Json<RequestBody>, user: UserID) -> Response {
   let user_id = user.0;
-  let list_id = to_object_id(body.list.clone())?;
-  let payload = body.payload.clone();
+  let body = body.into_inner();
 
-  let list = ctx
-    .models
-    .list
-    .find_one(doc! { "_id": list_id, "user": user_id }, None)
-    .await?;
+  let list = match body.list {
+    Some(ref list_id) => {
+      let list_id = to_object_id(list_id)?;
+      ctx
+        .models
+        .list
+        .find_one(doc! { "_id": list_id, "user": &user_id }, None)
+        .await?
+    }
+    None => Some(ctx.models.list.find_or_create_inbox(&user_id).await?),
+  };
 
   let list = match list {
     Some(list) => list,
     None => return Ok(HttpResponse::NotFound().finish()),
   };
 
-  let urls = parse_import_payload(payload);
-  let payload = JobPayload {
-    list: list.id.unwrap().to_string(),
-    urls,
-  };
-
-  // TODO: Queue un batch.
-  ctx.jobs.queue("create_resources", payload).await;
+  let format = body.format.unwrap_or_default();
+  let items = format.importer().parse(&body.payload);
 
-  debug!("Returning resource metadata to the client");
-  let res = HttpResponse::Ok().finish();
-  Ok(res)
-}
+  let list_id = list.id.unwrap();
+  // Items are stored before the job, the ID is generated up front.
+  let job_id = ObjectId::new();
+  let mut job_items = vec![];
+  let mut folder_lists: HashMap<String, ObjectId> = HashMap::new();
+  let folders_as_lists = body.folders_as_lists.unwrap_or(false);
 
-#[derive(Debug, Clone, Validate, Serialize, Deserialize)]
-struct ImportItem {
-  #[validate(url)]
-  url: String,
-}
+  for (index, item) in items.into_iter().enumerate() {
+    if !folders_as_lists || item.folder.is_empty() || item.error.is_some() {
+      job_items.push(ImportJobItem::new(&job_id, index, item, list_id.clone()));
+      continue;
+    }
 
-fn parse_import_payload(payload: String) -> Vec<String> {
-  payload
-    .trim()
-    .lines()
-    .filter(|line| !line.is_empty())
-    .filter_map(|line| {
-      let url = line.trim().split(' ').next()?;
-      let import_item = ImportItem {
-        url: url.to_string(),
-      };
-
-      match import_item.validate() {
-        Ok(_) => Some(import_item.url),
-        Err(_) => None,
+    let title = item.folder.join(" / ");
+    let folder_list_id = match folder_lists.get(&title) {
+      Some(folder_list_id) => folder_list_id.clone(),
+      None => {
+        let folder_list = ctx
+          .models
+          .list
+          .find_or_create_by_title(&user_id, &title)
+          .await?;
+        let folder_list_id = folder_list.id.unwrap();
+        folder_lists.insert(title, folder_list_id.clone());
+        folder_list_id
       }
-    })
-    .collect::<Vec<String>>()
+    };
+
+    job_items.push(ImportJobItem::new(&job_id, index, item, folder_list_id));
+  }
+
+  let count = job_items.len();
+  let mut job = ImportJob::new(user_id, list_id, format, Source::Import, &job_items);
+  job.id = Some(job_id);
+  ctx.models.import_job_item.create_many(&job_items).await?;
+  let job = ctx.models.import_job.create(job).await?;
+
+  for payload in create_payloads(&job, &job_items) {
+    ctx.jobs.queue("create_resources", payload).await;
+  }
+
+  let created_lists = folder_lists
+    .values()
+    .map(|list_id| list_id.to_hex())
+    .collect::<Vec<String>>();
+
+  debug!("Resources import queued, returning 200 status code");
+  let res = HttpResponse::Ok().json(json!({
+    "id": job.id.unwrap().to_hex(),
+    "count": count,
+    "lists": created_lists
+  }));
+  Ok(res)
 }
@@ -0,0 +1,167 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::mongodb::options::FindOptions;
+
+use crate::auth;
+use crate::auth::UserID;
+use crate::lib::id::ID;
+use crate::lib::minhash;
+use crate::lib::serde::serialize_object_id_as_hex_string;
+use crate::models::resource::Kind;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+// Maximum number of candidates compared with the resource signature.
+const MAX_CANDIDATES: i64 = 1000;
+// Candidates below this estimated similarity are not considered related.
+const MIN_SIMILARITY: f64 = 0.1;
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Deserialize)]
+struct Query {
+  limit: Option<usize>,
+}
+
+// Related resources can belong to public lists of other users, only the
+// public attributes of the resource are returned.
+#[derive(Debug, Serialize)]
+struct RelatedResource {
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  id: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  user: ObjectId,
+  #[serde(serialize_with = "serialize_object_id_as_hex_string")]
+  list: ObjectId,
+  url: String,
+  title: Option<String>,
+  description: Option<String>,
+  thumbnail: Option<String>,
+  favicon: Option<String>,
+  kind: Option<Kind>,
+  tags: Vec<String>,
+  similarity: f64,
+}
+
+impl RelatedResource {
+  fn new(resource: Resource, similarity: f64) -> Self {
+    Self {
+      id: resource.id.unwrap(),
+      user: resource.user,
+      list: resource.list,
+      url: resource.url,
+      title: resource.title,
+      description: resource.description,
+      thumbnail: resource.thumbnail,
+      favicon: resource.favicon,
+      kind: resource.kind,
+      tags: resource.tags,
+      similarity,
+    }
+  }
+}
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/resources/{id}/related")
+      .route(web::get().to(query_related_resources))
+      .wrap(auth),
+  );
+}
+
+// Finds the resources of the user and of public lists with a similar content
+// using the MinHash signatures computed by the index resources job.
+async fn query_related_resources(
+  ctx: Ctx,
+  id: ID,
+  user_id: UserID,
+  qs: web::Query<Query>,
+) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+  let limit = qs.limit.unwrap_or(DEFAULT_LIMIT);
+
+  let resource_exists = ctx
+    .models
+    .resource
+    .exists(doc! { "_id": &resource_id, "user": &user_id })
+    .await?;
+
+  if !resource_exists {
+    debug!("Resource not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let signature = ctx
+    .models
+    .resource_signature
+    .find_one(doc! { "resource": &resource_id }, None)
+    .await?;
+
+  // The resource was not indexed yet.
+  let signature = match signature {
+    Some(signature) => signature,
+    None => {
+      debug!("Resource signature not found, returning empty related resources");
+      return Ok(HttpResponse::Ok().json(Vec::<RelatedResource>::new()));
+    }
+  };
+
+  let candidates = ctx
+    .models
+    .resource_signature
+    .find_candidates(&signature, &user_id, MAX_CANDIDATES)
+    .await?;
+
+  let mut similarities = candidates
+    .into_iter()
+    .map(|candidate| {
+      let similarity = minhash::similarity(&signature.minhash, &candidate.minhash);
+      (candidate.resource, similarity)
+    })
+    .filter(|(_, similarity)| *similarity >= MIN_SIMILARITY)
+    .collect::<Vec<(ObjectId, f64)>>();
+
+  similarities.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+  similarities.truncate(limit);
+
+  let resource_ids = similarities
+    .iter()
+    .map(|(resource_id, _)| resource_id.clone())
+    .collect::<Vec<ObjectId>>();
+  let similarities = similarities.into_iter().collect::<HashMap<ObjectId, f64>>();
+
+  let options = FindOptions::builder()
+    .projection(doc! { "html": false, "text": false })
+    .build();
+  let mut resources = ctx
+    .models
+    .resource
+    .find(doc! { "_id": { "$in": resource_ids } }, Some(options))
+    .await?
+    .into_iter()
+    .map(|resource| {
+      let similarity = similarities[resource.id.as_ref().unwrap()];
+      RelatedResource::new(resource, similarity)
+    })
+    .collect::<Vec<RelatedResource>>();
+
+  resources.sort_by(|a, b| {
+    b.similarity
+      .partial_cmp(&a.similarity)
+      .unwrap_or(std::cmp::Ordering::Equal)
+  });
+
+  debug!("Returning related resources");
+  let res = HttpResponse::Ok().json(resources);
+  Ok(res)
+}
@@ -0,0 +1,334 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use futures::future::try_join_all;
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+use validator::Validate;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+
+use crate::actors::subscription;
+use crate::auth;
+use crate::auth::UserID;
+use crate::errors::Error;
+use crate::importers;
+use crate::lib::date;
+use crate::lib::util;
+use crate::lib::util::to_object_id;
+use crate::metadata::native;
+use crate::metadata::PageMetadata;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::annotation::Annotation;
+use crate::models::annotation::Highlight;
+use crate::models::annotation::HighlightSource;
+use crate::models::list::List;
+use crate::models::resource::PrivateResource;
+use crate::models::resource::Resource;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+// The captured page HTML is usually bigger than the default JSON limit.
+const MAX_PAYLOAD_SIZE: usize = 10 * 1024 * 1024;
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+
+#[derive(Deserialize)]
+struct SaveBody {
+  url: String,
+  // Defaults to the Inbox list of the user.
+  list: Option<String>,
+  title: Option<String>,
+  // Text selected by the user in the page, saved as a highlight.
+  selection: Option<String>,
+  // Page HTML captured by the client, used instead of fetching the page.
+  html: Option<String>,
+}
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/save")
+      .app_data(web::JsonConfig::default().limit(MAX_PAYLOAD_SIZE))
+      .route(web::post().to(save_resource))
+      .wrap(auth),
+  );
+}
+
+// Saves a page in a single request, intended for browser extensions. Saving
+// a URL that is already in the list returns the existing resource.
+async fn save_resource(ctx: Ctx, body: web::Json<SaveBody>, user: UserID) -> Response {
+  let user_id = user.0;
+  let body = body.into_inner();
+  let url = util::parse_url(body.url.as_str())?;
+
+  let list = match body.list {
+    Some(list_id) => {
+      let list_id = to_object_id(list_id)?;
+      ctx
+        .models
+        .list
+        .find_one(doc! { "_id": &list_id, "user": &user_id }, None)
+        .await?
+    }
+    None => Some(ctx.models.list.find_or_create_inbox(&user_id).await?),
+  };
+
+  let list = match list {
+    Some(list) => list,
+    None => {
+      debug!("List not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let list_id = list.id.clone().unwrap();
+  let resource = ctx
+    .models
+    .resource
+    .find_one(doc! { "list": &list_id, "url": url.to_string() }, None)
+    .await?;
+
+  let (resource, is_created) = match resource {
+    Some(resource) => (resource, false),
+    None => {
+      let resource = create_resource(&ctx, &list, url.to_string(), body.title).await?;
+      (resource, true)
+    }
+  };
+  let resource_id = resource.id.clone().unwrap();
+
+  if is_created {
+    populate_resource(&ctx, &resource_id, &url, body.html).await;
+  }
+
+  let resource = ctx
+    .models
+    .resource
+    .find_by_id(&resource_id)
+    .await?
+    .unwrap_or(resource);
+
+  if let Some(selection) = body.selection.as_deref().map(str::trim) {
+    if !selection.is_empty() {
+      create_selection_annotation(&ctx, &resource, selection).await?;
+    }
+  }
+
+  let suggested_lists = ctx.models.list.suggest_lists(&resource).await?;
+  let suggested_lists = suggested_lists
+    .iter()
+    .map(|list| ctx.models.list.to_private_schema(list));
+  let suggested_lists = try_join_all(suggested_lists).await?;
+
+  let resource: PrivateResource = resource.into();
+  let body = json!({
+    "resource": resource,
+    "suggested_lists": suggested_lists
+  });
+
+  if !is_created {
+    debug!("Resource already saved, returning 200 status code");
+    return Ok(HttpResponse::Ok().json(body));
+  }
+
+  debug!("Returning saved resource");
+  let res = HttpResponse::Created().json(body);
+  Ok(res)
+}
+
+async fn create_resource(
+  ctx: &Ctx,
+  list: &List,
+  url: String,
+  title: Option<String>,
+) -> Result<Resource, Error> {
+  let list_id = list.id.clone().unwrap();
+  let position = ctx.models.list.get_next_resource_position(&list_id).await?;
+  let now = date::now();
+
+  // The page title is shown until the resource is populated, it is not an
+  // user edit and can be overwritten.
+  let resource = Resource {
+    id: None,
+    position,
+    tags: vec![],
+    user: list.user.clone(),
+    list: list_id,
+    url,
+    title: title.as_deref().and_then(importers::clean_text),
+    description: None,
+    thumbnail: None,
+    created_at: now,
+    updated_at: now,
+    completed_at: None,
+    html: None,
+    text: None,
+    author: None,
+    length: None,
+    publisher: None,
+    populated_at: None,
+    refresh_attempted_at: None,
+    signature_indexed_at: None,
+    annotations_text: None,
+    estimated_reading_time: None,
+    progress: None,
+    snoozed_until: None,
+    review: None,
+    archived_at: None,
+    favicon: None,
+    kind: None,
+    media: None,
+    health: None,
+    last_checked_at: None,
+    edited_fields: Some(vec![]),
+    suggested_tags: None,
+  };
+
+  let resource = ctx.models.list.create_resource(resource).await?;
+  let resource_id = resource.id.clone().unwrap();
+
+  ctx
+    .models
+    .activity
+    .record(Activity::new(&resource, Action::Create, Source::User))
+    .await;
+
+  ctx
+    .actors
+    .subscription
+    .try_send(subscription::on_resource_created::ResourceCreated {
+      resource_id: resource_id.clone(),
+    })
+    .map_err(|err| error!("Failed to send message to subscription actor, {}", err))
+    .ok();
+
+  ctx
+    .models
+    .list
+    .update_last_activity_at(&resource.list)
+    .await
+    .map_err(|err| {
+      error!(
+        "Failed to update last activity for list {}. Error {}",
+        &resource.list, err
+      )
+    })
+    .ok();
+
+  Ok(resource)
+}
+
+// The captured HTML is parsed in the request so the response includes the
+// page metadata, otherwise the page is fetched by the populate job. Parsing
+// is CPU bound, it runs in the blocking thread pool.
+async fn populate_resource(ctx: &Ctx, resource_id: &ObjectId, url: &Url, html: Option<String>) {
+  let html = match html {
+    Some(html) if !html.trim().is_empty() => html,
+    _ => {
+      ctx
+        .jobs
+        .queue("populate_resources", vec![resource_id.to_string()])
+        .await;
+      return;
+    }
+  };
+
+  let url = url.clone();
+  let metadata = web::block(move || Ok::<PageMetadata, ()>(native::parse(&html, &url, false)))
+    .await
+    .map_err(|err| format!("Failed to parse captured HTML. Error: {}", err));
+
+  let result = match metadata {
+    Ok(metadata) => ctx
+      .models
+      .resource
+      .populate_from_metadata(resource_id.clone(), metadata)
+      .await
+      .map_err(|err| err.to_string()),
+    Err(err) => Err(err),
+  };
+
+  if let Err(err) = result {
+    error!(
+      "Failed to populate resource {} from captured HTML. Error: {}",
+      resource_id, err
+    );
+    ctx
+      .jobs
+      .queue("populate_resources", vec![resource_id.to_string()])
+      .await;
+  }
+}
+
+// The selection is highlighted when it is found in the extracted text,
+// otherwise it is quoted in a note. Saving the same selection again does not
+// create another annotation.
+async fn create_selection_annotation(
+  ctx: &Ctx,
+  resource: &Resource,
+  selection: &str,
+) -> Result<(), Error> {
+  let resource_id = resource.id.clone().unwrap();
+  let highlight = resource.text.as_deref().and_then(|text| {
+    let index = text.find(selection)?;
+    let start = text[..index].chars().count();
+    let end = start + selection.chars().count();
+
+    Some(Highlight {
+      source: HighlightSource::Text,
+      start: start as i32,
+      end: end as i32,
+      quote: selection.to_owned(),
+    })
+  });
+
+  let note = match highlight {
+    Some(_) => None,
+    None => {
+      let quote = selection
+        .lines()
+        .map(|line| format!("> {}", line))
+        .collect::<Vec<String>>()
+        .join("\n");
+      importers::clean_note(&quote)
+    }
+  };
+
+  let query = match (&highlight, &note) {
+    (Some(highlight), _) => doc! { "resource": &resource_id, "highlight.quote": &highlight.quote },
+    (None, Some(note)) => doc! { "resource": &resource_id, "note": note },
+    (None, None) => return Ok(()),
+  };
+
+  if ctx.models.annotation.exists(query).await? {
+    debug!("Selection already saved as an annotation");
+    return Ok(());
+  }
+
+  let now = date::now();
+  let annotation = Annotation {
+    id: None,
+    user: resource.user.clone(),
+    list: resource.list.clone(),
+    resource: resource_id.clone(),
+    highlight,
+    note,
+    created_at: now,
+    updated_at: now,
+  };
+
+  annotation.validate().map_err(Error::ValidateModel)?;
+  ctx.models.annotation.create(annotation).await?;
+
+  let text = ctx.models.annotation.get_search_text(&resource_id).await?;
+  ctx
+    .models
+    .resource
+    .update_annotations_text(&resource_id, text)
+    .await
+}
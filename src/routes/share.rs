@@ -0,0 +1,195 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::Deserialize;
+use validator::Validate;
+use wither::bson::doc;
+use wither::mongodb::options::FindOptions;
+
+use crate::auth;
+use crate::auth::UserID;
+use crate::errors::Error;
+use crate::lib::date;
+use crate::lib::id::ID;
+use crate::lib::util::create_random_string;
+use crate::models::share::PrivateShare;
+use crate::models::share::Share;
+use crate::models::Model as ModelTrait;
+use crate::pages;
+use crate::Context;
+
+const SHORT_ID_SIZE: usize = 10;
+const MAX_SHORT_ID_ATTEMPTS: usize = 5;
+
+#[derive(Deserialize)]
+pub struct ShareCreate {
+  pub note: Option<String>,
+  pub include_annotations: Option<bool>,
+}
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+type ShareCreateBody = web::Json<ShareCreate>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/resources/{id}/shares")
+      .route(web::get().to(query_shares))
+      .route(web::post().to(create_share))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/shares/{id}")
+      .route(web::delete().to(revoke_share))
+      .wrap(auth),
+  );
+
+  cfg.service(web::resource("/r/{short_id}").route(web::get().to(get_shared_resource)));
+}
+
+async fn query_shares(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let resource_exists = ctx
+    .models
+    .resource
+    .exists(doc! { "_id": &resource_id, "user": &user_id })
+    .await?;
+
+  if !resource_exists {
+    debug!("Resource not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  let options = FindOptions::builder()
+    .sort(doc! { "created_at": -1 })
+    .build();
+  let shares = ctx
+    .models
+    .share
+    .find(doc! { "resource": &resource_id }, Some(options))
+    .await?
+    .iter()
+    .map(|share| share.to_private_schema(&ctx.settings.base_url))
+    .collect::<Vec<PrivateShare>>();
+
+  debug!("Returning shares");
+  let res = HttpResponse::Ok().json(shares);
+  Ok(res)
+}
+
+async fn create_share(ctx: Ctx, id: ID, body: ShareCreateBody, user_id: UserID) -> Response {
+  let resource_id = id.0;
+  let user_id = user_id.0;
+
+  let resource = ctx
+    .models
+    .resource
+    .find_one(doc! { "_id": &resource_id, "user": &user_id }, None)
+    .await?;
+
+  let resource = match resource {
+    Some(resource) => resource,
+    None => {
+      debug!("Resource not found, returning 404 status code");
+      return Ok(HttpResponse::NotFound().finish());
+    }
+  };
+
+  let body = body.into_inner();
+  let now = date::now();
+  let mut share = Share {
+    id: None,
+    short_id: create_random_string(SHORT_ID_SIZE),
+    user: user_id,
+    list: resource.list,
+    resource: resource_id,
+    note: body.note,
+    include_annotations: body.include_annotations.unwrap_or(false),
+    views_count: 0,
+    created_at: now,
+    updated_at: now,
+    revoked_at: None,
+  };
+
+  share.validate().map_err(Error::ValidateModel)?;
+
+  // Short IDs are random, a new one is generated when it is already taken.
+  let mut attempts = 0;
+  let share = loop {
+    match ctx.models.share.create(share.clone()).await {
+      Err(err) if err.is_duplicate_key() && attempts < MAX_SHORT_ID_ATTEMPTS => {
+        attempts += 1;
+        share.short_id = create_random_string(SHORT_ID_SIZE);
+      }
+      result => break result?,
+    }
+  };
+  let share = share.to_private_schema(&ctx.settings.base_url);
+
+  debug!("Returning created share");
+  let res = HttpResponse::Created().json(share);
+  Ok(res)
+}
+
+async fn revoke_share(ctx: Ctx, id: ID, user_id: UserID) -> Response {
+  let share_id = id.0;
+  let user_id = user_id.0;
+
+  let share = ctx.models.share.revoke(&share_id, &user_id).await?;
+  if share.is_none() {
+    debug!("Share not found, returning 404 status code");
+    return Ok(HttpResponse::NotFound().finish());
+  }
+
+  debug!("Share revoked, returning 204 status code");
+  let res = HttpResponse::NoContent().finish();
+  Ok(res)
+}
+
+async fn get_shared_resource(ctx: Ctx, short_id: web::Path<String>) -> Response {
+  let short_id = short_id.into_inner();
+  let not_found = || {
+    HttpResponse::NotFound()
+      .content_type("text/html; charset=utf-8")
+      .body(pages::create_not_found_page().into_string())
+  };
+
+  let share = match ctx.models.share.view(&short_id).await? {
+    Some(share) => share,
+    None => {
+      debug!("Share not found or revoked, returning 404 status code");
+      return Ok(not_found());
+    }
+  };
+
+  let resource = match ctx.models.resource.find_by_id(&share.resource).await? {
+    Some(resource) => resource,
+    None => {
+      debug!("Shared resource not found, returning 404 status code");
+      return Ok(not_found());
+    }
+  };
+
+  let annotations = if share.include_annotations {
+    ctx
+      .models
+      .annotation
+      .find_by_resource(&share.resource)
+      .await?
+  } else {
+    vec![]
+  };
+
+  let share_url = share.get_url(&ctx.settings.base_url);
+  let page = pages::create_shared_resource_page(&share, &resource, &annotations, &share_url);
+
+  debug!("Returning shared resource page");
+  let res = HttpResponse::Ok()
+    .content_type("text/html; charset=utf-8")
+    .body(page.into_string());
+  Ok(res)
+}
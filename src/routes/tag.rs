@@ -0,0 +1,316 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+use wither::bson::Bson;
+use wither::bson::Document;
+use wither::mongodb::options::FindOptions;
+
+use crate::auth;
+use crate::auth::UserID;
+use crate::errors::Error;
+use crate::lib::util::sanitize_tags;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+#[derive(Deserialize)]
+struct Query {
+  prefix: Option<String>,
+  limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct AutocompleteQuery {
+  q: String,
+  limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct TagUpdate {
+  name: String,
+}
+
+#[derive(Deserialize)]
+struct TagMerge {
+  tags: Vec<String>,
+  into: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TagGroup {
+  #[serde(rename = "_id")]
+  tag: String,
+  count: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct TagCount {
+  tag: String,
+  count: i64,
+  resources_count: i64,
+  lists_count: i64,
+}
+
+impl TagCount {
+  fn new(tag: String) -> Self {
+    Self {
+      tag,
+      count: 0,
+      resources_count: 0,
+      lists_count: 0,
+    }
+  }
+}
+
+type Response = actix_web::Result<HttpResponse>;
+type Ctx = web::Data<Context>;
+type TagUpdateBody = web::Json<TagUpdate>;
+type TagMergeBody = web::Json<TagMerge>;
+
+const DEFAULT_AUTOCOMPLETE_LIMIT: usize = 10;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  let auth = HttpAuthentication::bearer(auth::validator);
+
+  cfg.service(
+    web::resource("/tags")
+      .route(web::get().to(query_tags))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/tags/autocomplete")
+      .route(web::get().to(autocomplete_tags))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/tags/merge")
+      .route(web::post().to(merge_tags))
+      .wrap(auth.clone()),
+  );
+
+  cfg.service(
+    web::resource("/tags/{tag}")
+      .route(web::put().to(rename_tag))
+      .wrap(auth),
+  );
+}
+
+async fn query_tags(ctx: Ctx, user_id: UserID, qs: web::Query<Query>) -> Response {
+  let user_id = user_id.0;
+  let tags = get_tag_counts(&ctx, &user_id, qs.prefix.as_deref(), qs.limit).await?;
+
+  debug!("Returning tags");
+  let res = HttpResponse::Ok().json(tags);
+  Ok(res)
+}
+
+async fn autocomplete_tags(
+  ctx: Ctx,
+  user_id: UserID,
+  qs: web::Query<AutocompleteQuery>,
+) -> Response {
+  let user_id = user_id.0;
+  let limit = qs.limit.unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT);
+  let prefix = qs.q.to_lowercase();
+
+  let tags = get_tag_counts(&ctx, &user_id, Some(prefix.trim()), Some(limit))
+    .await?
+    .into_iter()
+    .map(|tag| tag.tag)
+    .collect::<Vec<String>>();
+
+  debug!("Returning tag suggestions");
+  let res = HttpResponse::Ok().json(tags);
+  Ok(res)
+}
+
+async fn rename_tag(
+  ctx: Ctx,
+  tag: web::Path<String>,
+  body: TagUpdateBody,
+  user_id: UserID,
+) -> Response {
+  let user_id = user_id.0;
+  let tag = match sanitize_tags(vec![tag.into_inner()]).pop() {
+    Some(tag) => tag,
+    None => {
+      debug!("Tag is empty, returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let name = match sanitize_tags(vec![body.into_inner().name]).pop() {
+    Some(name) => name,
+    None => {
+      debug!("Tag name is empty, returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  replace_tags(&ctx, &user_id, vec![tag], name).await
+}
+
+async fn merge_tags(ctx: Ctx, body: TagMergeBody, user_id: UserID) -> Response {
+  let user_id = user_id.0;
+  let body = body.into_inner();
+
+  let tags = sanitize_tags(body.tags);
+  let into = match sanitize_tags(vec![body.into]).pop() {
+    Some(into) => into,
+    None => {
+      debug!("Target tag is empty, returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  if tags.is_empty() {
+    debug!("No tags to merge, returning 400 status code");
+    return Ok(HttpResponse::BadRequest().finish());
+  }
+
+  replace_tags(&ctx, &user_id, tags, into).await
+}
+
+// Replaces the specified tags with the new tag in every resource and list of
+// the user. The update is done with an aggregation pipeline so each document
+// is updated in a single operation, removing the duplicated tags when the new
+// tag was already present.
+//
+// Resources and lists are updated without a transaction, when an update fails
+// the documents updated before keep the new tag. The replacement is
+// idempotent, retrying the same request updates the remaining documents and
+// leaves the updated ones untouched.
+async fn replace_tags(ctx: &Ctx, user_id: &ObjectId, tags: Vec<String>, into: String) -> Response {
+  let query = doc! { "user": user_id, "tags": { "$in": &tags } };
+  let pipeline = create_replace_tags_pipeline(&tags, &into);
+
+  let options = FindOptions::builder()
+    .projection(doc! { "html": false, "text": false })
+    .build();
+  let updated_resources = ctx
+    .models
+    .resource
+    .find(query.clone(), Some(options))
+    .await?;
+
+  // The tags are part of the resource similarity signature.
+  let mut resource_pipeline = pipeline.clone();
+  resource_pipeline.push(doc! { "$set": { "signature_indexed_at": Bson::Null } });
+  let resources = ctx
+    .models
+    .resource
+    .update_many(query.clone(), resource_pipeline, None)
+    .await?;
+
+  let activities = updated_resources
+    .iter()
+    .map(|resource| {
+      let previous = doc! { "tags": &resource.tags };
+      Activity::new(resource, Action::Update, Source::User).with_previous(previous)
+    })
+    .collect::<Vec<Activity>>();
+  ctx.models.activity.record_many(activities).await;
+
+  let lists = ctx.models.list.update_many(query, pipeline, None).await?;
+
+  debug!("Tags updated, returning 200 status code");
+  let res = HttpResponse::Ok().json(json!({
+    "tag": into,
+    "resources_count": resources.modified_count,
+    "lists_count": lists.modified_count,
+  }));
+  Ok(res)
+}
+
+fn create_replace_tags_pipeline(tags: &[String], into: &str) -> Vec<Document> {
+  vec![doc! {
+    "$set": {
+      "tags": {
+        "$reduce": {
+          "input": {
+            "$map": {
+              "input": "$tags",
+              "as": "tag",
+              "in": { "$cond": [{ "$in": ["$$tag", tags] }, into, "$$tag"] }
+            }
+          },
+          "initialValue": [],
+          "in": {
+            "$cond": [
+              { "$in": ["$$this", "$$value"] },
+              "$$value",
+              { "$concatArrays": ["$$value", ["$$this"]] }
+            ]
+          }
+        }
+      }
+    }
+  }]
+}
+
+fn create_tag_counts_pipeline(user_id: &ObjectId, prefix: Option<&str>) -> Vec<Document> {
+  let mut pipeline = vec![
+    doc! { "$match": { "user": user_id } },
+    doc! { "$unwind": "$tags" },
+  ];
+
+  if let Some(prefix) = prefix.filter(|prefix| !prefix.is_empty()) {
+    let regex = format!("^{}", regex::escape(prefix));
+    pipeline.push(doc! { "$match": { "tags": { "$regex": regex } } });
+  }
+
+  pipeline.push(doc! { "$group": { "_id": "$tags", "count": { "$sum": 1 } } });
+  pipeline
+}
+
+// Returns the tags used by the user in resources and lists sorted by the
+// number of times they are used.
+async fn get_tag_counts(
+  ctx: &Ctx,
+  user_id: &ObjectId,
+  prefix: Option<&str>,
+  limit: Option<usize>,
+) -> Result<Vec<TagCount>, Error> {
+  let pipeline = create_tag_counts_pipeline(user_id, prefix);
+  let resource_tags = ctx
+    .models
+    .resource
+    .aggregate::<TagGroup>(pipeline.clone())
+    .await?;
+  let list_tags = ctx.models.list.aggregate::<TagGroup>(pipeline).await?;
+
+  let mut counts: HashMap<String, TagCount> = HashMap::new();
+
+  for group in resource_tags {
+    let entry = counts
+      .entry(group.tag.clone())
+      .or_insert_with(|| TagCount::new(group.tag));
+    entry.resources_count = group.count;
+    entry.count += group.count;
+  }
+
+  for group in list_tags {
+    let entry = counts
+      .entry(group.tag.clone())
+      .or_insert_with(|| TagCount::new(group.tag));
+    entry.lists_count = group.count;
+    entry.count += group.count;
+  }
+
+  let mut tags = counts.into_values().collect::<Vec<TagCount>>();
+  tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+
+  if let Some(limit) = limit {
+    tags.truncate(limit);
+  }
+
+  Ok(tags)
+}
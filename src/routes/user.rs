@@ -2,6 +2,7 @@ use actix_web::{web, HttpResponse};
 use actix_web_httpauth::middleware::HttpAuthentication;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
 use validator::Validate;
 use wither::bson::doc;
 use wither::bson::Bson;
@@ -111,8 +112,10 @@ async fn create_user(ctx: web::Data<Context>, body: web::Json<UserCreateBody>) -
   let confirm_email = emails::create_confirm_email(send_from, base_url, &user)?;
   ctx.mailer.send(confirm_email).await?;
 
-  debug!("Creating demo list and resource for new user");
-  create_demo_data_for_user::create(&ctx.models, user.id.clone().unwrap()).await?;
+  debug!("Creating inbox, demo list and resource for new user");
+  let user_id = user.id.clone().unwrap();
+  ctx.models.list.find_or_create_inbox(&user_id).await?;
+  create_demo_data_for_user::create(&ctx.models, user_id).await?;
 
   debug!("Returning created user");
   let user: PrivateUser = user.into();
@@ -157,10 +160,11 @@ async fn get_metrics(ctx: Ctx, slug: web::Path<String>) -> Response {
     }
   };
 
+  let user_id = user.id.unwrap();
   let pipeline = vec![
     doc! {
       "$match": {
-        "user": user.id.unwrap(),
+        "user": &user_id,
         "completed_at": { "$exists": true, "$ne": Bson::Null }
       }
     },
@@ -178,7 +182,47 @@ async fn get_metrics(ctx: Ctx, slug: web::Path<String>) -> Response {
     doc! { "$sort": { "_id": 1 } },
   ];
 
-  let metrics = ctx.models.resource.aggregate::<Metric>(pipeline).await?;
+  let completed_metrics = ctx.models.resource.aggregate::<Metric>(pipeline).await?;
+
+  let pipeline = vec![
+    doc! {
+      "$match": {
+        "user": &user_id
+      }
+    },
+    doc! {
+      "$group": {
+        "_id": {
+          "$dateToString": {
+            "date":   "$created_at",
+            "format": "%Y-%m-%d",
+          }
+        },
+        "reading_time": { "$sum": "$duration" }
+      }
+    },
+    doc! { "$sort": { "_id": 1 } },
+  ];
+
+  let reading_metrics = ctx
+    .models
+    .reading_session
+    .aggregate::<Metric>(pipeline)
+    .await?;
+
+  // Merge both metrics by date, days without completed resources or reading
+  // sessions default to zero.
+  let mut metrics: BTreeMap<String, Metric> = BTreeMap::new();
+  for metric in completed_metrics.into_iter().chain(reading_metrics) {
+    let entry = metrics.entry(metric.date.clone()).or_insert(Metric {
+      date: metric.date,
+      completed_count: 0,
+      reading_time: 0,
+    });
+    entry.completed_count += metric.completed_count;
+    entry.reading_time += metric.reading_time;
+  }
+  let metrics = metrics.into_values().collect::<Vec<Metric>>();
 
   debug!("Returning user metrics");
   let res = HttpResponse::Ok().json(metrics);
@@ -340,6 +384,13 @@ async fn create_token_from_google(
 
       let user = ctx.models.user.create(user).await?;
 
+      debug!("Creating inbox for new user");
+      ctx
+        .models
+        .list
+        .find_or_create_inbox(user.id.as_ref().unwrap())
+        .await?;
+
       user
     }
   };
@@ -457,5 +508,9 @@ async fn find_user_by_slug(ctx: web::Data<Context>, slug: web::Path<String>) ->
 struct Metric {
   #[serde(alias = "_id")]
   date: String,
+  #[serde(default)]
   completed_count: i64,
+  // Time spent reading in seconds.
+  #[serde(default)]
+  reading_time: i64,
 }
@@ -0,0 +1,325 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use constant_time_eq::constant_time_eq;
+use serde::Deserialize;
+use std::collections::HashMap;
+use wither::bson::doc;
+use wither::bson::oid::ObjectId;
+
+use crate::importers;
+use crate::importers::ImportFormat;
+use crate::jobs::create_resources::create_payloads;
+use crate::lib::multipart;
+use crate::models::activity::Source;
+use crate::models::import_job::ImportJob;
+use crate::models::import_job_item::ImportJobItem;
+use crate::models::Model as ModelTrait;
+use crate::Context;
+
+// SendGrid posts up to 30MB, attachments included.
+const MAX_PAYLOAD_SIZE: usize = 30 * 1024 * 1024;
+
+type Response = actix_web::Result<HttpResponse>;
+
+pub fn create_router(cfg: &mut web::ServiceConfig) {
+  cfg.service(
+    web::resource("/{secret}")
+      .app_data(web::PayloadConfig::new(MAX_PAYLOAD_SIZE))
+      .route(web::post().to(webhook)),
+  );
+}
+
+// SMTP envelope of the email, sent as a JSON field.
+#[derive(Debug, Default, Deserialize)]
+struct Envelope {
+  #[serde(default)]
+  to: Vec<String>,
+  from: Option<String>,
+}
+
+// Handles SendGrid Inbound Parse webhooks. Emails that can not be processed
+// are acknowledged with a 200 status code, otherwise SendGrid retries them.
+async fn webhook(
+  ctx: web::Data<Context>,
+  req: HttpRequest,
+  secret: web::Path<String>,
+  body: web::Bytes,
+) -> Response {
+  debug!("Processing inbound email webhook");
+
+  if !is_valid_secret(&ctx.settings.inbound_email.secret, &secret) {
+    debug!("Invalid inbound email webhook secret, returning 401 status code");
+    return Ok(HttpResponse::Unauthorized().finish());
+  }
+
+  let content_type = req
+    .headers()
+    .get("Content-Type")
+    .and_then(|value| value.to_str().ok())
+    .unwrap_or_default();
+
+  let fields = match multipart::parse(content_type, &body) {
+    Some(fields) => fields,
+    None => {
+      debug!("Inbound email is not a multipart payload, returning 400 status code");
+      return Ok(HttpResponse::BadRequest().finish());
+    }
+  };
+
+  let envelope = fields
+    .get("envelope")
+    .and_then(|envelope| serde_json::from_str::<Envelope>(envelope).ok())
+    .unwrap_or_default();
+
+  let domain = ctx.settings.inbound_email.domain.as_str();
+  let token = match get_inbound_token(&fields, &envelope, domain) {
+    Some(token) => token,
+    None => {
+      debug!("Inbound email has no list recipient, returning 200 status code");
+      return Ok(HttpResponse::Ok().finish());
+    }
+  };
+
+  let sender = fields
+    .get("from")
+    .and_then(|from| get_address(from))
+    .or_else(|| envelope.from.as_deref().and_then(get_address));
+
+  // Lists are only found by their secret token, the inbox has its own
+  // address like any other list.
+  let list = ctx
+    .models
+    .list
+    .find_one(doc! { "inbound_token": &token }, None)
+    .await?;
+
+  let list = match list {
+    Some(list) => list,
+    None => {
+      debug!("List not found, returning 200 status code");
+      return Ok(HttpResponse::Ok().finish());
+    }
+  };
+
+  if !is_sender_authenticated(&fields, &envelope, sender.as_deref()) {
+    warn!(
+      "Inbound email to list {:?} failed the SPF and DKIM checks",
+      &list.id
+    );
+    return Ok(HttpResponse::Ok().finish());
+  }
+
+  let user = ctx.models.user.find_by_id(&list.user).await?;
+  let user = match user {
+    Some(user) => user,
+    None => {
+      debug!("User not found, returning 200 status code");
+      return Ok(HttpResponse::Ok().finish());
+    }
+  };
+
+  // The sender check only filters out mistakes and unauthenticated spoofing,
+  // anyone can send authenticated email from an address they control. The
+  // inbound token of the list address is the real credential.
+  let is_verified = match sender {
+    Some(sender) => sender.eq_ignore_ascii_case(user.email.trim()),
+    None => false,
+  };
+
+  if !is_verified {
+    warn!(
+      "Inbound email to list {:?} was not sent by the list owner",
+      &list.id
+    );
+    return Ok(HttpResponse::Ok().finish());
+  }
+
+  let items = importers::email::parse(
+    fields.get("text").map(String::as_str),
+    fields.get("html").map(String::as_str),
+  );
+
+  if items.is_empty() {
+    debug!("Inbound email has no links, returning 200 status code");
+    return Ok(HttpResponse::Ok().finish());
+  }
+
+  let list_id = list.id.unwrap();
+  let job_id = ObjectId::new();
+  let job_items = items
+    .into_iter()
+    .enumerate()
+    .map(|(index, item)| ImportJobItem::new(&job_id, index, item, list_id.clone()))
+    .collect::<Vec<ImportJobItem>>();
+
+  let mut job = ImportJob::new(
+    user.id.unwrap(),
+    list_id,
+    ImportFormat::Urls,
+    Source::Email,
+    &job_items,
+  );
+  job.id = Some(job_id);
+  ctx.models.import_job_item.create_many(&job_items).await?;
+  let job = ctx.models.import_job.create(job).await?;
+
+  for payload in create_payloads(&job, &job_items) {
+    ctx.jobs.queue("create_resources", payload).await;
+  }
+
+  debug!("Inbound email links queued, returning 200 status code");
+  Ok(HttpResponse::Ok().finish())
+}
+
+// Finds the first recipient at the inbound domain, sub-addresses like
+// "<token>+work@<domain>" are supported.
+fn get_inbound_token(
+  fields: &HashMap<String, String>,
+  envelope: &Envelope,
+  domain: &str,
+) -> Option<String> {
+  let header_recipients = fields
+    .get("to")
+    .map(|to| {
+      to.split(',')
+        .filter_map(get_address)
+        .collect::<Vec<String>>()
+    })
+    .unwrap_or_default();
+
+  let envelope_recipients = envelope.to.iter().filter_map(|to| get_address(to));
+
+  envelope_recipients
+    .chain(header_recipients)
+    .find_map(|address| {
+      let (local, address_domain) = address.rsplit_once('@')?;
+      if !address_domain.eq_ignore_ascii_case(domain) {
+        return None;
+      }
+
+      let token = local.split('+').next()?.to_lowercase();
+      match token.is_empty() {
+        true => None,
+        false => Some(token),
+      }
+    })
+}
+
+// Gets the address of a mailbox, E.g. "Jane <jane@example.com>".
+fn get_address(mailbox: &str) -> Option<String> {
+  let mailbox = mailbox.trim();
+  let address = match (mailbox.rfind('<'), mailbox.rfind('>')) {
+    (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+    _ => mailbox,
+  };
+
+  let address = address.trim();
+  match address.contains('@') {
+    true => Some(address.to_owned()),
+    false => None,
+  }
+}
+
+fn get_domain(address: &str) -> Option<&str> {
+  address
+    .rsplit_once('@')
+    .map(|(_, domain)| domain.trim())
+    .filter(|domain| !domain.is_empty())
+}
+
+// Secrets are compared in constant time, an empty secret disables the
+// webhook.
+fn is_valid_secret(expected: &str, secret: &str) -> bool {
+  !expected.is_empty() && constant_time_eq(expected.as_bytes(), secret.as_bytes())
+}
+
+// Emails pass when SPF passes for the sender domain or a DKIM signature of
+// the sender domain is valid. SPF checks the envelope sender, it only counts
+// when its domain is the sender domain. Missing results are failures.
+fn is_sender_authenticated(
+  fields: &HashMap<String, String>,
+  envelope: &Envelope,
+  sender: Option<&str>,
+) -> bool {
+  let sender_domain = match sender.and_then(get_domain) {
+    Some(domain) => domain,
+    None => return false,
+  };
+
+  let spf_pass = fields
+    .get("SPF")
+    .map(|spf| spf.trim().eq_ignore_ascii_case("pass"))
+    .unwrap_or(false);
+  let is_spf_aligned = envelope
+    .from
+    .as_deref()
+    .and_then(get_address)
+    .as_deref()
+    .and_then(get_domain)
+    .map(|domain| domain.eq_ignore_ascii_case(sender_domain))
+    .unwrap_or(false);
+
+  if spf_pass && is_spf_aligned {
+    return true;
+  }
+
+  // SendGrid sends the result of each signature, E.g.
+  // "{@example.com : pass, @sendgrid.net : fail}".
+  let dkim = fields.get("dkim").map(String::as_str).unwrap_or_default();
+  dkim
+    .trim_matches(|c| c == '{' || c == '}' || char::is_whitespace(c))
+    .split(',')
+    .filter_map(|signature| signature.split_once(':'))
+    .any(|(domain, result)| {
+      let domain = domain.trim().trim_start_matches('@');
+      domain.eq_ignore_ascii_case(sender_domain) && result.trim().eq_ignore_ascii_case("pass")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_fields(spf: &str, dkim: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    fields.insert("SPF".to_owned(), spf.to_owned());
+    fields.insert("dkim".to_owned(), dkim.to_owned());
+    fields
+  }
+
+  fn create_envelope(from: &str) -> Envelope {
+    Envelope {
+      to: vec![],
+      from: Some(from.to_owned()),
+    }
+  }
+
+  #[test]
+  fn spf_passes_when_aligned_with_the_sender() {
+    let fields = create_fields("pass", "none");
+    let envelope = create_envelope("bounces@Example.com");
+
+    assert!(is_sender_authenticated(
+      &fields,
+      &envelope,
+      Some("jane@example.com")
+    ));
+  }
+
+  #[test]
+  fn unaligned_spf_falls_back_to_dkim() {
+    let fields = create_fields("pass", "none");
+    let envelope = create_envelope("attacker@spammer.com");
+    assert!(!is_sender_authenticated(
+      &fields,
+      &envelope,
+      Some("jane@example.com")
+    ));
+
+    let fields = create_fields("pass", "{@example.com : pass}");
+    assert!(is_sender_authenticated(
+      &fields,
+      &envelope,
+      Some("jane@example.com")
+    ));
+  }
+}
@@ -1,2 +1,3 @@
+pub mod inbound_email;
 pub mod paddle;
 pub mod rss;
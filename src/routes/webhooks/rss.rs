@@ -4,6 +4,9 @@ use wither::bson::doc;
 use wither::bson::oid::ObjectId;
 
 use crate::errors::Error;
+use crate::models::activity::Action;
+use crate::models::activity::Activity;
+use crate::models::activity::Source;
 use crate::models::Model as ModelTrait;
 use crate::models::Models;
 use crate::thirdparty::rss;
@@ -61,22 +64,22 @@ async fn webhook(ctx: web::Data<Context>, body: WebhookBody) -> Response {
     return Ok(HttpResponse::Ok().finish());
   }
 
-  let next_resource_position = ctx.models.list.get_next_resource_position(&list_id).await?;
+  let entries = body
+    .entries
+    .clone()
+    .into_iter()
+    .filter(|entry| entry.url.is_some())
+    .collect::<Vec<RssEntry>>();
 
-  futures::stream::iter(body.entries.clone())
-    .enumerate()
-    .filter(|(_, entry)| {
-      let has_url = entry.url.is_some();
-      async move { has_url }
-    })
-    .map(|(index, entry)| {
-      create_resource_from_rss_entry(
-        &ctx.models,
-        entry,
-        &user_id,
-        &list_id,
-        next_resource_position + (index as i32),
-      )
+  let positions = ctx
+    .models
+    .list
+    .get_next_resource_positions(&list_id, entries.len())
+    .await?;
+
+  futures::stream::iter(entries.into_iter().zip(positions))
+    .map(|(entry, position)| {
+      create_resource_from_rss_entry(&ctx.models, entry, &user_id, &list_id, position)
     })
     .buffered(10)
     .collect::<Vec<Result<(), Error>>>()
@@ -94,11 +97,17 @@ pub async fn create_resource_from_rss_entry(
   entry: RssEntry,
   user_id: &ObjectId,
   list_id: &ObjectId,
-  position: i32,
+  position: String,
 ) -> Result<(), Error> {
   let mut resource = rss::create_resource_payload_from_entry(entry, user_id, list_id).await?;
 
   resource.position = position;
-  models.resource.create(resource).await?;
+  let resource = models.list.create_resource(resource).await?;
+
+  models
+    .activity
+    .record(Activity::new(&resource, Action::Create, Source::Rss))
+    .await;
+
   Ok(())
 }
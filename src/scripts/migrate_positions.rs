@@ -0,0 +1,44 @@
+use futures::stream::StreamExt;
+use wither::bson::doc;
+
+use crate::context::Context;
+use crate::models::Model as ModelTrait;
+
+// Converts the legacy integer resource positions into rank positions. Lists
+// are migrated one at a time keeping their current resource order.
+pub async fn run(ctx: &Context) {
+  println!("Runing migrate-positions script");
+
+  let mut cursor = ctx
+    .models
+    .list
+    .cursor(doc! {}, None)
+    .await
+    .expect("Failed to get model cursor");
+
+  while let Some(result) = cursor.next().await {
+    let list = result.expect("Failed to get list");
+    let list_id = list.id.clone().expect("Failed to get list ID");
+
+    let has_legacy_positions = ctx
+      .models
+      .resource
+      .exists(doc! { "list": &list_id, "position": { "$type": "number" } })
+      .await
+      .expect("Failed to query list resources");
+
+    if !has_legacy_positions {
+      continue;
+    }
+
+    println!("List: {:?}", list.slug);
+    let res = ctx.models.list.rebalance_positions(&list_id).await;
+
+    if let Err(err) = res {
+      println!(
+        "Failed to migrate list positions: {:?}. Error {:?}",
+        list_id, err
+      );
+    }
+  }
+}
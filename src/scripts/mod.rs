@@ -1,4 +1,6 @@
+mod migrate_positions;
 mod populate_resources;
+mod restore_backup;
 
 use clap::{App, Arg};
 
@@ -7,26 +9,53 @@ use crate::context::Context;
 pub async fn run(context: &Context) {
   let matches = App::new("Listas scripts CLI")
     .subcommand(
-      App::new("cli").subcommand(
-        App::new("populate-resources")
-          .help("Populates all resources")
-          .arg(
-            Arg::with_name("user")
-              .short("u")
-              .long("user")
-              .value_name("user")
-              .help("Populate resource for a specific user")
-              .takes_value(true),
-          )
-          .arg(
-            Arg::with_name("non-populated")
-              .short("n")
-              .long("non-populated")
-              .value_name("non-populated")
-              .help("Populate resource that were not previously populated")
-              .takes_value(false),
-          ),
-      ),
+      App::new("cli")
+        .subcommand(
+          App::new("populate-resources")
+            .help("Populates all resources")
+            .arg(
+              Arg::with_name("user")
+                .short("u")
+                .long("user")
+                .value_name("user")
+                .help("Populate resource for a specific user")
+                .takes_value(true),
+            )
+            .arg(
+              Arg::with_name("non-populated")
+                .short("n")
+                .long("non-populated")
+                .value_name("non-populated")
+                .help("Populate resource that were not previously populated")
+                .takes_value(false),
+            ),
+        )
+        .subcommand(
+          App::new("migrate-positions")
+            .help("Converts legacy integer resource positions into rank positions"),
+        )
+        .subcommand(
+          App::new("restore-backup")
+            .help("Restores a backup archive into a user account")
+            .arg(
+              Arg::with_name("user")
+                .short("u")
+                .long("user")
+                .value_name("user")
+                .help("User the backup is restored to")
+                .takes_value(true)
+                .required(true),
+            )
+            .arg(
+              Arg::with_name("file")
+                .short("f")
+                .long("file")
+                .value_name("file")
+                .help("Path of the backup archive")
+                .takes_value(true)
+                .required(true),
+            ),
+        ),
     )
     .get_matches();
 
@@ -41,4 +70,14 @@ pub async fn run(context: &Context) {
     let non_populated = matches.is_present("non-populated");
     populate_resources::run(context, user, non_populated).await;
   }
+
+  if matches.subcommand_matches("migrate-positions").is_some() {
+    migrate_positions::run(context).await;
+  }
+
+  if let Some(matches) = matches.subcommand_matches("restore-backup") {
+    let user = matches.value_of("user").unwrap();
+    let file = matches.value_of("file").unwrap();
+    restore_backup::run(context, user, file).await;
+  }
 }
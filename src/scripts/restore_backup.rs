@@ -0,0 +1,40 @@
+use crate::context::Context;
+use crate::lib::backup;
+use crate::lib::util::to_object_id;
+use crate::models::Model as ModelTrait;
+
+// Restores a backup archive file into the specified user account, used to
+// move accounts between environments.
+pub async fn run(ctx: &Context, user: &str, file: &str) {
+  println!("Running restore-backup script");
+
+  let user_id = to_object_id(user).expect("Failed to parse user ID");
+  let user_exists = ctx
+    .models
+    .user
+    .find_by_id(&user_id)
+    .await
+    .expect("Failed to query user")
+    .is_some();
+
+  if !user_exists {
+    println!("User {} not found", user);
+    return;
+  }
+
+  let data = tokio::fs::read(file)
+    .await
+    .expect("Failed to read backup file");
+  let archive = backup::decode(&data).expect("Failed to decode backup file");
+
+  println!(
+    "Restoring backup version {} of user {}",
+    archive.version, archive.user
+  );
+
+  let summary = backup::restore(&ctx.models, &ctx.rss, &user_id, archive)
+    .await
+    .expect("Failed to restore backup");
+
+  println!("Backup restored: {:?}", summary);
+}
@@ -56,6 +56,54 @@ pub struct Traer {
 pub struct RabbitMQ {
   pub uri: String,
 }
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Health {
+  pub recheck_after_days: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Refresh {
+  // Resources populated before this amount of days are refreshed.
+  pub stale_after_days: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHub {
+  // Repository metadata is not fetched when disabled.
+  pub enabled: bool,
+  pub token: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+  Traer,
+  Native,
+  NativeWithTraerFallback,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Metadata {
+  pub provider: ProviderKind,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Archive {
+  // Blob store used to save the archived resources. Supported: "local".
+  pub backend: String,
+  pub path: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InboundEmail {
+  // Domain of the list inbound addresses, E.g. "<token>@inbound.listas.io".
+  pub domain: String,
+  // Secret path segment of the webhook URL configured in SendGrid, E.g.
+  // "/webhooks/inbound-email/<secret>". Requests are rejected when empty.
+  pub secret: String,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Settings {
   pub environment: String,
@@ -71,6 +119,12 @@ pub struct Settings {
   pub sendgrid: Sendgrid,
   pub traer: Traer,
   pub rabbit_mq: RabbitMQ,
+  pub archive: Archive,
+  pub metadata: Metadata,
+  pub github: GitHub,
+  pub health: Health,
+  pub refresh: Refresh,
+  pub inbound_email: InboundEmail,
 }
 
 impl Settings {
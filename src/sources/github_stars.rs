@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::integration::Integration;
+use crate::sources::SourceItem;
+use crate::sources::SourceProvider;
+use crate::thirdparty::github::GitHubClient;
+
+pub struct GitHubStarsProvider {
+  github: Arc<dyn GitHubClient>,
+}
+
+impl GitHubStarsProvider {
+  pub fn new(github: Arc<dyn GitHubClient>) -> Self {
+    Self { github }
+  }
+}
+
+#[async_trait]
+impl SourceProvider for GitHubStarsProvider {
+  async fn fetch(&self, integration: &Integration) -> Result<Vec<SourceItem>, Error> {
+    let username = match integration.github_stars {
+      Some(ref github_stars) => github_stars.username.as_str(),
+      None => return Ok(vec![]),
+    };
+
+    let repositories = self.github.get_starred_repositories(username).await?;
+    let items = repositories
+      .into_iter()
+      .map(|repository| {
+        let mut tags = vec!["github".to_owned()];
+        tags.extend(repository.language.map(|language| language.to_lowercase()));
+
+        SourceItem {
+          url: repository.html_url,
+          title: Some(repository.full_name),
+          description: repository.description,
+          thumbnail: None,
+          tags,
+        }
+      })
+      .collect();
+
+    Ok(items)
+  }
+}
@@ -0,0 +1,54 @@
+pub mod github_stars;
+pub mod youtube_playlist;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::errors::Error;
+use crate::models::integration::Integration;
+use crate::models::integration::Kind;
+
+// Link fetched from the source of a polled integration.
+#[derive(Debug, Clone, Default)]
+pub struct SourceItem {
+  pub url: String,
+  pub title: Option<String>,
+  pub description: Option<String>,
+  pub thumbnail: Option<String>,
+  pub tags: Vec<String>,
+}
+
+// Fetches the latest items of a polled integration. Items that were already
+// saved are returned again, the caller is responsible for skipping them.
+#[async_trait]
+pub trait SourceProvider: Send + Sync {
+  async fn fetch(&self, integration: &Integration) -> Result<Vec<SourceItem>, Error>;
+}
+
+// Providers by integration kind, they can be replaced by stubs in tests.
+#[derive(Clone)]
+pub struct Sources {
+  github_stars: Arc<dyn SourceProvider>,
+  youtube_playlist: Arc<dyn SourceProvider>,
+}
+
+impl Sources {
+  pub fn new(
+    github_stars: Arc<dyn SourceProvider>,
+    youtube_playlist: Arc<dyn SourceProvider>,
+  ) -> Self {
+    Self {
+      github_stars,
+      youtube_playlist,
+    }
+  }
+
+  // Returns None for the integrations that are not polled.
+  pub fn get(&self, kind: &Kind) -> Option<Arc<dyn SourceProvider>> {
+    match kind {
+      Kind::GitHubStars => Some(self.github_stars.clone()),
+      Kind::YouTubePlaylist => Some(self.youtube_playlist.clone()),
+      Kind::Rss | Kind::ListasSubscription => None,
+    }
+  }
+}
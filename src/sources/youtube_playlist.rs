@@ -0,0 +1,97 @@
+use async_trait::async_trait;
+use roxmltree::Node;
+
+use crate::errors::Error;
+use crate::models::integration::Integration;
+use crate::sources::SourceItem;
+use crate::sources::SourceProvider;
+
+// Public Atom feed of the playlist, it contains the latest 15 videos and does
+// not require an API key.
+const FEED_URL: &str = "https://www.youtube.com/feeds/videos.xml";
+
+pub struct YouTubePlaylistProvider {
+  client: reqwest::Client,
+}
+
+impl YouTubePlaylistProvider {
+  pub fn new() -> Self {
+    Self {
+      client: reqwest::Client::new(),
+    }
+  }
+}
+
+impl Default for YouTubePlaylistProvider {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+#[async_trait]
+impl SourceProvider for YouTubePlaylistProvider {
+  async fn fetch(&self, integration: &Integration) -> Result<Vec<SourceItem>, Error> {
+    let playlist_id = match integration.youtube_playlist {
+      Some(ref youtube_playlist) => youtube_playlist.playlist_id.as_str(),
+      None => return Ok(vec![]),
+    };
+
+    let res = self
+      .client
+      .get(FEED_URL)
+      .query(&[("playlist_id", playlist_id)])
+      .send()
+      .await?;
+
+    // Private and removed playlists have no feed.
+    if !res.status().is_success() {
+      return Ok(vec![]);
+    }
+
+    let feed = res.text().await?;
+    parse(&feed).map_err(|err| Error::IntegrationSource(err.to_string()))
+  }
+}
+
+pub fn parse(feed: &str) -> Result<Vec<SourceItem>, roxmltree::Error> {
+  let document = roxmltree::Document::parse(feed.trim())?;
+
+  let items = document
+    .root_element()
+    .children()
+    .filter(|node| node.has_tag_name("entry"))
+    .filter_map(|entry| {
+      let url = entry
+        .children()
+        .find(|node| node.has_tag_name("link") && node.attribute("rel") == Some("alternate"))
+        .and_then(|link| link.attribute("href"))?;
+
+      let media = entry.children().find(|node| node.has_tag_name("group"));
+      let description = media
+        .and_then(|media| get_child_text(&media, "description"))
+        .filter(|description| !description.is_empty());
+      let thumbnail = media
+        .and_then(|media| media.children().find(|node| node.has_tag_name("thumbnail")))
+        .and_then(|thumbnail| thumbnail.attribute("url"))
+        .map(ToOwned::to_owned);
+
+      Some(SourceItem {
+        url: url.to_owned(),
+        title: get_child_text(&entry, "title"),
+        description,
+        thumbnail,
+        tags: vec!["youtube".to_owned()],
+      })
+    })
+    .collect();
+
+  Ok(items)
+}
+
+fn get_child_text(node: &Node, name: &str) -> Option<String> {
+  node
+    .children()
+    .find(|child| child.has_tag_name(name))
+    .and_then(|child| child.text())
+    .map(|text| text.trim().to_owned())
+}
@@ -0,0 +1,123 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::errors::Error;
+
+// Maximum page size allowed by the GitHub API.
+const STARRED_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repository {
+  pub full_name: String,
+  pub description: Option<String>,
+  pub stargazers_count: i64,
+  pub language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarredRepository {
+  pub full_name: String,
+  pub html_url: String,
+  pub description: Option<String>,
+  pub language: Option<String>,
+}
+
+// Abstracts the GitHub API so it can be replaced by a stub when the API is
+// not available.
+#[async_trait]
+pub trait GitHubClient: Send + Sync {
+  async fn get_repository(&self, owner: &str, name: &str) -> Result<Option<Repository>, Error>;
+
+  // Returns the latest repositories starred by the user, most recent first.
+  async fn get_starred_repositories(&self, username: &str)
+    -> Result<Vec<StarredRepository>, Error>;
+}
+
+#[derive(Clone)]
+pub struct GitHub {
+  inner: Arc<GitHubInner>,
+}
+
+struct GitHubInner {
+  // Requests are unauthenticated when the token is empty, which are subject
+  // to a lower rate limit.
+  token: String,
+  base_url: String,
+  client: reqwest::Client,
+}
+
+impl GitHub {
+  pub fn new(token: String) -> Self {
+    let inner = Arc::new(GitHubInner {
+      token,
+      base_url: "https://api.github.com".to_string(),
+      client: reqwest::Client::new(),
+    });
+
+    Self { inner }
+  }
+
+  fn get(&self, url: &str) -> reqwest::RequestBuilder {
+    let request = self
+      .inner
+      .client
+      .get(url)
+      .header("User-Agent", "listas")
+      .header("Accept", "application/vnd.github.v3+json");
+
+    match self.inner.token.is_empty() {
+      true => request,
+      false => request.header("Authorization", format!("token {}", self.inner.token)),
+    }
+  }
+}
+
+#[async_trait]
+impl GitHubClient for GitHub {
+  async fn get_repository(&self, owner: &str, name: &str) -> Result<Option<Repository>, Error> {
+    let url = format!("{}/repos/{}/{}", self.inner.base_url, owner, name);
+    let res = self.get(url.as_str()).send().await?;
+    if !res.status().is_success() {
+      return Ok(None);
+    }
+
+    let repository = res.json::<Repository>().await?;
+    Ok(Some(repository))
+  }
+
+  async fn get_starred_repositories(
+    &self,
+    username: &str,
+  ) -> Result<Vec<StarredRepository>, Error> {
+    let url = format!(
+      "{}/users/{}/starred?per_page={}",
+      self.inner.base_url, username, STARRED_PAGE_SIZE
+    );
+    let res = self.get(url.as_str()).send().await?;
+    if !res.status().is_success() {
+      return Ok(vec![]);
+    }
+
+    let repositories = res.json::<Vec<StarredRepository>>().await?;
+    Ok(repositories)
+  }
+}
+
+// Client used when the GitHub integration is disabled, repositories are
+// detected but no metadata is returned.
+pub struct StubGitHub;
+
+#[async_trait]
+impl GitHubClient for StubGitHub {
+  async fn get_repository(&self, _owner: &str, _name: &str) -> Result<Option<Repository>, Error> {
+    Ok(None)
+  }
+
+  async fn get_starred_repositories(
+    &self,
+    _username: &str,
+  ) -> Result<Vec<StarredRepository>, Error> {
+    Ok(vec![])
+  }
+}
@@ -6,6 +6,7 @@ use wither::bson::oid::ObjectId;
 
 use crate::errors::Error;
 use crate::lib::date;
+use crate::lib::rank;
 use crate::lib::util::parse_url;
 use crate::models::resource::Resource;
 
@@ -152,7 +153,7 @@ pub async fn create_resource_payload_from_entry(
     list: list.clone(),
     // The position will be computed before inserting the resource into the
     // database.
-    position: 0,
+    position: rank::initial(),
     tags: vec!["rss".to_owned()],
     url: url.to_string(),
     title: entry.title.clone(),
@@ -167,6 +168,21 @@ pub async fn create_resource_payload_from_entry(
     length: None,
     publisher: None,
     populated_at: None,
+    refresh_attempted_at: None,
+    signature_indexed_at: None,
+    annotations_text: None,
+    estimated_reading_time: None,
+    progress: None,
+    snoozed_until: None,
+    review: None,
+    archived_at: None,
+    favicon: None,
+    kind: None,
+    media: None,
+    health: None,
+    last_checked_at: None,
+    edited_fields: Some(vec![]),
+    suggested_tags: None,
   };
 
   Ok(resource)